@@ -0,0 +1,18 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title SoundBreak Meeting
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon 🎤
+# @raycast.packageName SoundBreak
+# @raycast.argument1 { "type": "dropdown", "placeholder": "Action", "data": [{ "title": "Start", "value": "start" }, { "title": "End", "value": "end" }, { "title": "Skip", "value": "skip" }] }
+
+# Documentation:
+# @raycast.description Turn the manual meeting override on or off, or skip the current meeting
+
+# Set SOUNDBREAK_BIN if SoundBreak isn't installed in /Applications
+soundbreak="${SOUNDBREAK_BIN:-/Applications/SoundBreak.app/Contents/MacOS/sound-break}"
+exec "$soundbreak" meeting "$1"
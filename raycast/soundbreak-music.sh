@@ -0,0 +1,18 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title SoundBreak Music
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon 🎶
+# @raycast.packageName SoundBreak
+# @raycast.argument1 { "type": "dropdown", "placeholder": "Action", "data": [{ "title": "Pause", "value": "pause" }, { "title": "Resume", "value": "resume" }] }
+
+# Documentation:
+# @raycast.description Pause or resume music right away
+
+# Set SOUNDBREAK_BIN if SoundBreak isn't installed in /Applications
+soundbreak="${SOUNDBREAK_BIN:-/Applications/SoundBreak.app/Contents/MacOS/sound-break}"
+exec "$soundbreak" music "$1"
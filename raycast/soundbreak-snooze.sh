@@ -0,0 +1,18 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Snooze SoundBreak
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon 💤
+# @raycast.packageName SoundBreak
+# @raycast.argument1 { "type": "text", "placeholder": "Minutes", "optional": true, "percentEncoded": false }
+
+# Documentation:
+# @raycast.description Suspend automatic actions for a number of minutes, 30 if left empty
+
+# Set SOUNDBREAK_BIN if SoundBreak isn't installed in /Applications
+soundbreak="${SOUNDBREAK_BIN:-/Applications/SoundBreak.app/Contents/MacOS/sound-break}"
+exec "$soundbreak" snooze ${1:+"$1"}
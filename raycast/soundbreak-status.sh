@@ -0,0 +1,18 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title SoundBreak Status
+# @raycast.mode inline
+
+# Optional parameters:
+# @raycast.icon 🎵
+# @raycast.packageName SoundBreak
+# @raycast.refreshTime 1m
+
+# Documentation:
+# @raycast.description Show whether SoundBreak is monitoring, the current meeting and music
+
+# Set SOUNDBREAK_BIN if SoundBreak isn't installed in /Applications
+soundbreak="${SOUNDBREAK_BIN:-/Applications/SoundBreak.app/Contents/MacOS/sound-break}"
+exec "$soundbreak" summary
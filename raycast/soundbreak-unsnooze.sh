@@ -0,0 +1,17 @@
+#!/bin/bash
+
+# Required parameters:
+# @raycast.schemaVersion 1
+# @raycast.title Unsnooze SoundBreak
+# @raycast.mode compact
+
+# Optional parameters:
+# @raycast.icon ⏰
+# @raycast.packageName SoundBreak
+
+# Documentation:
+# @raycast.description End a snooze early
+
+# Set SOUNDBREAK_BIN if SoundBreak isn't installed in /Applications
+soundbreak="${SOUNDBREAK_BIN:-/Applications/SoundBreak.app/Contents/MacOS/sound-break}"
+exec "$soundbreak" unsnooze
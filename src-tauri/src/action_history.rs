@@ -0,0 +1,185 @@
+use crate::config::ConfigManager;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Oldest records are dropped beyond this many entries
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Pause,
+    Resume,
+    Duck,
+    RestoreVolume,
+    Mute,
+    Unmute,
+    /// Delivering an outgoing webhook
+    Webhook,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub timestamp: u64,
+    pub kind: ActionKind,
+    /// Meeting app that triggered the action
+    pub app: Option<String>,
+    pub success: bool,
+    pub message: String,
+}
+
+/// What the most recent action shown in the monitoring status was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LastActionKind {
+    MonitoringStarted,
+    MonitoringStopped,
+    Snoozed,
+    SnoozeCancelled,
+    PauseCancelled,
+    ManualMeeting,
+    Pause,
+    Resume,
+    Duck,
+    RestoreVolume,
+    Mute,
+    Unmute,
+    FocusOn,
+    FocusOff,
+    OnAirLight,
+    OnAirLightOff,
+    Webhook,
+    NotifyPeers,
+    RunHook,
+}
+
+impl From<ActionKind> for LastActionKind {
+    fn from(kind: ActionKind) -> Self {
+        match kind {
+            ActionKind::Pause => LastActionKind::Pause,
+            ActionKind::Resume => LastActionKind::Resume,
+            ActionKind::Duck => LastActionKind::Duck,
+            ActionKind::RestoreVolume => LastActionKind::RestoreVolume,
+            ActionKind::Mute => LastActionKind::Mute,
+            ActionKind::Unmute => LastActionKind::Unmute,
+            ActionKind::Webhook => LastActionKind::Webhook,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastAction {
+    pub timestamp: u64,
+    pub kind: LastActionKind,
+    /// What caused the action, e.g. "Meeting started"
+    pub trigger: Option<String>,
+    /// Meeting app that triggered the action
+    pub app: Option<String>,
+    pub success: bool,
+    pub message: String,
+    /// Only logged, not performed
+    pub dry_run: bool,
+}
+
+impl LastAction {
+    /// A successful action taken directly by the user
+    pub fn new(kind: LastActionKind, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            kind,
+            trigger: None,
+            app: None,
+            success: true,
+            message: message.into(),
+            dry_run: false,
+        }
+    }
+}
+
+/// Bounded log of automatic actions, persisted as `history.json` in the app directory.
+#[derive(Clone)]
+pub struct ActionHistory {
+    records: Arc<Mutex<VecDeque<ActionRecord>>>,
+    // Never written to disk
+    in_memory: bool,
+}
+
+impl ActionHistory {
+    pub fn load() -> Self {
+        let records = Self::get_history_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<VecDeque<ActionRecord>>(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            records: Arc::new(Mutex::new(records)),
+            in_memory: false,
+        }
+    }
+
+    /// An empty history kept for the lifetime of the process only
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            records: Arc::default(),
+            in_memory: true,
+        }
+    }
+
+    pub fn record(&self, kind: ActionKind, app: Option<String>, result: &Result<String, String>) {
+        let record = ActionRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            kind,
+            app,
+            success: result.is_ok(),
+            message: match result {
+                Ok(message) | Err(message) => message.clone(),
+            },
+        };
+
+        let snapshot = {
+            let mut records = self.records.lock_or_recover();
+            records.push_back(record);
+            while records.len() > MAX_HISTORY_ENTRIES {
+                records.pop_front();
+            }
+            records.clone()
+        };
+
+        if self.in_memory {
+            return;
+        }
+        if let Err(e) = Self::save(&snapshot) {
+            eprintln!("SoundBreak: Failed to save action history: {}", e);
+        }
+    }
+
+    /// Returns the most recent records, newest first
+    pub fn recent(&self, limit: usize) -> Vec<ActionRecord> {
+        let records = self.records.lock_or_recover();
+        records.iter().rev().take(limit).cloned().collect()
+    }
+
+    fn get_history_path() -> Result<PathBuf, String> {
+        Ok(ConfigManager::get_app_dir()?.join("history.json"))
+    }
+
+    fn save(records: &VecDeque<ActionRecord>) -> Result<(), String> {
+        let history_path = Self::get_history_path()?;
+        let json_content = serde_json::to_string_pretty(records)
+            .map_err(|e| format!("Failed to serialize action history: {}", e))?;
+        std::fs::write(&history_path, json_content)
+            .map_err(|e| format!("Failed to write action history: {}", e))
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// A step run when a meeting starts or ends.
+///
+/// Each transition runs its configured actions in order; a failing action does not
+/// stop the ones after it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransitionAction {
+    /// Pause (or duck, for short meetings) music that is playing
+    PauseMusic,
+    /// Undo what `PauseMusic` did: resume playback or restore the ducked volume
+    ResumeMusic,
+    /// Mute system output if it isn't muted already
+    MuteSystem,
+    /// Unmute system output if `MuteSystem` muted it
+    UnmuteSystem,
+    /// Turn on the Focus for meetings if no Focus is on already
+    EnableFocus,
+    /// Turn the Focus off again if `EnableFocus` turned it on
+    RestoreFocus,
+    /// Turn the configured Hue lights red
+    HueOnAir,
+    /// Put the lights turned red by `HueOnAir` back the way they were
+    HueRestore,
+    /// Forward the transition to LAN peers
+    NotifyPeers,
+    /// Run the hook script configured for this transition, if any
+    RunHook,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionResult {
+    pub action: TransitionAction,
+    pub success: bool,
+    pub message: String,
+}
+
+impl ActionResult {
+    pub fn new(action: TransitionAction, result: Result<String, String>) -> Self {
+        match result {
+            Ok(message) => Self {
+                action,
+                success: true,
+                message,
+            },
+            Err(message) => Self {
+                action,
+                success: false,
+                message,
+            },
+        }
+    }
+}
+
+pub fn default_meeting_start_actions() -> Vec<TransitionAction> {
+    vec![
+        TransitionAction::PauseMusic,
+        TransitionAction::NotifyPeers,
+        TransitionAction::RunHook,
+    ]
+}
+
+pub fn default_meeting_end_actions() -> Vec<TransitionAction> {
+    vec![
+        TransitionAction::ResumeMusic,
+        TransitionAction::NotifyPeers,
+        TransitionAction::RunHook,
+    ]
+}
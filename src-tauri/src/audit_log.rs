@@ -0,0 +1,107 @@
+use crate::action_history::LastAction;
+use crate::actions::ActionResult;
+use crate::config::ConfigManager;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// The log is rotated once it grows past this size
+const MAX_AUDIT_LOG_BYTES: u64 = 1024 * 1024;
+
+// Rotated logs kept next to the current one, as `audit.1.jsonl` (newest) and up
+const ROTATED_AUDIT_LOGS: usize = 2;
+
+/// One line of the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A meeting started or ended, the screen was locked or unlocked, or the user
+    /// went away or came back, with the outcome of each action that ran for it
+    Transition {
+        timestamp: u64,
+        transition: String,
+        app: Option<String>,
+        results: Vec<ActionResult>,
+    },
+    /// Anything shown as the monitoring status' last action
+    Action(LastAction),
+}
+
+/// Append-only record of transitions and actions, written as JSON lines to
+/// `audit.jsonl` in the app directory, for working out after the fact why music
+/// was paused or resumed.
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    // Serializes appends and rotation
+    write_lock: Arc<Mutex<()>>,
+    // Never written to disk
+    in_memory: bool,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A log that drops every event, for tests
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            in_memory: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn record(&self, event: &AuditEvent) {
+        if self.in_memory {
+            return;
+        }
+        let _guard = self.write_lock.lock_or_recover();
+        if let Err(e) = Self::append(event) {
+            eprintln!("SoundBreak: Failed to write audit log: {}", e);
+        }
+    }
+
+    fn append(event: &AuditEvent) -> Result<(), String> {
+        let app_dir = ConfigManager::get_app_dir()?;
+        let log_path = Self::log_path(&app_dir, 0);
+        if std::fs::metadata(&log_path).is_ok_and(|metadata| metadata.len() >= MAX_AUDIT_LOG_BYTES) {
+            Self::rotate(&app_dir)?;
+        }
+
+        let mut line = serde_json::to_string(event)
+            .map_err(|e| format!("Failed to serialize audit event: {}", e))?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .map_err(|e| format!("Failed to open audit log: {}", e))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to append to audit log: {}", e))
+    }
+
+    // Shifts each log one place back, dropping the oldest
+    fn rotate(app_dir: &Path) -> Result<(), String> {
+        for index in (0..ROTATED_AUDIT_LOGS).rev() {
+            let from = Self::log_path(app_dir, index);
+            if from.exists() {
+                std::fs::rename(&from, Self::log_path(app_dir, index + 1))
+                    .map_err(|e| format!("Failed to rotate audit log: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn log_path(app_dir: &Path, index: usize) -> PathBuf {
+        if index == 0 {
+            app_dir.join("audit.jsonl")
+        } else {
+            app_dir.join(format!("audit.{}.jsonl", index))
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! USB busylights
+//!
+//! Turns Luxafor, Kuando Busylight and MuteMe devices on the desk red while in a
+//! meeting, by writing HID output reports through IOKit. Every connected light shows the
+//! same color.
+//! Kuando lights go dark on their own when they aren't sent anything for a while, so
+//! the current color is sent again periodically while one is shown.
+
+#[cfg(target_os = "macos")]
+use crate::hid;
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, Once};
+use std::time::Duration;
+
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const OFF: [u8; 3] = [0, 0, 0];
+
+// Color last sent to the lights. Also serializes writes between threads.
+static SHOWN: Mutex<[u8; 3]> = Mutex::new(OFF);
+static KEEPALIVE: Once = Once::new();
+
+// Only the macOS HID code picks out devices
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Model {
+    Luxafor,
+    Kuando,
+    MuteMe,
+}
+
+// (vendor id, product id, model) of supported lights
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const DEVICES: &[(i32, i32, Model)] = &[
+    (0x04D8, 0xF372, Model::Luxafor),
+    (0x04D8, 0xF848, Model::Kuando),
+    (0x27BB, 0x3BCA, Model::Kuando),
+    (0x27BB, 0x3BCB, Model::Kuando),
+    (0x27BB, 0x3BCC, Model::Kuando),
+    (0x27BB, 0x3BCD, Model::Kuando),
+    (0x27BB, 0x3BCE, Model::Kuando),
+    (0x27BB, 0x3BCF, Model::Kuando),
+    (0x16C0, 0x27DB, Model::MuteMe),
+    (0x20A0, 0x42DA, Model::MuteMe),
+    (0x20A0, 0x42DB, Model::MuteMe),
+    (0x3603, 0x0001, Model::MuteMe),
+    (0x3603, 0x0002, Model::MuteMe),
+    (0x3603, 0x0003, Model::MuteMe),
+    (0x3603, 0x0004, Model::MuteMe),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BusylightConfig {
+    pub enabled: bool,
+    /// Color while in a meeting, as "#RRGGBB"
+    pub meeting_color: String,
+    /// Color outside meetings, as "#RRGGBB"; empty turns the light off
+    pub idle_color: String,
+}
+
+impl Default for BusylightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            meeting_color: "#FF0000".to_string(),
+            idle_color: String::new(),
+        }
+    }
+}
+
+/// Sets the lights to the color for the meeting state if it changed, on a background thread
+pub fn publish(config: &BusylightConfig, status: &MonitoringStatus) {
+    if !config.enabled {
+        return;
+    }
+
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let color = if in_meeting { &config.meeting_color } else { &config.idle_color };
+    let color = match parse_color(color) {
+        Ok(color) => color,
+        Err(e) => {
+            eprintln!("SoundBreak: Invalid busylight color: {}", e);
+            return;
+        }
+    };
+
+    KEEPALIVE.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(KEEPALIVE_INTERVAL);
+            let shown = SHOWN.lock_or_recover();
+            if *shown != OFF {
+                // Errors were logged when the color was set
+                let _ = write_reports(|model| report(model, *shown));
+            }
+        });
+    });
+    std::thread::spawn(move || {
+        let mut shown = SHOWN.lock_or_recover();
+        if *shown == color {
+            return;
+        }
+        match write_reports(|model| report(model, color)) {
+            Ok(0) => eprintln!("SoundBreak: No busylight connected"),
+            Ok(_) => {}
+            Err(e) => eprintln!("SoundBreak: Failed to set busylight color: {}", e),
+        }
+        // Remembered even when no light is connected, so the error isn't logged on
+        // every status change
+        *shown = color;
+    });
+}
+
+/// Turns the lights off if a color is shown, e.g. before quitting
+pub fn turn_off() {
+    let mut shown = SHOWN.lock_or_recover();
+    if *shown == OFF {
+        return;
+    }
+    if let Err(e) = write_reports(|model| report(model, OFF)) {
+        eprintln!("SoundBreak: Failed to turn off busylight: {}", e);
+    }
+    *shown = OFF;
+}
+
+// Parses "#RRGGBB"; an empty color is off
+fn parse_color(color: &str) -> Result<[u8; 3], String> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.is_empty() {
+        return Ok(OFF);
+    }
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|channel| u8::from_str_radix(channel, 16).ok());
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(red), Some(green), Some(blue)) => Ok([red, green, blue]),
+        _ => Err(format!("{:?} isn't a #RRGGBB color", color)),
+    }
+}
+
+// The output report that sets a light to `color`
+fn report(model: Model, [red, green, blue]: [u8; 3]) -> Vec<u8> {
+    match model {
+        // Static color on all LEDs
+        Model::Luxafor => vec![0x01, 0xFF, red, green, blue, 0, 0, 0],
+        // Seven 8-byte steps, of which the first keeps jumping to itself with the color
+        // steady on, then a footer and a checksum of everything before it. Channels go
+        // from 0 to 100.
+        Model::Kuando => {
+            let scale = |channel: u8| (channel as u16 * 100 / 255) as u8;
+            let mut report = vec![0u8; 64];
+            report[..8].copy_from_slice(&[0x10, 0, scale(red), scale(green), scale(blue), 0, 0, 0]);
+            report[59..62].copy_from_slice(&[0xFF, 0xFF, 0xFF]);
+            let checksum = report[..62].iter().map(|&byte| byte as u16).sum::<u16>();
+            report[62..].copy_from_slice(&checksum.to_be_bytes());
+            report
+        }
+        // One bit per channel, so only the eight colors with channels fully on or off
+        Model::MuteMe => {
+            let bit = |channel: u8, mask: u8| if channel >= 0x80 { mask } else { 0 };
+            vec![bit(red, 0x01) | bit(green, 0x02) | bit(blue, 0x04)]
+        }
+    }
+}
+
+/// (vendor id, product id) of MuteMe buttons, which are lights and buttons in one
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn muteme_ids() -> Vec<(i32, i32)> {
+    DEVICES
+        .iter()
+        .filter(|(_, _, model)| *model == Model::MuteMe)
+        .map(|(vendor, product, _)| (*vendor, *product))
+        .collect()
+}
+
+// Writes the report for its model to every connected light, returning how many lights
+// were written to
+#[cfg(target_os = "macos")]
+fn write_reports(report: impl Fn(Model) -> Vec<u8>) -> Result<usize, String> {
+    let mut written = 0;
+    let mut errors = Vec::new();
+    hid::for_each_device(|device| {
+        let ids = device.vendor_id().zip(device.product_id());
+        let Some(model) = DEVICES
+            .iter()
+            .find(|(vendor, product, _)| ids == Some((*vendor, *product)))
+            .map(|(_, _, model)| *model)
+        else {
+            return;
+        };
+        match device.write_report(&report(model)) {
+            Ok(()) => written += 1,
+            Err(e) => errors.push(format!("{:?} light: {}", model, e)),
+        }
+    })?;
+
+    if written == 0 && !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(written)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn write_reports(_report: impl Fn(Model) -> Vec<u8>) -> Result<usize, String> {
+    Err("USB busylights are only supported on macOS".to_string())
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::Duration;
+
+/// Settings for adding detected meetings that weren't on the calendar to it afterwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarLogConfig {
+    pub enabled: bool,
+    /// Title of the calendar to add events to; empty for the default calendar
+    pub calendar: String,
+    /// Shorter meetings aren't logged
+    pub min_minutes: u64,
+}
+
+impl Default for CalendarLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            calendar: String::new(),
+            min_minutes: 2,
+        }
+    }
+}
+
+/// Looks up the calendar event that is currently in progress via EventKit.
+///
+/// Only used to estimate how long a detected meeting is expected to last, so any
+/// failure (no permission, no events) is reported as "unknown" rather than an error.
+pub struct CalendarLookup;
+
+impl CalendarLookup {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn current_event_duration(&self) -> Option<Duration> {
+        // All-day events are skipped since they say nothing about meeting length
+        let script = r#"
+            use framework "Foundation"
+            use framework "EventKit"
+            try
+                set eventStore to current application's EKEventStore's alloc()'s init()
+                set nowDate to current application's NSDate's |date|()
+                set rangeStart to nowDate's dateByAddingTimeInterval:-86400
+                set rangeEnd to nowDate's dateByAddingTimeInterval:86400
+                set predicate to eventStore's predicateForEventsWithStartDate:rangeStart endDate:rangeEnd calendars:(missing value)
+                set theEvents to eventStore's eventsMatchingPredicate:predicate
+
+                repeat with anEvent in theEvents
+                    if (anEvent's isAllDay()) as boolean is false then
+                        set startDate to anEvent's startDate()
+                        set endDate to anEvent's endDate()
+                        set sinceStart to (nowDate's timeIntervalSinceDate:startDate) as real
+                        set untilEnd to (endDate's timeIntervalSinceDate:nowDate) as real
+                        if sinceStart is greater than or equal to 0 and untilEnd is greater than 0 then
+                            return ((endDate's timeIntervalSinceDate:startDate) as integer) as text
+                        end if
+                    end if
+                end repeat
+                return "none"
+            on error
+                return "none"
+            end try
+        "#;
+
+        let output = Command::new("osascript").arg("-e").arg(script).output().ok()?;
+        let result_str = String::from_utf8_lossy(&output.stdout);
+        result_str
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(Duration::from_secs)
+    }
+}
+
+/// Adds an event for a meeting that ran from `started_at` to `ended_at` (Unix seconds),
+/// unless a timed event already overlaps it, i.e. the meeting was scheduled.
+/// Returns what was done.
+pub fn log_meeting(config: &CalendarLogConfig, title: &str, started_at: u64, ended_at: u64) -> Result<String, String> {
+    // The values are passed as arguments so they need no quoting
+    let script = r#"
+        use framework "Foundation"
+        use framework "EventKit"
+        on run argv
+            set {theTitle, startSecs, endSecs, calendarTitle} to argv
+            set eventStore to current application's EKEventStore's alloc()'s init()
+            set startDate to current application's NSDate's dateWithTimeIntervalSince1970:(startSecs as real)
+            set endDate to current application's NSDate's dateWithTimeIntervalSince1970:(endSecs as real)
+
+            set predicate to eventStore's predicateForEventsWithStartDate:startDate endDate:endDate calendars:(missing value)
+            repeat with anEvent in (eventStore's eventsMatchingPredicate:predicate)
+                if (anEvent's isAllDay()) as boolean is false then return "scheduled"
+            end repeat
+
+            set theCalendar to eventStore's defaultCalendarForNewEvents()
+            if calendarTitle is not "" then
+                set theCalendar to missing value
+                repeat with aCalendar in (eventStore's calendarsForEntityType:0)
+                    if (aCalendar's title()) as text is calendarTitle then set theCalendar to aCalendar
+                end repeat
+            end if
+            if theCalendar is missing value then error "Calendar not found"
+
+            set newEvent to current application's EKEvent's eventWithEventStore:eventStore
+            newEvent's setTitle:theTitle
+            newEvent's setStartDate:startDate
+            newEvent's setEndDate:endDate
+            newEvent's setCalendar:theCalendar
+            newEvent's setNotes:"Logged by SoundBreak"
+            set {saved, theError} to eventStore's saveEvent:newEvent span:0 |error|:(reference)
+            if saved as boolean is false then error (theError's localizedDescription() as text)
+            return "logged"
+        end run
+    "#;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .args([title, &started_at.to_string(), &ended_at.to_string(), config.calendar.trim()])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to add the meeting to the calendar: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "scheduled" => Ok(format!("{} was on the calendar already", title)),
+        _ => Ok(format!("Added {} to the calendar", title)),
+    }
+}
+
+impl Default for CalendarLookup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
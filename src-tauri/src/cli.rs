@@ -0,0 +1,74 @@
+//! Command-line options
+//!
+//! Lets launch agents and scripts customize a run:
+//!
+//! - `--config <path>`: read and save settings at `path` instead of the app directory
+//! - `--config-dir <dir>`: same, for `config.json` in `dir`
+//! - `--profile <name>`: use a profile for this run without saving it, e.g. `aggressive`
+//! - `--no-autostart-monitoring`: don't start monitoring on launch
+//! - `--dry-run`: only log the actions that would run
+//! - `--minimized`: keep the window hidden on launch, even in development builds
+//! - `--headless`: run monitoring and the tray menu without opening any window
+//! - `soundbreak://...`: run a deep link action once launched
+//! - `<command> [args]`: send a command to the running instance, see `control`
+
+use crate::config::ConfigManager;
+use crate::deep_link;
+use crate::profiles::Profile;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default)]
+pub struct CliOptions {
+    pub config_path: Option<PathBuf>,
+    pub profile: Option<Profile>,
+    pub no_autostart_monitoring: bool,
+    pub dry_run: bool,
+    pub minimized: bool,
+    pub headless: bool,
+    pub deep_links: Vec<String>,
+    /// Command for the running instance; empty to launch the app
+    pub command: Vec<String>,
+}
+
+impl CliOptions {
+    /// Parses the process arguments; problems are reported and the argument skipped,
+    /// since there is no terminal to show a usage error in when launched as an app
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => match args.next() {
+                    Some(path) => options.config_path = Some(PathBuf::from(path)),
+                    None => eprintln!("SoundBreak: --config needs a path"),
+                },
+                "--config-dir" => match args.next() {
+                    Some(dir) => options.config_path = Some(PathBuf::from(dir).join(ConfigManager::CONFIG_FILE_NAME)),
+                    None => eprintln!("SoundBreak: --config-dir needs a directory"),
+                },
+                "--profile" => match args.next().as_deref().map(|id| (id, Profile::from_id(id))) {
+                    Some((_, Some(profile))) => options.profile = Some(profile),
+                    Some((id, None)) => eprintln!("SoundBreak: Unknown profile {:?}", id),
+                    None => eprintln!("SoundBreak: --profile needs a profile name"),
+                },
+                "--no-autostart-monitoring" => options.no_autostart_monitoring = true,
+                "--dry-run" => options.dry_run = true,
+                "--minimized" => options.minimized = true,
+                "--headless" => options.headless = true,
+                _ if deep_link::is_deep_link(&arg) => options.deep_links.push(arg),
+                // macOS passes a process serial number to apps started from Finder
+                _ if arg.starts_with("-psn_") => {}
+                _ if !arg.starts_with('-') => {
+                    options.command.push(arg);
+                    options.command.extend(args.by_ref());
+                }
+                _ => eprintln!("SoundBreak: Ignoring unknown argument {:?}", arg),
+            }
+        }
+        options
+    }
+}
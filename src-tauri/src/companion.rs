@@ -0,0 +1,98 @@
+//! Phone companion
+//!
+//! Serves a small web app on the local network for checking the status and snoozing
+//! or overriding meetings from a phone. It is the local HTTP API on all interfaces, with
+//! every request but the page itself needing the paired token as a bearer token.
+//!
+//! The pairing URL doesn't carry the token but a one-time code that expires after a few
+//! minutes, which the page trades for the token and keeps. A URL left in a browser
+//! history or a screenshot is then useless. Traffic is plain HTTP, so the token can still
+//! be seen by anyone watching the network; the companion is meant for trusted networks.
+
+use crate::peer::{random_hex, tokens_match};
+use crate::sync::MutexExt;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Url};
+
+/// The companion web app, answered for `GET /`
+pub const PAGE: &str = include_str!("companion.html");
+
+const TOKEN_BYTES: usize = 16;
+const PAIRING_CODE_LIFETIME: Duration = Duration::from_secs(10 * 60);
+
+// The pairing code last handed out and when it stops working
+static PAIRING_CODE: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompanionConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Shared with paired phones. Kept in the keychain; only read from `config.json` when
+    /// set there by hand. `sound-break pair` creates one when empty.
+    pub token: String,
+}
+
+impl Default for CompanionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47822,
+            token: String::new(),
+        }
+    }
+}
+
+/// The URL to open on a phone to pair it, with a new pairing code. Creates and saves a
+/// token first if there is none yet.
+pub fn pair(app: &AppHandle) -> Result<String, String> {
+    let service = &app.state::<AppState>().monitoring_service;
+    let mut app_config = service.get_app_config();
+    if !app_config.companion.enabled {
+        return Err("Set \"companion\": { \"enabled\": true } in config.json and restart SoundBreak first".to_string());
+    }
+    if app_config.companion.token.is_empty() {
+        app_config.companion.token = new_token()?;
+        service.update_app_config(app_config.clone())?;
+        println!("SoundBreak: Created a new companion token");
+    }
+
+    let mut url = Url::parse(&format!("http://{}:{}/", host_name()?, app_config.companion.port))
+        .map_err(|e| format!("Invalid companion URL: {}", e))?;
+    let code = new_token()?;
+    url.set_fragment(Some(&format!("pair={}", code)));
+    *PAIRING_CODE.lock_or_recover() = Some((code, Instant::now() + PAIRING_CODE_LIFETIME));
+    Ok(url.to_string())
+}
+
+/// Trades the pairing code from `pair` for the token; each code works once
+pub fn redeem_pairing_code(app: &AppHandle, code: &str) -> Result<String, String> {
+    let mut pairing_code = PAIRING_CODE.lock_or_recover();
+    match pairing_code.as_ref() {
+        Some((expected, expires_at)) if tokens_match(code, expected) && Instant::now() < *expires_at => {
+            *pairing_code = None;
+            Ok(app.state::<AppState>().monitoring_service.get_app_config().companion.token)
+        }
+        _ => Err("Invalid or expired pairing code; run sound-break pair again".to_string()),
+    }
+}
+
+fn new_token() -> Result<String, String> {
+    random_hex(TOKEN_BYTES).map_err(|e| format!("Failed to create a token: {}", e))
+}
+
+// The Bonjour name phones on the same network can reach this Mac at, e.g. "studio.local"
+fn host_name() -> Result<String, String> {
+    let output = std::process::Command::new("scutil")
+        .args(["--get", "LocalHostName"])
+        .output()
+        .map_err(|e| format!("Failed to run scutil: {}", e))?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || name.is_empty() {
+        return Err("Failed to read the local host name".to_string());
+    }
+    Ok(format!("{}.local", name))
+}
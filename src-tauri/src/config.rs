@@ -1,24 +1,742 @@
+use crate::actions::{default_meeting_end_actions, default_meeting_start_actions, TransitionAction};
+use crate::busylight::BusylightConfig;
+use crate::calendar::CalendarLogConfig;
+use crate::companion::CompanionConfig;
+use crate::discord::DiscordConfig;
+use crate::focus::FocusConfig;
+use crate::google_chat::GoogleChatConfig;
+use crate::home_assistant::HomeAssistantConfig;
+use crate::hooks::{self, HookConfig};
+use crate::hue::HueConfig;
+use crate::i18n::Language;
+use crate::journal::JournalConfig;
+use crate::keyboard_led::KeyboardLedConfig;
 use crate::meeting_detector::MeetingConfig;
+use crate::mqtt::MqttConfig;
+use crate::mute_button::MuteButtonConfig;
+use crate::osc::OscConfig;
+use crate::music_controller::MusicStatus;
+use crate::notifications::NotificationConfig;
+use crate::profiles::Profile;
+use crate::schedule::ScheduleConfig;
+use crate::secrets::{
+    default_secrets_store, SecretsStore, COMPANION_TOKEN, GOOGLE_CHAT_REFRESH_TOKEN, HOME_ASSISTANT_TOKEN, IFTTT_KEY, MQTT_PASSWORD, PEER_TOKEN,
+    TEAMS_REFRESH_TOKEN,
+};
+use crate::sync::MutexExt;
+use crate::teams::TeamsConfig;
+use crate::webhooks::{IftttConfig, Webhook, ZapierConfig};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+pub const DEFAULT_CHECK_INTERVAL_MS: u64 = 2000;
+pub const MIN_CHECK_INTERVAL_MS: u64 = 500;
+pub const MAX_CHECK_INTERVAL_MS: u64 = 60_000;
+
+// Previous versions of the config file kept in `backups/`
+const MAX_CONFIG_BACKUPS: usize = 5;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub meeting_config: MeetingConfig,
+    /// Profile the settings were last switched to, if any
+    pub profile: Option<Profile>,
+    /// How often the monitoring loop checks for meetings, in milliseconds
+    pub check_interval_ms: u64,
+    pub adaptive_polling: AdaptivePollingConfig,
+    /// Consecutive checks that must agree before a meeting start or end is acted on
+    pub detection_confirmations: u32,
+    pub flap_suppression: FlapSuppressionConfig,
+    pub repause_guard: RepauseGuardConfig,
+    /// Process names of apps (e.g. screen recorders, DAWs) that suspend all automatic
+    /// actions while they run
+    pub inhibitor_apps: Vec<String>,
+    /// Detect meetings and log the actions that would run, without running them
+    pub dry_run: bool,
+    /// Global keyboard shortcut that starts or stops monitoring, e.g. "Ctrl+Alt+M"
+    /// (empty turns it off)
+    pub toggle_monitoring_shortcut: String,
+    /// Global keyboard shortcut that flips the manual "in a meeting" override, for calls
+    /// SoundBreak can't detect (empty turns it off)
+    pub manual_meeting_shortcut: String,
+    /// Let `pause_music` pause playback when a meeting starts
+    pub pause_on_meeting_start: bool,
+    /// Seconds to wait, with a chance to cancel, before pausing for a new meeting
+    pub pause_countdown_secs: u64,
+    /// Hours a meeting may keep music paused before asking whether it's really still
+    /// going on (0 turns the check off)
+    pub stale_pause_alert_hours: u64,
+    /// Let `resume_music` resume playback when a meeting ends. A ducked volume is
+    /// restored either way.
+    pub resume_on_meeting_end: bool,
+    /// Ask before resuming instead of resuming right away
+    pub ask_before_resume: bool,
+    /// Resume music paused for a meeting when SoundBreak quits, instead of leaving it
+    /// for the next launch to resume
+    pub restore_music_on_quit: bool,
+    /// Actions run in order when a meeting starts
+    pub on_meeting_start: Vec<TransitionAction>,
+    /// Actions run in order when a meeting ends
+    pub on_meeting_end: Vec<TransitionAction>,
+    pub screen_lock: ScreenLockConfig,
+    pub idle_away: IdleAwayConfig,
+    pub hooks: HookConfig,
+    pub focus: FocusConfig,
+    pub notifications: NotificationConfig,
+    pub schedule: ScheduleConfig,
+    pub soft_pause: SoftPauseConfig,
+    pub podcast: PodcastConfig,
+    pub peer: PeerConfig,
+    pub local_api: LocalApiConfig,
+    /// Web app for phones on the local network
+    pub companion: CompanionConfig,
+    pub home_assistant: HomeAssistantConfig,
+    pub mqtt: MqttConfig,
+    pub osc: OscConfig,
+    pub discord: DiscordConfig,
+    pub busylight: BusylightConfig,
+    pub keyboard_led: KeyboardLedConfig,
+    /// MuteMe buttons toggle the manual meeting override
+    pub mute_button: MuteButtonConfig,
+    pub teams: TeamsConfig,
+    /// Busy calendar events that make Google Chat show "In a meeting"
+    pub google_chat: GoogleChatConfig,
+    /// Detected meetings that weren't scheduled, added to the calendar afterwards
+    pub calendar_log: CalendarLogConfig,
+    /// Daily markdown summaries of meetings, e.g. for an Obsidian vault
+    pub journal: JournalConfig,
+    /// URLs called when a meeting starts or ends and when music is paused or resumed
+    pub webhooks: Vec<Webhook>,
+    pub ifttt: IftttConfig,
+    pub zapier: ZapierConfig,
+    pub hue: HueConfig,
+    /// Settings for individual meeting apps, keyed by process name
+    pub app_overrides: BTreeMap<String, MeetingAppOverride>,
+    /// Language of the tray menu, notifications and command results
+    pub language: Language,
+    /// Show tray menu labels as plain text, without emoji
+    pub plain_tray_labels: bool,
+    /// Show a short status next to the tray icon, e.g. "🎤 42m" during a meeting
+    pub tray_title: bool,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             meeting_config: MeetingConfig::default(),
+            profile: None,
+            check_interval_ms: DEFAULT_CHECK_INTERVAL_MS,
+            adaptive_polling: AdaptivePollingConfig::default(),
+            detection_confirmations: 1,
+            flap_suppression: FlapSuppressionConfig::default(),
+            repause_guard: RepauseGuardConfig::default(),
+            inhibitor_apps: Vec::new(),
+            dry_run: false,
+            toggle_monitoring_shortcut: "Ctrl+Alt+M".to_string(),
+            manual_meeting_shortcut: "Ctrl+Alt+P".to_string(),
+            pause_on_meeting_start: true,
+            pause_countdown_secs: 0,
+            stale_pause_alert_hours: 4,
+            resume_on_meeting_end: true,
+            ask_before_resume: false,
+            restore_music_on_quit: false,
+            on_meeting_start: default_meeting_start_actions(),
+            on_meeting_end: default_meeting_end_actions(),
+            screen_lock: ScreenLockConfig::default(),
+            idle_away: IdleAwayConfig::default(),
+            hooks: HookConfig::default(),
+            focus: FocusConfig::default(),
+            notifications: NotificationConfig::default(),
+            schedule: ScheduleConfig::default(),
+            soft_pause: SoftPauseConfig::default(),
+            podcast: PodcastConfig::default(),
+            peer: PeerConfig::default(),
+            local_api: LocalApiConfig::default(),
+            companion: CompanionConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
+            mqtt: MqttConfig::default(),
+            osc: OscConfig::default(),
+            discord: DiscordConfig::default(),
+            busylight: BusylightConfig::default(),
+            keyboard_led: KeyboardLedConfig::default(),
+            mute_button: MuteButtonConfig::default(),
+            teams: TeamsConfig::default(),
+            google_chat: GoogleChatConfig::default(),
+            calendar_log: CalendarLogConfig::default(),
+            journal: JournalConfig::default(),
+            webhooks: Vec::new(),
+            ifttt: IftttConfig::default(),
+            zapier: ZapierConfig::default(),
+            hue: HueConfig::default(),
+            app_overrides: BTreeMap::new(),
+            language: Language::default(),
+            plain_tray_labels: false,
+            tray_title: false,
         }
     }
 }
 
+impl AppConfig {
+    /// Checks the settings that can't be fixed up silently, before they are applied or saved
+    pub fn validate(&self) -> Result<(), String> {
+        validate_check_interval(self.check_interval_ms)?;
+        self.schedule.validate()?;
+        self.hooks.validate()
+    }
+
+    /// Settings that apply to a meeting in `meeting_app`: its overrides merged over
+    /// the global settings
+    pub fn for_meeting_app(&self, meeting_app: Option<&str>) -> AppConfig {
+        let mut config = self.clone();
+        let Some(app_override) = meeting_app.and_then(|app| self.app_overrides.get(app)) else {
+            return config;
+        };
+
+        if let Some(secs) = app_override.min_secs_before_start {
+            config.flap_suppression.min_secs_before_start = secs;
+        }
+        if let Some(secs) = app_override.min_secs_before_end {
+            config.flap_suppression.min_secs_before_end = secs;
+        }
+        match app_override.pause_mode {
+            Some(PauseMode::Pause) => config.soft_pause.enabled = false,
+            Some(PauseMode::Duck) => {
+                config.soft_pause.enabled = true;
+                config.soft_pause.always = true;
+            }
+            None => {}
+        }
+        if app_override.follow_schedule == Some(false) {
+            config.schedule.enabled = false;
+        }
+        config
+    }
+
+    /// Settings that differ from their defaults, keyed by dotted path (e.g.
+    /// `flap_suppression.min_secs_before_end`). Lists are compared as a whole, and
+    /// secrets are redacted so the result can be shared when asking for help.
+    pub fn diff_from_default(&self) -> BTreeMap<String, ConfigDifference> {
+        fn collect(path: &str, value: &Value, default: &Value, diff: &mut BTreeMap<String, ConfigDifference>) {
+            if let (Value::Object(fields), Value::Object(default_fields)) = (value, default) {
+                let keys: BTreeSet<&String> = fields.keys().chain(default_fields.keys()).collect();
+                for key in keys {
+                    let field_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    collect(
+                        &field_path,
+                        fields.get(key).unwrap_or(&Value::Null),
+                        default_fields.get(key).unwrap_or(&Value::Null),
+                        diff,
+                    );
+                }
+            } else if value != default {
+                diff.insert(path.to_string(), ConfigDifference {
+                    value: value.clone(),
+                    default: default.clone(),
+                });
+            }
+        }
+
+        let mut config = self.clone();
+        for token in [
+            &mut config.peer.token,
+            &mut config.home_assistant.token,
+            &mut config.mqtt.password,
+            &mut config.ifttt.key,
+            &mut config.teams.refresh_token,
+            &mut config.google_chat.refresh_token,
+            &mut config.companion.token,
+        ] {
+            if !token.is_empty() {
+                *token = "<redacted>".to_string();
+            }
+        }
+        let value = serde_json::to_value(&config).unwrap_or_default();
+        let default = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+        let mut diff = BTreeMap::new();
+        collect("", &value, &default, &mut diff);
+        diff
+    }
+
+    /// Meeting apps that are configured but currently ignored
+    pub fn disabled_meeting_apps(&self) -> Vec<String> {
+        self.app_overrides
+            .iter()
+            .filter(|(_, app_override)| app_override.enabled == Some(false))
+            .map(|(app, _)| app.clone())
+            .collect()
+    }
+
+    /// Meeting apps that are handled even outside monitoring hours
+    pub fn schedule_exempt_apps(&self) -> Vec<String> {
+        self.app_overrides
+            .iter()
+            .filter(|(_, app_override)| app_override.follow_schedule == Some(false))
+            .map(|(app, _)| app.clone())
+            .collect()
+    }
+}
+
+pub fn validate_check_interval(interval_ms: u64) -> Result<(), String> {
+    if (MIN_CHECK_INTERVAL_MS..=MAX_CHECK_INTERVAL_MS).contains(&interval_ms) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Check interval must be between {} and {} ms",
+            MIN_CHECK_INTERVAL_MS, MAX_CHECK_INTERVAL_MS
+        ))
+    }
+}
+
+/// Checks less often when it matters less: on battery, and while no meeting app is running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptivePollingConfig {
+    pub enabled: bool,
+    /// Interval multiplier while running on battery
+    pub battery_multiplier: u32,
+    /// Interval multiplier while none of the meeting apps is running
+    pub idle_multiplier: u32,
+    /// Upper bound for the adapted interval, in milliseconds
+    pub max_interval_ms: u64,
+}
+
+impl Default for AdaptivePollingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            battery_multiplier: 3,
+            idle_multiplier: 2,
+            max_interval_ms: 10_000,
+        }
+    }
+}
+
+impl AdaptivePollingConfig {
+    /// Interval to use given the configured base interval and current conditions
+    pub fn effective_interval_ms(&self, base_ms: u64, on_battery: bool, meeting_app_running: bool) -> u64 {
+        if !self.enabled {
+            return base_ms;
+        }
+
+        let mut interval_ms = base_ms;
+        if on_battery {
+            interval_ms = interval_ms.saturating_mul(self.battery_multiplier.max(1) as u64);
+        }
+        if !meeting_app_running {
+            interval_ms = interval_ms.saturating_mul(self.idle_multiplier.max(1) as u64);
+        }
+        interval_ms.min(self.max_interval_ms.max(base_ms))
+    }
+}
+
+/// Minimum time a detected meeting start or end must hold before SoundBreak acts on it.
+///
+/// Meeting apps briefly disappear while they restart or reconnect; waiting out those
+/// gaps avoids resuming music only to pause it again seconds later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlapSuppressionConfig {
+    pub min_secs_before_start: u64,
+    pub min_secs_before_end: u64,
+    /// New meetings are ignored for this long after music was resumed
+    pub resume_cooldown_secs: u64,
+}
+
+impl Default for FlapSuppressionConfig {
+    fn default() -> Self {
+        Self {
+            min_secs_before_start: 0,
+            min_secs_before_end: 10,
+            resume_cooldown_secs: 0,
+        }
+    }
+}
+
+/// Pauses music again if it starts playing while a meeting is still going on,
+/// e.g. from autoplay or a Bluetooth device reconnecting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepauseGuardConfig {
+    pub enabled: bool,
+    /// How long music may play during a meeting before it is paused again
+    pub delay_secs: u64,
+}
+
+impl Default for RepauseGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_secs: 5,
+        }
+    }
+}
+
+/// Treats locking the screen like stepping away: runs `on_lock` when the screen locks
+/// and `on_unlock` when it is unlocked again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenLockConfig {
+    pub enabled: bool,
+    /// Let `resume_music` resume playback on unlock
+    pub resume_on_unlock: bool,
+    pub on_lock: Vec<TransitionAction>,
+    pub on_unlock: Vec<TransitionAction>,
+}
+
+impl Default for ScreenLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            resume_on_unlock: true,
+            on_lock: vec![TransitionAction::PauseMusic],
+            on_unlock: vec![TransitionAction::ResumeMusic],
+        }
+    }
+}
+
+/// Treats a long stretch without keyboard or mouse input as being away: runs
+/// `on_away` after `idle_minutes` and `on_return` on the next input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleAwayConfig {
+    pub enabled: bool,
+    pub idle_minutes: u64,
+    /// Let `resume_music` resume playback when the user is back
+    pub resume_on_return: bool,
+    pub on_away: Vec<TransitionAction>,
+    pub on_return: Vec<TransitionAction>,
+}
+
+impl Default for IdleAwayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 10,
+            resume_on_return: true,
+            on_away: vec![TransitionAction::PauseMusic],
+            on_return: vec![TransitionAction::ResumeMusic],
+        }
+    }
+}
+
+/// A setting's current value next to its default, as reported by `AppConfig::diff_from_default`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDifference {
+    pub value: Value,
+    pub default: Value,
+}
+
+/// Overrides for meetings in one app; unset fields fall back to the global settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingAppOverride {
+    /// Set to false to ignore this app without removing it from the list
+    pub enabled: Option<bool>,
+    pub min_secs_before_start: Option<u64>,
+    pub min_secs_before_end: Option<u64>,
+    /// Always pause or always duck for this app, whatever the meeting length
+    pub pause_mode: Option<PauseMode>,
+    /// Set to false to handle this app's meetings outside monitoring hours too
+    pub follow_schedule: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseMode {
+    Pause,
+    Duck,
+}
+
+/// Short meetings only duck the player's volume instead of pausing playback.
+///
+/// The expected meeting length comes from the calendar event that is currently
+/// in progress. When no calendar event is found, music is always fully paused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoftPauseConfig {
+    pub enabled: bool,
+    /// Duck for every meeting, whatever its length
+    pub always: bool,
+    /// Meetings scheduled for at most this many minutes are ducked instead of paused
+    pub max_meeting_minutes: u64,
+    /// Player volume while ducked, as a percentage of the volume before the meeting
+    pub duck_volume_percent: u8,
+}
+
+impl Default for SoftPauseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            always: false,
+            max_meeting_minutes: 15,
+            duck_volume_percent: 20,
+        }
+    }
+}
+
+/// What to do with spoken-word content (podcasts, audiobooks) when a meeting ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PodcastResumePolicy {
+    Resume,
+    RewindAndResume,
+    DontResume,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PodcastConfig {
+    pub resume_policy: PodcastResumePolicy,
+    pub rewind_seconds: u32,
+    /// Now-playing apps whose content is always treated as spoken word
+    pub app_bundle_ids: Vec<String>,
+    /// Media type substrings (case-insensitive) that identify spoken-word content
+    pub media_type_keywords: Vec<String>,
+}
+
+impl Default for PodcastConfig {
+    fn default() -> Self {
+        Self {
+            resume_policy: PodcastResumePolicy::RewindAndResume,
+            rewind_seconds: 15,
+            app_bundle_ids: vec![
+                "com.apple.podcasts".to_string(),
+                "com.apple.iBooksX".to_string(),
+            ],
+            media_type_keywords: vec![
+                "podcast".to_string(),
+                "audiobook".to_string(),
+            ],
+        }
+    }
+}
+
+impl PodcastConfig {
+    pub fn is_spoken_word(&self, music_status: &MusicStatus) -> bool {
+        let from_app = music_status
+            .app_bundle_id
+            .as_ref()
+            .is_some_and(|bundle_id| self.app_bundle_ids.iter().any(|id| id == bundle_id));
+
+        let from_media_type = music_status.media_type.as_ref().is_some_and(|media_type| {
+            let media_type = media_type.to_lowercase();
+            self.media_type_keywords
+                .iter()
+                .any(|keyword| media_type.contains(&keyword.to_lowercase()))
+        });
+
+        from_app || from_media_type
+    }
+}
+
+/// Forwards meeting transitions to other SoundBreak instances on the local network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeerConfig {
+    /// Accept pause/resume requests from other instances
+    pub listen: bool,
+    /// Address of the interface to accept them on, e.g. the Mac's address on the LAN;
+    /// `0.0.0.0` for every interface
+    pub listen_address: String,
+    pub listen_port: u16,
+    /// Shared secret that must match on both machines. Kept in the keychain; only
+    /// read from `config.json` when set there by hand.
+    pub token: String,
+    /// Instances to notify on meeting transitions, as "host:port"
+    pub peers: Vec<String>,
+}
+
+impl Default for PeerConfig {
+    fn default() -> Self {
+        Self {
+            listen: false,
+            listen_address: "0.0.0.0".to_string(),
+            listen_port: 47820,
+            token: String::new(),
+            peers: Vec::new(),
+        }
+    }
+}
+
+/// HTTP API on `127.0.0.1` for Stream Deck plugins and other local tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for LocalApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47821,
+        }
+    }
+}
+
+/// Settings overridden for this run only, from `SOUNDBREAK_*` environment variables
+/// for debugging and headless setups. They apply on top of `config.json` and are never
+/// saved into it. Invalid values are reported and ignored.
+///
+/// - `SOUNDBREAK_POLL_INTERVAL_MS`: check interval in milliseconds
+/// - `SOUNDBREAK_DRY_RUN`: `1`/`true` or `0`/`false`
+/// - `SOUNDBREAK_PROFILE`: profile id, e.g. `aggressive`
+/// - `SOUNDBREAK_MEETING_APPS`: comma-separated meeting app process names
+/// - `SOUNDBREAK_PAUSE_COUNTDOWN_SECS`: seconds to wait before pausing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    pub profile: Option<Profile>,
+    pub check_interval_ms: Option<u64>,
+    pub dry_run: Option<bool>,
+    pub meeting_apps: Option<Vec<String>>,
+    pub pause_countdown_secs: Option<u64>,
+}
+
+impl ConfigOverrides {
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the overrides from variables looked up with `var`
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse_bool(value: &str) -> Option<bool> {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Some(true),
+                "0" | "false" | "no" | "off" => Some(false),
+                _ => None,
+            }
+        }
+
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let mut overrides = Self::default();
+
+        if let Some(value) = var("SOUNDBREAK_PROFILE") {
+            match Profile::from_id(value.trim()) {
+                Some(profile) => overrides.profile = Some(profile),
+                None => eprintln!("SoundBreak: Ignoring unknown SOUNDBREAK_PROFILE {:?}", value),
+            }
+        }
+
+        if let Some(value) = var("SOUNDBREAK_POLL_INTERVAL_MS") {
+            match value.trim().parse::<u64>().map_err(|e| e.to_string()).and_then(|interval_ms| {
+                validate_check_interval(interval_ms)?;
+                Ok(interval_ms)
+            }) {
+                Ok(interval_ms) => overrides.check_interval_ms = Some(interval_ms),
+                Err(e) => eprintln!("SoundBreak: Ignoring SOUNDBREAK_POLL_INTERVAL_MS {:?}: {}", value, e),
+            }
+        }
+
+        if let Some(value) = var("SOUNDBREAK_DRY_RUN") {
+            match parse_bool(&value) {
+                Some(dry_run) => overrides.dry_run = Some(dry_run),
+                None => eprintln!("SoundBreak: Ignoring SOUNDBREAK_DRY_RUN {:?}", value),
+            }
+        }
+
+        if let Some(value) = var("SOUNDBREAK_MEETING_APPS") {
+            overrides.meeting_apps = Some(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+
+        if let Some(value) = var("SOUNDBREAK_PAUSE_COUNTDOWN_SECS") {
+            match value.trim().parse::<u64>() {
+                Ok(secs) => overrides.pause_countdown_secs = Some(secs),
+                Err(e) => eprintln!("SoundBreak: Ignoring SOUNDBREAK_PAUSE_COUNTDOWN_SECS {:?}: {}", value, e),
+            }
+        }
+
+        overrides
+    }
+
+    pub fn apply(&self, config: &mut AppConfig) {
+        // Applied first, so the more specific overrides below win over the profile
+        if let Some(profile) = self.profile {
+            profile.apply(config);
+        }
+        if let Some(interval_ms) = self.check_interval_ms {
+            config.check_interval_ms = interval_ms;
+        }
+        if let Some(dry_run) = self.dry_run {
+            config.dry_run = dry_run;
+        }
+        if let Some(meeting_apps) = &self.meeting_apps {
+            config.meeting_config.process_names = meeting_apps.clone();
+        }
+        if let Some(secs) = self.pause_countdown_secs {
+            config.pause_countdown_secs = secs;
+        }
+    }
+}
+
+// Puts back the saved value of every setting still at its overridden value, so settings
+// changed in the app are saved while the overrides themselves never are. Lists count as
+// single settings.
+fn revert_overrides(value: &mut Value, overridden: &Value, saved: &Value) {
+    match (value, overridden, saved) {
+        (Value::Object(fields), Value::Object(overridden), Value::Object(saved)) => {
+            for (key, field) in fields.iter_mut() {
+                if let (Some(overridden), Some(saved)) = (overridden.get(key), saved.get(key)) {
+                    revert_overrides(field, overridden, saved);
+                }
+            }
+        }
+        (value, overridden, saved) => {
+            if value == overridden && overridden != saved {
+                *value = saved.clone();
+            }
+        }
+    }
+}
+
+/// A previous version of the config file kept by `ConfigManager::save_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    /// 1 for the most recent backup
+    pub index: usize,
+    /// When this version was replaced (unix secs)
+    pub saved_at: u64,
+}
+
+// Config file chosen with `--config` or `--config-dir`, used instead of the one in the app directory
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+// Overrides for this run, read from the environment unless set on launch
+static OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+
+// The settings as last read from or saved to `config.json`, before overrides
+static SAVED_CONFIG: Mutex<Option<AppConfig>> = Mutex::new(None);
+
+// Set when `load_config` found no config file and wrote the defaults
+static FIRST_LAUNCH: AtomicBool = AtomicBool::new(false);
+
+// Keychain accounts that couldn't be read when the config was loaded. Their fields are
+// empty only because of that, so saving must not delete them from the keychain.
+static UNREADABLE_SECRETS: Mutex<BTreeSet<&str>> = Mutex::new(BTreeSet::new());
+
 pub struct ConfigManager;
 
 impl ConfigManager {
-    fn get_config_path() -> Result<PathBuf, String> {
+    pub const CONFIG_FILE_NAME: &'static str = "config.json";
+
+    // File in the app directory naming another directory to keep `config.json` in,
+    // e.g. in iCloud Drive or a dotfiles repo so settings sync across machines
+    const CONFIG_DIR_POINTER: &'static str = "config-dir";
+
+    /// Directory holding the config file and other persistent app data
+    pub fn get_app_dir() -> Result<PathBuf, String> {
         let config_dir = dirs::config_dir()
             .ok_or("Could not find config directory")?;
         
@@ -28,50 +746,372 @@ impl ConfigManager {
         std::fs::create_dir_all(&app_config_dir)
             .map_err(|e| format!("Failed to create config directory: {}", e))?;
         
-        Ok(app_config_dir.join("config.json"))
+        Ok(app_config_dir)
+    }
+
+    /// Reads and saves settings at `path` from now on; only the first call has an effect
+    pub fn set_config_path(path: PathBuf) {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
+    }
+
+    pub fn get_config_path() -> Result<PathBuf, String> {
+        match CONFIG_PATH_OVERRIDE.get() {
+            Some(path) => Ok(path.clone()),
+            None => Ok(Self::get_config_dir()?.join(Self::CONFIG_FILE_NAME)),
+        }
+    }
+
+    /// Directory holding `config.json`: the one named in the `config-dir` file if
+    /// there is one, otherwise the app directory. Monitoring state, statistics and
+    /// logs always stay in the app directory, since they belong to this machine.
+    pub fn get_config_dir() -> Result<PathBuf, String> {
+        let app_dir = Self::get_app_dir()?;
+        let Ok(content) = std::fs::read_to_string(app_dir.join(Self::CONFIG_DIR_POINTER)) else {
+            return Ok(app_dir);
+        };
+        let Some(line) = content.lines().map(str::trim).find(|line| !line.is_empty()) else {
+            return Ok(app_dir);
+        };
+
+        let config_dir = match line.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().ok_or("Could not find home directory")?.join(rest),
+            None => PathBuf::from(line),
+        };
+        std::fs::create_dir_all(&config_dir)
+            .map_err(|e| format!("Failed to create config directory {:?}: {}", config_dir, e))?;
+        Ok(config_dir)
     }
 
     pub fn load_config() -> AppConfig {
-        match Self::get_config_path() {
+        let config_path = Self::get_config_path();
+        match &config_path {
+            Ok(config_path) if config_path.exists() => match Self::read_config() {
+                Ok(config) => return config,
+                Err(e) => eprintln!("SoundBreak: {}", e),
+            },
+            // On first launch, write out every setting with its default so they can be
+            // discovered and edited in the file
             Ok(config_path) => {
-                if config_path.exists() {
-                    match std::fs::read_to_string(&config_path) {
-                        Ok(content) => {
-                            match serde_json::from_str::<AppConfig>(&content) {
-                                Ok(config) => {
-                                    println!("SoundBreak: Loaded configuration from {:?}", config_path);
-                                    return config;
-                                }
-                                Err(e) => {
-                                    eprintln!("SoundBreak: Failed to parse config file: {}", e);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("SoundBreak: Failed to read config file: {}", e);
-                        }
-                    }
+                FIRST_LAUNCH.store(true, Ordering::Relaxed);
+                match Self::write_config_file(&AppConfig::default(), config_path) {
+                    Ok(()) => println!("SoundBreak: Wrote default configuration to {:?}", config_path),
+                    Err(e) => eprintln!("SoundBreak: {}", e),
                 }
             }
+            Err(e) => eprintln!("SoundBreak: {}", e),
+        }
+
+        println!("SoundBreak: Using default configuration");
+        Self::with_overrides(AppConfig::default())
+    }
+
+    /// Whether `load_config` found no config file, i.e. SoundBreak runs for the first time
+    pub fn is_first_launch() -> bool {
+        FIRST_LAUNCH.load(Ordering::Relaxed)
+    }
+
+    /// Reads the config file, replacing invalid values with defaults and applying the
+    /// overrides for this run. Fails if the file can't be read or parsed.
+    pub fn read_config() -> Result<AppConfig, String> {
+        Ok(Self::with_overrides(Self::read_config_file(&Self::get_config_path()?)?))
+    }
+
+    /// Sets the overrides for this run, e.g. from command-line options. Must be called
+    /// before the config is first loaded.
+    pub fn set_overrides(overrides: ConfigOverrides) {
+        let _ = OVERRIDES.set(overrides);
+    }
+
+    /// The overrides for this run
+    pub fn overrides() -> &'static ConfigOverrides {
+        OVERRIDES.get_or_init(ConfigOverrides::from_env)
+    }
+
+    /// The settings to run with for `config` as saved, with the overrides for this run
+    /// applied. `config` is remembered so saving can leave the overrides out.
+    pub fn with_overrides(config: AppConfig) -> AppConfig {
+        let mut overridden = config.clone();
+        Self::overrides().apply(&mut overridden);
+        *SAVED_CONFIG.lock_or_recover() = Some(config);
+        overridden
+    }
+
+    // The settings to save for `config` as used at runtime, i.e. without the overrides
+    fn without_overrides(config: &AppConfig) -> AppConfig {
+        let Some(saved) = SAVED_CONFIG.lock_or_recover().clone() else {
+            return config.clone();
+        };
+        let mut overridden = saved.clone();
+        Self::overrides().apply(&mut overridden);
+
+        let (Ok(mut value), Ok(overridden), Ok(saved)) =
+            (serde_json::to_value(config), serde_json::to_value(&overridden), serde_json::to_value(&saved))
+        else {
+            return config.clone();
+        };
+        revert_overrides(&mut value, &overridden, &saved);
+        serde_json::from_value(value).unwrap_or_else(|_| config.clone())
+    }
+
+    /// Like `read_config`, for a config file at any path, without the overrides
+    pub fn read_config_file(config_path: &Path) -> Result<AppConfig, String> {
+        let mut config = Self::parse_config_file(config_path)?;
+
+        if let Err(e) = validate_check_interval(config.check_interval_ms) {
+            eprintln!("SoundBreak: {}, using default", e);
+            config.check_interval_ms = DEFAULT_CHECK_INTERVAL_MS;
+        }
+        if let Err(e) = config.schedule.validate() {
+            eprintln!("SoundBreak: {}, schedule disabled", e);
+            config.schedule.enabled = false;
+        }
+        if let Err(e) = config.hooks.validate() {
+            eprintln!("SoundBreak: {}, using {} s", e, hooks::MAX_TIMEOUT_SECS);
+            config.hooks.timeout_secs = hooks::MAX_TIMEOUT_SECS;
+        }
+        Ok(config)
+    }
+
+    /// Reads a config file that is about to replace the settings in use, e.g. an exported
+    /// one being imported. Fails on invalid values instead of replacing them.
+    pub fn import_config_file(config_path: &Path) -> Result<AppConfig, String> {
+        let config = Self::parse_config_file(config_path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Reads and parses a config file, filling in its secrets from the keychain
+    fn parse_config_file(config_path: &Path) -> Result<AppConfig, String> {
+        let content = std::fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read config file: {}", e))?;
+        let mut config = serde_json::from_str::<AppConfig>(&content)
+            .map_err(|e| format!("Failed to parse config file: {}", e))?;
+
+        Self::load_secret(PEER_TOKEN, &mut config.peer.token);
+        Self::load_secret(HOME_ASSISTANT_TOKEN, &mut config.home_assistant.token);
+        Self::load_secret(MQTT_PASSWORD, &mut config.mqtt.password);
+        Self::load_secret(IFTTT_KEY, &mut config.ifttt.key);
+        Self::load_secret(TEAMS_REFRESH_TOKEN, &mut config.teams.refresh_token);
+        Self::load_secret(GOOGLE_CHAT_REFRESH_TOKEN, &mut config.google_chat.refresh_token);
+        Self::load_secret(COMPANION_TOKEN, &mut config.companion.token);
+        println!("SoundBreak: Loaded configuration from {:?}", config_path);
+        Ok(config)
+    }
+
+    // Fills an empty secret field from the keychain
+    fn load_secret(key: &'static str, field: &mut String) {
+        if !field.is_empty() {
+            return;
+        }
+        match default_secrets_store().get(key) {
+            Ok(value) => {
+                *field = value.unwrap_or_default();
+                UNREADABLE_SECRETS.lock_or_recover().remove(key);
+            }
             Err(e) => {
-                eprintln!("SoundBreak: Failed to get config path: {}", e);
+                eprintln!("SoundBreak: {}", e);
+                UNREADABLE_SECRETS.lock_or_recover().insert(key);
             }
         }
-        
-        println!("SoundBreak: Using default configuration");
-        AppConfig::default()
     }
 
+    /// Saves `config`, first moving the previous version into `backups/`
     pub fn save_config(config: &AppConfig) -> Result<(), String> {
         let config_path = Self::get_config_path()?;
-        
-        let json_content = serde_json::to_string_pretty(config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
-        
-        std::fs::write(&config_path, json_content)
+        let saved = Self::without_overrides(config);
+        let json_content = Self::to_json(&saved)?;
+        if let Err(e) = Self::backup_config(&config_path, &json_content) {
+            eprintln!("SoundBreak: Failed to back up configuration: {}", e);
+        }
+        Self::write_json(&json_content, &config_path)?;
+        *SAVED_CONFIG.lock_or_recover() = Some(saved);
+        Ok(())
+    }
+
+    /// Writes `config` as JSON to `config_path`, without the overrides for this run
+    pub fn write_config_file(config: &AppConfig, config_path: &Path) -> Result<(), String> {
+        Self::write_json(&Self::to_json(&Self::without_overrides(config))?, config_path)
+    }
+
+    /// Returns the saved backups, newest first
+    pub fn list_config_backups() -> Result<Vec<ConfigBackup>, String> {
+        let backup_dir = Self::get_backup_dir(&Self::get_config_path()?);
+        Ok((1..=MAX_CONFIG_BACKUPS)
+            .filter_map(|index| {
+                let modified = std::fs::metadata(Self::backup_path(&backup_dir, index)).ok()?.modified().ok()?;
+                Some(ConfigBackup {
+                    index,
+                    saved_at: modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs(),
+                })
+            })
+            .collect())
+    }
+
+    /// Reads backup `index` (1 is the newest), to be applied like an imported config
+    pub fn read_config_backup(index: usize) -> Result<AppConfig, String> {
+        let backup_dir = Self::get_backup_dir(&Self::get_config_path()?);
+        let backup_path = Self::backup_path(&backup_dir, index);
+        if !backup_path.exists() {
+            return Err(format!("There is no config backup {}", index));
+        }
+        Self::import_config_file(&backup_path)
+    }
+
+    // Shifts the existing backups back by one and keeps the current file as the newest,
+    // unless saving wouldn't change it
+    fn backup_config(config_path: &Path, new_content: &str) -> Result<(), String> {
+        let Ok(current_content) = std::fs::read_to_string(config_path) else {
+            return Ok(());
+        };
+        if current_content == new_content {
+            return Ok(());
+        }
+
+        let backup_dir = Self::get_backup_dir(config_path);
+        std::fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        for index in (1..MAX_CONFIG_BACKUPS).rev() {
+            let from = Self::backup_path(&backup_dir, index);
+            if from.exists() {
+                std::fs::rename(&from, Self::backup_path(&backup_dir, index + 1))
+                    .map_err(|e| format!("Failed to rotate config backups: {}", e))?;
+            }
+        }
+        std::fs::write(Self::backup_path(&backup_dir, 1), current_content)
+            .map_err(|e| format!("Failed to write config backup: {}", e))
+    }
+
+    fn get_backup_dir(config_path: &Path) -> PathBuf {
+        config_path.parent().unwrap_or(Path::new(".")).join("backups")
+    }
+
+    fn backup_path(backup_dir: &Path, index: usize) -> PathBuf {
+        backup_dir.join(format!("config.{}.json", index))
+    }
+
+    // Serializes `config` for writing to disk, with secrets moved to the keychain
+    fn to_json(config: &AppConfig) -> Result<String, String> {
+        let mut config = config.clone();
+        let secrets = default_secrets_store();
+        let secret_fields = [
+            (PEER_TOKEN, &mut config.peer.token),
+            (HOME_ASSISTANT_TOKEN, &mut config.home_assistant.token),
+            (MQTT_PASSWORD, &mut config.mqtt.password),
+            (IFTTT_KEY, &mut config.ifttt.key),
+            (TEAMS_REFRESH_TOKEN, &mut config.teams.refresh_token),
+            (GOOGLE_CHAT_REFRESH_TOKEN, &mut config.google_chat.refresh_token),
+            (COMPANION_TOKEN, &mut config.companion.token),
+        ];
+        for (key, token) in secret_fields {
+            // An empty field for a secret that failed to load leaves the keychain alone
+            if token.is_empty() && UNREADABLE_SECRETS.lock_or_recover().contains(key) {
+                continue;
+            }
+            let stored = if token.is_empty() { secrets.delete(key) } else { secrets.set(key, token) };
+            match stored {
+                Ok(()) => {
+                    token.clear();
+                    UNREADABLE_SECRETS.lock_or_recover().remove(key);
+                }
+                Err(e) => eprintln!("SoundBreak: {}; keeping {} in config.json", e, key),
+            }
+        }
+        serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize config: {}", e))
+    }
+
+    fn write_json(json_content: &str, config_path: &Path) -> Result<(), String> {
+        std::fs::write(config_path, json_content)
             .map_err(|e| format!("Failed to write config file: {}", e))?;
         
         println!("SoundBreak: Saved configuration to {:?}", config_path);
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let pairs: Vec<(String, String)> = pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        move |name| pairs.iter().find(|(key, _)| key == name).map(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn reads_overrides_from_variables() {
+        let overrides = ConfigOverrides::from_vars(vars(&[
+            ("SOUNDBREAK_PROFILE", "aggressive"),
+            ("SOUNDBREAK_POLL_INTERVAL_MS", "1500"),
+            ("SOUNDBREAK_DRY_RUN", "yes"),
+            ("SOUNDBREAK_MEETING_APPS", "zoom.us, Teams ,"),
+            ("SOUNDBREAK_PAUSE_COUNTDOWN_SECS", "5"),
+        ]));
+
+        assert_eq!(overrides, ConfigOverrides {
+            profile: Some(Profile::Aggressive),
+            check_interval_ms: Some(1500),
+            dry_run: Some(true),
+            meeting_apps: Some(vec!["zoom.us".to_string(), "Teams".to_string()]),
+            pause_countdown_secs: Some(5),
+        });
+    }
+
+    #[test]
+    fn ignores_invalid_and_empty_overrides() {
+        let overrides = ConfigOverrides::from_vars(vars(&[
+            ("SOUNDBREAK_PROFILE", "fastest"),
+            ("SOUNDBREAK_POLL_INTERVAL_MS", "10"),
+            ("SOUNDBREAK_DRY_RUN", "maybe"),
+            ("SOUNDBREAK_MEETING_APPS", " "),
+            ("SOUNDBREAK_PAUSE_COUNTDOWN_SECS", "-1"),
+        ]));
+
+        assert_eq!(overrides, ConfigOverrides::default());
+    }
+
+    #[test]
+    fn specific_overrides_win_over_the_profile() {
+        let overrides = ConfigOverrides {
+            profile: Some(Profile::Aggressive),
+            check_interval_ms: Some(1500),
+            ..ConfigOverrides::default()
+        };
+        let mut config = AppConfig::default();
+        overrides.apply(&mut config);
+
+        assert_eq!(config.profile, Some(Profile::Aggressive));
+        assert_eq!(config.check_interval_ms, 1500);
+        assert_eq!(config.flap_suppression.min_secs_before_end, 3);
+    }
+
+    #[test]
+    fn reverts_only_settings_still_at_their_overridden_value() {
+        let saved = json!({ "check_interval_ms": 2000, "dry_run": false, "schedule": { "start": "09:00" } });
+        let overridden = json!({ "check_interval_ms": 1000, "dry_run": true, "schedule": { "start": "09:00" } });
+        // The interval is still overridden, while dry run and the schedule were changed in the app
+        let mut value = json!({ "check_interval_ms": 1000, "dry_run": false, "schedule": { "start": "10:00" } });
+
+        revert_overrides(&mut value, &overridden, &saved);
+
+        assert_eq!(value, json!({ "check_interval_ms": 2000, "dry_run": false, "schedule": { "start": "10:00" } }));
+    }
+
+    #[test]
+    fn validate_rejects_invalid_settings() {
+        assert!(AppConfig::default().validate().is_ok());
+
+        let config = AppConfig {
+            check_interval_ms: MAX_CHECK_INTERVAL_MS + 1,
+            ..AppConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let mut config = AppConfig::default();
+        config.schedule.start = "9am".to_string();
+        assert!(config.validate().is_err());
+
+        let mut config = AppConfig::default();
+        config.hooks.timeout_secs = hooks::MAX_TIMEOUT_SECS + 1;
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! Live reloading of `config.json`
+//!
+//! Watches the config file's directory so edits made outside SoundBreak, including editors that
+//! replace the file instead of writing it in place, are picked up without a restart.
+
+use crate::config::{AppConfig, ConfigManager};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// Editors save in several steps; changes are read once they have been quiet this long
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Calls `on_change` with the parsed config after every change to `config.json`,
+/// including SoundBreak's own saves. Files that fail to parse are skipped.
+pub fn watch(on_change: impl Fn(AppConfig) + Send + 'static) -> Result<(), String> {
+    let config_path = ConfigManager::get_config_path()?;
+    let config_dir = config_path
+        .parent()
+        .ok_or_else(|| format!("Config path {:?} has no parent directory", config_path))?
+        .to_path_buf();
+    let config_file_name = config_path.file_name().map(|name| name.to_os_string());
+    let (event_tx, event_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(event_tx)
+        .map_err(|e| format!("Failed to create config watcher: {}", e))?;
+    watcher
+        .watch(&config_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch config directory: {}", e))?;
+
+    std::thread::spawn(move || {
+        // The watcher stops when dropped, so it lives as long as this thread
+        let _watcher = watcher;
+        while let Ok(event) = event_rx.recv() {
+            let touches_config = event.is_ok_and(|event| {
+                matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|path| path.file_name() == config_file_name.as_deref())
+            });
+            if !touches_config {
+                continue;
+            }
+
+            while event_rx.recv_timeout(SETTLE_DELAY).is_ok() {}
+            match ConfigManager::read_config() {
+                Ok(config) => on_change(config),
+                Err(e) => eprintln!("SoundBreak: Ignoring config change: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
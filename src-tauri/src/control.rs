@@ -0,0 +1,132 @@
+//! Control socket
+//!
+//! A running instance listens on a Unix socket in the app data directory, so running the
+//! binary again with a command controls it from a terminal:
+//!
+//! - `sound-break status`: print the current status as JSON
+//! - `sound-break summary`: print the current status as one line of text
+//! - `sound-break toggle`, `sound-break start`, `sound-break stop`
+//! - `sound-break snooze [minutes]`, `sound-break unsnooze`
+//! - `sound-break meeting start|end|skip`, `sound-break music pause|resume`
+//! - `sound-break profile <name>`
+//! - `sound-break pair`: print the URL that pairs a phone with the companion
+//!
+//! Each connection carries one request line, the path of the matching `soundbreak://` URL
+//! (e.g. `snooze?min=30`), `status`, `summary` or `pair`, answered by one line starting
+//! with `ok ` or `error `.
+
+use crate::companion;
+use crate::config::ConfigManager;
+use crate::deep_link;
+use crate::AppState;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const SOCKET_FILE_NAME: &str = "control.sock";
+
+fn socket_path() -> Result<PathBuf, String> {
+    Ok(ConfigManager::get_app_dir()?.join(SOCKET_FILE_NAME))
+}
+
+/// Starts answering requests from the command line on a background thread
+pub fn listen(app: &AppHandle) -> Result<(), String> {
+    let path = socket_path()?;
+    // A socket left behind by a crash would keep us from binding
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).map_err(|e| format!("Failed to open control socket: {}", e))?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    std::thread::spawn(move || serve(&app, stream));
+                }
+                Err(e) => eprintln!("SoundBreak: Control socket connection failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn serve(app: &AppHandle, mut stream: UnixStream) {
+    let mut request = String::new();
+    if let Err(e) = BufReader::new(&stream).read_line(&mut request) {
+        eprintln!("SoundBreak: Failed to read control request: {}", e);
+        return;
+    }
+    let response = match respond(app, request.trim()) {
+        Ok(result) => format!("ok {}\n", result),
+        Err(e) => format!("error {}\n", e),
+    };
+    if let Err(e) = stream.write_all(response.as_bytes()) {
+        eprintln!("SoundBreak: Failed to answer control request: {}", e);
+    }
+}
+
+fn respond(app: &AppHandle, request: &str) -> Result<String, String> {
+    if request == "status" {
+        let status = app.state::<AppState>().monitoring_service.get_status();
+        return serde_json::to_string(&status).map_err(|e| format!("Failed to serialize status: {}", e));
+    }
+    if request == "summary" {
+        return Ok(crate::status_summary(&app.state::<AppState>().monitoring_service.get_status()));
+    }
+    if request == "pair" {
+        return companion::pair(app);
+    }
+    deep_link::run(app, &format!("{}://{}", deep_link::SCHEME, request))
+}
+
+/// Sends the command in `args` to the running instance and prints its answer. Returns
+/// the exit code for the process.
+pub fn run_client(args: &[String]) -> i32 {
+    let result = request_for(args).and_then(|request| send(&request));
+    match result {
+        Ok(result) => {
+            println!("{}", result);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+fn request_for(args: &[String]) -> Result<String, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [command @ ("status" | "summary" | "pair" | "toggle" | "start" | "stop" | "snooze" | "unsnooze")] => Ok(command.to_string()),
+        ["snooze", minutes] => Ok(format!("snooze?min={}", minutes)),
+        [target @ "meeting", action @ ("start" | "end" | "skip")] | [target @ "music", action @ ("pause" | "resume")] => {
+            Ok(format!("{}/{}", target, action))
+        }
+        ["profile", name] => Ok(format!("profile/{}", name)),
+        _ => Err(format!(
+            "Unknown command {:?}. Use status, summary, pair, toggle, start, stop, snooze [minutes], unsnooze, meeting start|end|skip, music pause|resume or profile <name>.",
+            args.join(" ")
+        )),
+    }
+}
+
+fn send(request: &str) -> Result<String, String> {
+    let mut stream = UnixStream::connect(socket_path()?).map_err(|e| format!("SoundBreak isn't running ({})", e))?;
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .map_err(|e| format!("Failed to send command: {}", e))?;
+
+    let mut response = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut response)
+        .map_err(|e| format!("Failed to read the answer: {}", e))?;
+    let response = response.trim_end_matches('\n');
+    match (response.strip_prefix("ok "), response.strip_prefix("error ")) {
+        (Some(result), _) => Ok(result.to_string()),
+        (_, Some(error)) => Err(error.to_string()),
+        _ => Err(format!("Unexpected answer {:?}", response)),
+    }
+}
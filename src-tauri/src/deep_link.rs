@@ -0,0 +1,84 @@
+//! `soundbreak://` URLs
+//!
+//! Lets Shortcuts, Raycast and scripts drive the app, e.g. with `open soundbreak://toggle`:
+//!
+//! - `soundbreak://toggle`, `soundbreak://start`, `soundbreak://stop`: monitoring
+//! - `soundbreak://snooze?min=30`: snooze automatic actions (30 minutes by default)
+//! - `soundbreak://unsnooze`: end the snooze early
+//! - `soundbreak://meeting/start`, `soundbreak://meeting/end`: the manual meeting override
+//! - `soundbreak://meeting/skip`: leave music alone for the rest of the current meeting
+//! - `soundbreak://meeting/external?source=Softphone&state=start`: another tool says a
+//!   meeting started or ended (`state=end`)
+//! - `soundbreak://music/pause`, `soundbreak://music/resume`: pause or resume music now
+//! - `soundbreak://profile/<name>`: switch to a profile, e.g. `soundbreak://profile/duck_only`
+
+use crate::i18n::{tr, tr_args};
+use crate::music_controller::{MusicAction, MusicController};
+use crate::profiles::Profile;
+use crate::AppState;
+use tauri::{AppHandle, Manager, Url};
+
+pub const SCHEME: &str = "soundbreak";
+
+const DEFAULT_SNOOZE_MINUTES: u64 = 30;
+
+/// Runs the action a `soundbreak://` URL asks for on a background thread, logging the outcome
+pub fn open(app: &AppHandle, url: &str) {
+    let app = app.clone();
+    let url = url.to_string();
+    std::thread::spawn(move || match run(&app, &url) {
+        Ok(result) => println!("SoundBreak: {} - {}", url, result),
+        Err(e) => eprintln!("SoundBreak: Failed to open {}: {}", url, e),
+    });
+}
+
+/// Runs the action a `soundbreak://` URL asks for, returning the outcome
+pub fn run(app: &AppHandle, url: &str) -> Result<String, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {}:// URL", SCHEME));
+    }
+
+    let service = &app.state::<AppState>().monitoring_service;
+    let path = url.path().trim_matches('/');
+    match (url.host_str().unwrap_or_default(), path) {
+        ("toggle", "") => service.toggle_monitoring(),
+        ("start", "") => service.start_monitoring(),
+        ("stop", "") => service.stop_monitoring(),
+        ("snooze", "") => {
+            let minutes = match url.query_pairs().find(|(key, _)| key == "min") {
+                Some((_, value)) => value.parse().map_err(|_| format!("Invalid snooze duration {:?}", value))?,
+                None => DEFAULT_SNOOZE_MINUTES,
+            };
+            service.snooze(minutes)
+        }
+        ("unsnooze", "") => service.cancel_snooze(),
+        ("meeting", "start") => service.set_manual_meeting(true),
+        ("meeting", "end") => service.set_manual_meeting(false),
+        ("meeting", "skip") => service.skip_meeting(),
+        ("meeting", "external") => {
+            let query = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned());
+            let source = query("source").ok_or("Missing source")?;
+            match query("state").as_deref() {
+                Some("start") => service.set_external_meeting(&source, true),
+                Some("end") => service.set_external_meeting(&source, false),
+                Some(state) => Err(format!("Invalid state {:?}, expected start or end", state)),
+                None => Err("Missing state".to_string()),
+            }
+        }
+        ("music", "pause") => MusicController::new().execute_action(MusicAction::Pause),
+        ("music", "resume") => MusicController::new().execute_action(MusicAction::Play),
+        ("profile", id) if !id.is_empty() => {
+            let profile = Profile::from_id(id).ok_or_else(|| format!("Unknown profile {:?}", id))?;
+            service.apply_profile(profile)?;
+            Ok(tr_args("Switched to the {profile} profile", &[("profile", &tr(profile.label()))]))
+        }
+        (host, path) => Err(format!("Unknown action {}/{}", host, path).trim_end_matches('/').to_string()),
+    }
+}
+
+/// Whether a command-line argument is a `soundbreak://` URL, as passed on by a second
+/// instance launched with one
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.strip_prefix(SCHEME).is_some_and(|rest| rest.starts_with("://"))
+}
@@ -0,0 +1,150 @@
+//! Discord Rich Presence
+//!
+//! Shows "In a meeting" on the user's Discord profile while a meeting is active, through
+//! the IPC socket of the local Discord app. Discord clears the presence when the
+//! connection closes, so it is kept open for the length of the meeting and closed
+//! afterwards. The socket is only reached on a background thread.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const IPC_TIMEOUT: Duration = Duration::from_secs(3);
+// Discord listens on the first free one of discord-ipc-0 to discord-ipc-9
+const IPC_SOCKET_COUNT: u32 = 10;
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+
+// The open connection while a presence is shown, and the meeting app it names.
+// Also serializes updates between threads.
+static PRESENCE: Mutex<Presence> = Mutex::new(Presence { stream: None, shown: None });
+
+struct Presence {
+    stream: Option<UnixStream>,
+    shown: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    /// Application id from the Discord Developer Portal; its name is shown as the activity
+    pub client_id: String,
+}
+
+/// Shows or clears the presence when the meeting state changed, on a background thread
+pub fn publish(config: &DiscordConfig, status: &MonitoringStatus) {
+    let enabled = config.enabled && !config.client_id.trim().is_empty();
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    // When turned off, there's only something to do if a presence is still shown. A
+    // busy lock means an update is under way.
+    let idle = PRESENCE.try_lock().is_ok_and(|presence| presence.stream.is_none());
+    if !enabled && idle {
+        return;
+    }
+
+    let wanted = (enabled && in_meeting).then(|| status.meeting_app().unwrap_or_default());
+    let started_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - status.meeting_duration_secs.unwrap_or(0);
+    let client_id = config.client_id.trim().to_string();
+    std::thread::spawn(move || {
+        let mut presence = PRESENCE.lock_or_recover();
+        if presence.shown == wanted {
+            return;
+        }
+
+        match wanted {
+            Some(app) => match show(&mut presence, &client_id, &app, started_at) {
+                Ok(()) => presence.shown = Some(app),
+                Err(e) => {
+                    eprintln!("SoundBreak: Failed to set Discord status: {}", e);
+                    presence.stream = None;
+                }
+            },
+            None => {
+                // Closing the connection clears the presence, even if clearing it failed
+                if let Some(mut stream) = presence.stream.take() {
+                    let _ = set_activity(&mut stream, Value::Null);
+                }
+                presence.shown = None;
+            }
+        }
+    });
+}
+
+fn show(presence: &mut Presence, client_id: &str, app: &str, started_at: u64) -> Result<(), String> {
+    if presence.stream.is_none() {
+        presence.stream = Some(connect(client_id)?);
+    }
+    let stream = presence.stream.as_mut().expect("connected above");
+
+    let mut activity = json!({
+        "details": "In a meeting",
+        "timestamps": { "start": started_at },
+    });
+    if !app.is_empty() {
+        activity["state"] = json!(format!("on {}", app));
+    }
+    set_activity(stream, activity)
+}
+
+fn connect(client_id: &str) -> Result<UnixStream, String> {
+    let mut stream = socket_paths()
+        .into_iter()
+        .find_map(|path| UnixStream::connect(path).ok())
+        .ok_or_else(|| "Discord isn't running".to_string())?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(IPC_TIMEOUT)).map_err(|e| e.to_string())?;
+
+    write_frame(&mut stream, OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+    let ready = read_frame(&mut stream)?;
+    if ready["evt"] != "READY" {
+        return Err(format!("Discord refused the connection: {}", ready["message"].as_str().unwrap_or("unknown error")));
+    }
+    Ok(stream)
+}
+
+fn socket_paths() -> Vec<PathBuf> {
+    let dir = std::env::var_os("TMPDIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("/tmp"));
+    (0..IPC_SOCKET_COUNT).map(|i| dir.join(format!("discord-ipc-{}", i))).collect()
+}
+
+// `activity` is `null` to clear the presence
+fn set_activity(stream: &mut UnixStream, activity: Value) -> Result<(), String> {
+    let nonce = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().to_string();
+    let command = json!({
+        "cmd": "SET_ACTIVITY",
+        "args": { "pid": std::process::id(), "activity": activity },
+        "nonce": nonce,
+    });
+    write_frame(stream, OP_FRAME, &command)?;
+    let response = read_frame(stream)?;
+    if response["evt"] == "ERROR" {
+        return Err(response["data"]["message"].as_str().unwrap_or("unknown error").to_string());
+    }
+    Ok(())
+}
+
+// Frames are the opcode and the payload length as little-endian u32s, then JSON
+fn write_frame(stream: &mut UnixStream, opcode: u32, payload: &Value) -> Result<(), String> {
+    let payload = payload.to_string();
+    let mut frame = Vec::with_capacity(8 + payload.len());
+    frame.extend_from_slice(&opcode.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(payload.as_bytes());
+    stream.write_all(&frame).map_err(|e| format!("Failed to write to Discord: {}", e))
+}
+
+fn read_frame(stream: &mut UnixStream) -> Result<Value, String> {
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header).map_err(|e| format!("Failed to read from Discord: {}", e))?;
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let mut payload = vec![0u8; length];
+    stream.read_exact(&mut payload).map_err(|e| format!("Failed to read from Discord: {}", e))?;
+    serde_json::from_slice(&payload).map_err(|e| format!("Unexpected answer from Discord: {}", e))
+}
@@ -0,0 +1,64 @@
+//! macOS Focus (Do Not Disturb)
+//!
+//! macOS has no API for other apps to switch Focus modes, so SoundBreak runs two
+//! Shortcuts the user creates with the "Set Focus" action, one turning the chosen Focus
+//! on and one turning it off. Whether a Focus is already on is read from the Do Not
+//! Disturb database, which needs Full Disk Access; if it can't be read, Focus is taken
+//! to be off.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Name of the Shortcut that turns the Focus for meetings on
+    pub on_shortcut: String,
+    /// Name of the Shortcut that turns it off again
+    pub off_shortcut: String,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            on_shortcut: "SoundBreak Focus On".to_string(),
+            off_shortcut: "SoundBreak Focus Off".to_string(),
+        }
+    }
+}
+
+/// Whether a Focus mode was turned on by hand or by another app
+pub fn is_focus_on() -> Result<bool, String> {
+    let path = dirs::home_dir()
+        .ok_or("Could not find the home directory")?
+        .join("Library/DoNotDisturb/DB/Assertions.json");
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read the Focus state: {}", e))?;
+    let assertions: Value = serde_json::from_str(&content).map_err(|e| format!("Failed to parse the Focus state: {}", e))?;
+
+    Ok(assertions["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|store| store["storeAssertionRecords"].as_array().is_some_and(|records| !records.is_empty())))
+}
+
+/// Runs the configured Shortcut that turns the Focus on or off
+pub fn set_focus(config: &FocusConfig, on: bool) -> Result<String, String> {
+    let shortcut = if on { &config.on_shortcut } else { &config.off_shortcut };
+    if shortcut.trim().is_empty() {
+        return Err(format!("No Shortcut configured to turn Focus {}", if on { "on" } else { "off" }));
+    }
+
+    let output = Command::new("shortcuts")
+        .arg("run")
+        .arg(shortcut)
+        .output()
+        .map_err(|e| format!("Failed to run Shortcut {:?}: {}", shortcut, e))?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Shortcut {:?} failed: {}", shortcut, error.trim()));
+    }
+
+    Ok(if on { "Focus turned on" } else { "Focus turned off" }.to_string())
+}
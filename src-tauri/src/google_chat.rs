@@ -0,0 +1,195 @@
+//! Google Chat status
+//!
+//! Google Chat shows "In a meeting" next to the user's name while their Google Calendar
+//! has a busy event in progress, and offers no API to set that status directly. So while
+//! a meeting is detected, SoundBreak adds such an event to the primary calendar through
+//! the Calendar API and deletes it afterwards. The API is reached with `curl` on a
+//! background thread.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const EVENTS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars/primary/events";
+// The event ends by itself after this long, in case it's never deleted
+const EVENT_LENGTH: chrono::Duration = chrono::Duration::hours(8);
+// Access tokens are renewed this long before they expire
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+static STATE: Mutex<GoogleChatState> = Mutex::new(GoogleChatState {
+    access_token: None,
+    event_id: None,
+});
+
+// Also serializes updates between threads
+struct GoogleChatState {
+    access_token: Option<(String, Instant)>,
+    // Calendar event SoundBreak added for the meeting in progress
+    event_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoogleChatConfig {
+    pub enabled: bool,
+    /// Client id of the "Desktop app" OAuth client used to sign in, see the README
+    pub client_id: String,
+    /// Google requires the secret of desktop clients too, though it isn't kept secret
+    pub client_secret: String,
+    /// Refresh token from signing in. Kept in the keychain; only read from `config.json`
+    /// when set there by hand.
+    pub refresh_token: String,
+    /// Title of the calendar event shown to people who can see the calendar
+    pub event_title: String,
+}
+
+impl Default for GoogleChatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_id: String::new(),
+            client_secret: String::new(),
+            refresh_token: String::new(),
+            event_title: "In a meeting".to_string(),
+        }
+    }
+}
+
+/// Adds or deletes the calendar event when the meeting state changed, on a background thread
+pub fn publish(config: &GoogleChatConfig, status: &MonitoringStatus) {
+    if !config.enabled || config.client_id.trim().is_empty() || config.refresh_token.is_empty() {
+        return;
+    }
+
+    let wanted = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let config = config.clone();
+    std::thread::spawn(move || {
+        let mut state = STATE.lock_or_recover();
+        if state.event_id.is_some() == wanted {
+            return;
+        }
+
+        let result = if wanted {
+            add_event(&config, &mut state)
+        } else {
+            delete_event(&config, &mut state)
+        };
+        if let Err(e) = result {
+            eprintln!("SoundBreak: Failed to update Google Chat status: {}", e);
+        }
+    });
+}
+
+/// Deletes the calendar event if SoundBreak added one, e.g. before quitting
+pub fn clear(config: &GoogleChatConfig) {
+    let mut state = STATE.lock_or_recover();
+    if let Err(e) = delete_event(config, &mut state) {
+        eprintln!("SoundBreak: Failed to clear Google Chat status: {}", e);
+    }
+}
+
+fn add_event(config: &GoogleChatConfig, state: &mut GoogleChatState) -> Result<(), String> {
+    let token = access_token(config, state)?;
+    let now = Utc::now();
+    let body = json!({
+        "summary": config.event_title,
+        "start": { "dateTime": now.to_rfc3339_opts(SecondsFormat::Secs, true) },
+        "end": { "dateTime": (now + EVENT_LENGTH).to_rfc3339_opts(SecondsFormat::Secs, true) },
+        "transparency": "opaque",
+        "reminders": { "useDefault": false },
+    });
+    let response = calendar_request(&token, "POST", EVENTS_URL, Some(&body))?;
+    let event: Value = serde_json::from_str(&response).map_err(|e| format!("Unexpected answer from Google: {}", e))?;
+    let Some(event_id) = event["id"].as_str() else {
+        return Err("No event id in Google's answer".to_string());
+    };
+    state.event_id = Some(event_id.to_string());
+    Ok(())
+}
+
+fn delete_event(config: &GoogleChatConfig, state: &mut GoogleChatState) -> Result<(), String> {
+    let Some(event_id) = state.event_id.clone() else {
+        return Ok(());
+    };
+    let token = access_token(config, state)?;
+    calendar_request(&token, "DELETE", &format!("{}/{}", EVENTS_URL, event_id), None)?;
+    state.event_id = None;
+    Ok(())
+}
+
+// A valid access token, redeeming the refresh token for a new one when needed
+fn access_token(config: &GoogleChatConfig, state: &mut GoogleChatState) -> Result<String, String> {
+    if let Some((token, expires_at)) = &state.access_token {
+        if Instant::now() + TOKEN_EXPIRY_MARGIN < *expires_at {
+            return Ok(token.clone());
+        }
+    }
+
+    // The refresh token is passed on stdin so it doesn't show up in the process list
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--data-urlencode", "grant_type=refresh_token"])
+        .args(["--data-urlencode", &format!("client_id={}", config.client_id.trim())])
+        .args(["--data-urlencode", &format!("client_secret={}", config.client_secret.trim())])
+        .args(["--data-urlencode", "refresh_token@-", TOKEN_URL])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.refresh_token.trim().as_bytes())
+            .map_err(|e| format!("Failed to pass the refresh token to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let response: Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected answer from Google: {}", e))?;
+    let Some(token) = response["access_token"].as_str() else {
+        let error = response["error_description"].as_str().unwrap_or("no access token in the answer");
+        return Err(format!("Failed to sign in to Google: {}", error));
+    };
+    let expires_in = Duration::from_secs(response["expires_in"].as_u64().unwrap_or(0));
+    state.access_token = Some((token.to_string(), Instant::now() + expires_in));
+    Ok(token.to_string())
+}
+
+// The access token is passed on stdin so it doesn't show up in the process list.
+// Returns the response body.
+fn calendar_request(token: &str, method: &str, url: &str, body: Option<&Value>) -> Result<String, String> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--request", method, "--header", "@-"]);
+    if let Some(body) = body {
+        command.args(["--header", "Content-Type: application/json", "--data", &body.to_string()]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Authorization: Bearer {}", token).map_err(|e| format!("Failed to pass the token to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,242 @@
+//! IOKit HID access
+//!
+//! The few IOKit calls the busylight, keyboard LED and mute button integrations need:
+//! listing connected devices, writing output reports, setting LEDs and watching input
+//! reports. Only built on macOS.
+
+use std::ffi::{c_char, c_void, CString};
+
+type CFTypeRef = *const c_void;
+type IOReturn = i32;
+
+const K_IOHID_REPORT_TYPE_OUTPUT: u32 = 1;
+const K_CF_NUMBER_SINT32_TYPE: isize = 3;
+const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+const USAGE_PAGE_LED: u32 = 0x08;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOHIDManagerCreate(allocator: CFTypeRef, options: u32) -> CFTypeRef;
+    fn IOHIDManagerSetDeviceMatching(manager: CFTypeRef, matching: CFTypeRef);
+    fn IOHIDManagerSetDeviceMatchingMultiple(manager: CFTypeRef, multiple: CFTypeRef);
+    fn IOHIDManagerCopyDevices(manager: CFTypeRef) -> CFTypeRef;
+    fn IOHIDManagerOpen(manager: CFTypeRef, options: u32) -> IOReturn;
+    fn IOHIDManagerRegisterInputReportCallback(manager: CFTypeRef, callback: InputReportCallback, context: *mut c_void);
+    fn IOHIDManagerScheduleWithRunLoop(manager: CFTypeRef, run_loop: CFTypeRef, mode: CFTypeRef);
+    fn IOHIDDeviceConformsTo(device: CFTypeRef, usage_page: u32, usage: u32) -> bool;
+    fn IOHIDDeviceOpen(device: CFTypeRef, options: u32) -> IOReturn;
+    fn IOHIDDeviceClose(device: CFTypeRef, options: u32) -> IOReturn;
+    fn IOHIDDeviceGetProperty(device: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+    fn IOHIDDeviceSetReport(device: CFTypeRef, report_type: u32, report_id: isize, report: *const u8, length: isize) -> IOReturn;
+    fn IOHIDDeviceCopyMatchingElements(device: CFTypeRef, matching: CFTypeRef, options: u32) -> CFTypeRef;
+    fn IOHIDDeviceSetValue(device: CFTypeRef, element: CFTypeRef, value: CFTypeRef) -> IOReturn;
+    fn IOHIDElementGetUsagePage(element: CFTypeRef) -> u32;
+    fn IOHIDElementGetUsage(element: CFTypeRef) -> u32;
+    fn IOHIDValueCreateWithIntegerValue(allocator: CFTypeRef, element: CFTypeRef, timestamp: u64, value: isize) -> CFTypeRef;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    static kCFTypeDictionaryKeyCallBacks: c_void;
+    static kCFTypeDictionaryValueCallBacks: c_void;
+    static kCFTypeArrayCallBacks: c_void;
+    static kCFRunLoopDefaultMode: CFTypeRef;
+
+    fn CFRelease(object: CFTypeRef);
+    fn CFSetGetCount(set: CFTypeRef) -> isize;
+    fn CFSetGetValues(set: CFTypeRef, values: *mut CFTypeRef);
+    fn CFArrayGetCount(array: CFTypeRef) -> isize;
+    fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+    fn CFArrayCreateMutable(allocator: CFTypeRef, capacity: isize, callbacks: *const c_void) -> CFTypeRef;
+    fn CFArrayAppendValue(array: CFTypeRef, value: CFTypeRef);
+    fn CFNumberCreate(allocator: CFTypeRef, number_type: isize, value: *const c_void) -> CFTypeRef;
+    fn CFNumberGetValue(number: CFTypeRef, number_type: isize, value: *mut c_void) -> bool;
+    fn CFStringCreateWithCString(allocator: CFTypeRef, string: *const c_char, encoding: u32) -> CFTypeRef;
+    fn CFDictionaryCreateMutable(allocator: CFTypeRef, capacity: isize, key_callbacks: *const c_void, value_callbacks: *const c_void) -> CFTypeRef;
+    fn CFDictionarySetValue(dictionary: CFTypeRef, key: CFTypeRef, value: CFTypeRef);
+    fn CFRunLoopGetCurrent() -> CFTypeRef;
+    fn CFRunLoopRun();
+}
+
+type InputReportCallback =
+    extern "C" fn(context: *mut c_void, result: IOReturn, sender: *mut c_void, report_type: u32, report_id: u32, report: *mut u8, length: isize);
+
+/// A connected HID device, valid during the `for_each_device` call that passed it
+pub struct Device(CFTypeRef);
+
+impl Device {
+    pub fn vendor_id(&self) -> Option<i32> {
+        unsafe { int_property(self.0, "VendorID") }
+    }
+
+    pub fn product_id(&self) -> Option<i32> {
+        unsafe { int_property(self.0, "ProductID") }
+    }
+
+    /// Whether the device is of the kind `usage` on `usage_page`, e.g. a keyboard
+    pub fn conforms_to(&self, usage_page: u32, usage: u32) -> bool {
+        unsafe { IOHIDDeviceConformsTo(self.0, usage_page, usage) }
+    }
+
+    /// Writes an output report to a device without numbered reports
+    pub fn write_report(&self, report: &[u8]) -> Result<(), String> {
+        self.with_open(|device| unsafe {
+            check(IOHIDDeviceSetReport(
+                device,
+                K_IOHID_REPORT_TYPE_OUTPUT,
+                0,
+                report.as_ptr(),
+                report.len() as isize,
+            ))
+        })
+    }
+
+    /// Turns the LED with `usage` on the LED usage page on or off, returning whether the
+    /// device has that LED
+    pub fn set_led(&self, usage: u32, on: bool) -> Result<bool, String> {
+        self.with_open(|device| unsafe {
+            let elements = IOHIDDeviceCopyMatchingElements(device, std::ptr::null(), 0);
+            if elements.is_null() {
+                return Ok(false);
+            }
+            let mut result = Ok(false);
+            for index in 0..CFArrayGetCount(elements) {
+                let element = CFArrayGetValueAtIndex(elements, index);
+                if IOHIDElementGetUsagePage(element) == USAGE_PAGE_LED && IOHIDElementGetUsage(element) == usage {
+                    let value = IOHIDValueCreateWithIntegerValue(std::ptr::null(), element, 0, on as isize);
+                    result = check(IOHIDDeviceSetValue(device, element, value)).map(|_| true);
+                    CFRelease(value);
+                    break;
+                }
+            }
+            CFRelease(elements);
+            result
+        })
+    }
+
+    fn with_open<T>(&self, f: impl FnOnce(CFTypeRef) -> Result<T, String>) -> Result<T, String> {
+        unsafe {
+            check(IOHIDDeviceOpen(self.0, 0))?;
+            let result = f(self.0);
+            IOHIDDeviceClose(self.0, 0);
+            result
+        }
+    }
+}
+
+/// Calls `f` with every connected HID device
+pub fn for_each_device(mut f: impl FnMut(&Device)) -> Result<(), String> {
+    unsafe {
+        let manager = IOHIDManagerCreate(std::ptr::null(), 0);
+        if manager.is_null() {
+            return Err("Failed to create an HID manager".to_string());
+        }
+        // Match every HID device; callers pick theirs out
+        IOHIDManagerSetDeviceMatching(manager, std::ptr::null());
+        let devices = IOHIDManagerCopyDevices(manager);
+        if !devices.is_null() {
+            let mut values = vec![std::ptr::null(); CFSetGetCount(devices) as usize];
+            CFSetGetValues(devices, values.as_mut_ptr());
+            for device in values {
+                f(&Device(device));
+            }
+            CFRelease(devices);
+        }
+        CFRelease(manager);
+    }
+    Ok(())
+}
+
+type InputWatch = Box<dyn FnMut(&[u8])>;
+
+/// Calls `on_report` with each input report from devices with one of the (vendor id,
+/// product id) pairs in `devices`, including devices connected later. Never returns
+/// unless watching fails to start, so call it on a thread of its own.
+pub fn watch_input_reports(devices: &[(i32, i32)], on_report: impl FnMut(&[u8]) + 'static) -> Result<(), String> {
+    unsafe {
+        let manager = IOHIDManagerCreate(std::ptr::null(), 0);
+        if manager.is_null() {
+            return Err("Failed to create an HID manager".to_string());
+        }
+
+        let matching = CFArrayCreateMutable(std::ptr::null(), 0, &kCFTypeArrayCallBacks);
+        for (vendor_id, product_id) in devices {
+            let ids = CFDictionaryCreateMutable(
+                std::ptr::null(),
+                0,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            );
+            set_int(ids, "VendorID", *vendor_id);
+            set_int(ids, "ProductID", *product_id);
+            CFArrayAppendValue(matching, ids);
+            CFRelease(ids);
+        }
+        IOHIDManagerSetDeviceMatchingMultiple(manager, matching);
+        CFRelease(matching);
+
+        // Lives as long as the run loop below, i.e. for good
+        let watch: *mut InputWatch = Box::into_raw(Box::new(Box::new(on_report)));
+        IOHIDManagerRegisterInputReportCallback(manager, input_report_callback, watch as *mut c_void);
+        IOHIDManagerScheduleWithRunLoop(manager, CFRunLoopGetCurrent(), kCFRunLoopDefaultMode);
+        if let Err(e) = check(IOHIDManagerOpen(manager, 0)) {
+            CFRelease(manager);
+            drop(Box::from_raw(watch));
+            return Err(e);
+        }
+        CFRunLoopRun();
+    }
+    Ok(())
+}
+
+extern "C" fn input_report_callback(
+    context: *mut c_void,
+    _result: IOReturn,
+    _sender: *mut c_void,
+    _report_type: u32,
+    _report_id: u32,
+    report: *mut u8,
+    length: isize,
+) {
+    if context.is_null() || report.is_null() {
+        return;
+    }
+    unsafe {
+        let on_report = &mut *(context as *mut InputWatch);
+        on_report(std::slice::from_raw_parts(report, length.max(0) as usize));
+    }
+}
+
+fn check(result: IOReturn) -> Result<(), String> {
+    match result {
+        0 => Ok(()),
+        error => Err(format!("IOKit error {:#x}", error)),
+    }
+}
+
+unsafe fn cf_string(value: &str) -> Option<CFTypeRef> {
+    let value = CString::new(value).ok()?;
+    let string = CFStringCreateWithCString(std::ptr::null(), value.as_ptr(), K_CF_STRING_ENCODING_UTF8);
+    (!string.is_null()).then_some(string)
+}
+
+unsafe fn set_int(dictionary: CFTypeRef, key: &str, value: i32) {
+    let Some(key) = cf_string(key) else {
+        return;
+    };
+    let number = CFNumberCreate(std::ptr::null(), K_CF_NUMBER_SINT32_TYPE, &value as *const i32 as *const c_void);
+    CFDictionarySetValue(dictionary, key, number);
+    CFRelease(key);
+    CFRelease(number);
+}
+
+unsafe fn int_property(device: CFTypeRef, key: &str) -> Option<i32> {
+    let key = cf_string(key)?;
+    let number = IOHIDDeviceGetProperty(device, key);
+    CFRelease(key);
+    if number.is_null() {
+        return None;
+    }
+    let mut value: i32 = 0;
+    CFNumberGetValue(number, K_CF_NUMBER_SINT32_TYPE, &mut value as *mut i32 as *mut c_void).then_some(value)
+}
@@ -0,0 +1,147 @@
+//! Home Assistant integration
+//!
+//! Mirrors the meeting, music and monitoring state into three binary sensors through
+//! Home Assistant's REST API, so automations can switch an "on air" light or hold room
+//! announcements during calls. Sensors are only posted when their state or attributes
+//! changed, on a background thread so a slow or unreachable server never delays checks.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+
+// Body last posted for each entity, so unchanged sensors aren't posted again.
+// Also serializes publishing between threads.
+static PUBLISHED: Mutex<BTreeMap<String, Value>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HomeAssistantConfig {
+    pub enabled: bool,
+    /// Base URL of the server, e.g. "http://homeassistant.local:8123"
+    pub url: String,
+    /// Long-lived access token. Kept in the keychain; only read from `config.json` when
+    /// set there by hand.
+    pub token: String,
+    /// Start of the sensor ids, e.g. "soundbreak" for `binary_sensor.soundbreak_meeting`
+    pub entity_prefix: String,
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            token: String::new(),
+            entity_prefix: "soundbreak".to_string(),
+        }
+    }
+}
+
+/// Posts the sensors that changed since they were last posted, on a background thread
+pub fn publish(config: &HomeAssistantConfig, status: &MonitoringStatus) {
+    if !config.enabled || config.url.trim().is_empty() {
+        return;
+    }
+
+    let config = config.clone();
+    let sensors = sensors(&config.entity_prefix, status);
+    std::thread::spawn(move || {
+        let mut published = PUBLISHED.lock_or_recover();
+        for (entity_id, body) in sensors {
+            if published.get(&entity_id) == Some(&body) {
+                continue;
+            }
+            match post_state(&config, &entity_id, &body) {
+                Ok(()) => {
+                    published.insert(entity_id, body);
+                }
+                Err(e) => eprintln!("SoundBreak: Failed to update {} in Home Assistant: {}", entity_id, e),
+            }
+        }
+    });
+}
+
+fn sensors(prefix: &str, status: &MonitoringStatus) -> Vec<(String, Value)> {
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let music = status.music_status.clone().unwrap_or_default();
+
+    vec![
+        (
+            format!("binary_sensor.{}_meeting", prefix),
+            json!({
+                "state": on_off(in_meeting),
+                "attributes": {
+                    "friendly_name": "SoundBreak Meeting",
+                    "device_class": "occupancy",
+                    "app": status.meeting_app(),
+                    "manual": status.manual_meeting,
+                    "duration_minutes": status.meeting_duration_secs.map(|secs| secs / 60),
+                },
+            }),
+        ),
+        (
+            format!("binary_sensor.{}_music", prefix),
+            json!({
+                "state": on_off(music.is_playing),
+                "attributes": {
+                    "friendly_name": "SoundBreak Music Playing",
+                    "device_class": "sound",
+                    "title": music.title,
+                    "artist": music.artist,
+                },
+            }),
+        ),
+        (
+            format!("binary_sensor.{}_monitoring", prefix),
+            json!({
+                "state": on_off(status.monitoring_state() == "active"),
+                "attributes": {
+                    "friendly_name": "SoundBreak Monitoring",
+                    "active": status.is_active,
+                    "snoozed_until": status.snoozed_until,
+                    "inhibited_by": status.inhibited_by,
+                    "within_schedule": status.within_schedule,
+                },
+            }),
+        ),
+    ]
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+// Sets an entity's state with `curl`, which handles both http and https. The token is
+// passed on stdin so it doesn't show up in the process list.
+fn post_state(config: &HomeAssistantConfig, entity_id: &str, body: &Value) -> Result<(), String> {
+    let url = format!("{}/api/states/{}", config.url.trim_end_matches('/'), entity_id);
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--header", "@-", "--header", "Content-Type: application/json"])
+        .args(["--data", &body.to_string(), "--output", "/dev/null", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Authorization: Bearer {}", config.token).map_err(|e| format!("Failed to pass the token to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
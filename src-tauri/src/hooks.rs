@@ -0,0 +1,98 @@
+//! User hook scripts
+//!
+//! Runs an executable of the user's choosing when a meeting starts or ends, the
+//! screen is locked or unlocked, or the user goes idle and comes back. Details about
+//! the transition are passed in `SOUNDBREAK_*` environment variables. Hooks that run
+//! past the timeout are killed so they can't stall monitoring.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest a hook may run before it is killed
+pub const MAX_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    /// Script run when a meeting starts
+    pub on_meeting_start: Option<String>,
+    /// Script run when a meeting ends
+    pub on_meeting_end: Option<String>,
+    /// Script run when the screen locks
+    pub on_screen_lock: Option<String>,
+    /// Script run when the screen is unlocked
+    pub on_screen_unlock: Option<String>,
+    /// Script run when the user has been idle long enough to count as away
+    pub on_user_away: Option<String>,
+    /// Script run when the user is back from being away
+    pub on_user_return: Option<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            on_meeting_start: None,
+            on_meeting_end: None,
+            on_screen_lock: None,
+            on_screen_unlock: None,
+            on_user_away: None,
+            on_user_return: None,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl HookConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(format!("Hook timeout must be at most {} s", MAX_TIMEOUT_SECS));
+        }
+        Ok(())
+    }
+}
+
+/// Runs `script` with `env` added to the environment, waiting at most `timeout` (and
+/// never longer than `MAX_TIMEOUT_SECS`)
+pub fn run_hook(script: &str, env: &[(&str, String)], timeout: Duration) -> Result<String, String> {
+    let timeout = timeout.min(Duration::from_secs(MAX_TIMEOUT_SECS));
+    let path = expand_home(script);
+    let mut child = Command::new(&path)
+        .envs(env.iter().map(|(key, value)| (*key, value.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run hook {:?}: {}", path, e))?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => {
+                return Ok(format!("Hook {:?} finished", path));
+            }
+            Ok(Some(status)) => {
+                return Err(format!("Hook {:?} exited with {}", path, status));
+            }
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Hook {:?} timed out after {} s", path, timeout.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for hook {:?}: {}", path, e)),
+        }
+    }
+}
+
+/// Resolves a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,152 @@
+//! Philips Hue "on air" light
+//!
+//! Turns the configured lights red through the Hue bridge's local API when a meeting
+//! starts, and back to the state each one was in afterwards. The bridge is reached with
+//! `curl`, like the Home Assistant integration.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+const REQUEST_TIMEOUT_SECS: u64 = 3;
+
+// State fields put back when restoring a light; which color fields apply depends on
+// the light's color mode
+const RESTORED_FIELDS: &[&str] = &["on", "bri"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HueConfig {
+    /// IP address or host name of the Hue bridge
+    pub bridge: String,
+    /// Application key created by pressing the bridge's link button, see the README
+    pub username: String,
+    /// Ids of the lights to turn red, as listed by the bridge
+    pub light_ids: Vec<String>,
+    /// Brightness while on air, from 1 to 254
+    pub brightness: u8,
+}
+
+impl Default for HueConfig {
+    fn default() -> Self {
+        Self {
+            bridge: String::new(),
+            username: String::new(),
+            light_ids: Vec::new(),
+            brightness: 254,
+        }
+    }
+}
+
+/// Turns the lights red, returning the state of each light turned red so `restore`
+/// can put it back
+pub fn on_air(config: &HueConfig) -> Result<(BTreeMap<String, Value>, String), String> {
+    check_config(config)?;
+
+    let mut previous_states = BTreeMap::new();
+    let mut errors = Vec::new();
+    for light_id in &config.light_ids {
+        let result = light_state(config, light_id).and_then(|state| {
+            set_light_state(config, light_id, &json!({ "on": true, "bri": config.brightness.max(1), "hue": 0, "sat": 254 }))?;
+            Ok(state)
+        });
+        match result {
+            Ok(state) => {
+                previous_states.insert(light_id.clone(), state);
+            }
+            Err(e) => errors.push(format!("light {}: {}", light_id, e)),
+        }
+    }
+
+    if previous_states.is_empty() {
+        return Err(format!("Failed to turn on the on-air light: {}", errors.join("; ")));
+    }
+    if !errors.is_empty() {
+        eprintln!("SoundBreak: Failed to turn on some on-air lights: {}", errors.join("; "));
+    }
+    let message = format!("Turned {} light(s) red", previous_states.len());
+    Ok((previous_states, message))
+}
+
+/// Puts lights turned red by `on_air` back into their previous state
+pub fn restore(config: &HueConfig, previous_states: &BTreeMap<String, Value>) -> Result<String, String> {
+    check_config(config)?;
+
+    let errors: Vec<String> = previous_states
+        .iter()
+        .filter_map(|(light_id, state)| {
+            set_light_state(config, light_id, state)
+                .err()
+                .map(|e| format!("light {}: {}", light_id, e))
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("Failed to restore the on-air light: {}", errors.join("; ")));
+    }
+    Ok(format!("Restored {} light(s)", previous_states.len()))
+}
+
+fn check_config(config: &HueConfig) -> Result<(), String> {
+    if config.bridge.trim().is_empty() || config.username.trim().is_empty() {
+        return Err("Set hue.bridge and hue.username in config.json first".to_string());
+    }
+    if config.light_ids.is_empty() {
+        return Err("No Hue lights configured".to_string());
+    }
+    Ok(())
+}
+
+fn light_url(config: &HueConfig, light_id: &str) -> String {
+    format!("http://{}/api/{}/lights/{}", config.bridge.trim(), config.username.trim(), light_id)
+}
+
+// The fields of a light's current state that `set_light_state` can put back
+fn light_state(config: &HueConfig, light_id: &str) -> Result<Value, String> {
+    let light = request("GET", &light_url(config, light_id), None)?;
+    let state = &light["state"];
+    let color_fields: &[&str] = match state["colormode"].as_str() {
+        Some("hs") => &["hue", "sat"],
+        Some("xy") => &["xy"],
+        Some("ct") => &["ct"],
+        _ => &[],
+    };
+
+    let restored: Map<String, Value> = RESTORED_FIELDS
+        .iter()
+        .chain(color_fields)
+        .filter_map(|field| state.get(*field).map(|value| (field.to_string(), value.clone())))
+        .collect();
+    Ok(Value::Object(restored))
+}
+
+fn set_light_state(config: &HueConfig, light_id: &str, state: &Value) -> Result<(), String> {
+    request("PUT", &format!("{}/state", light_url(config, light_id)), Some(state)).map(|_| ())
+}
+
+// The bridge answers errors with HTTP 200 and a list of `{"error": ...}` objects
+fn request(method: &str, url: &str, body: Option<&Value>) -> Result<Value, String> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--request", method]);
+    if let Some(body) = body {
+        command.args(["--data", &body.to_string()]);
+    }
+    let output = command.arg(url).output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let response: Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected answer from the Hue bridge: {}", e))?;
+    let error = response
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|item| item["error"]["description"].as_str());
+    match error {
+        Some(error) => Err(error.to_string()),
+        None => Ok(response),
+    }
+}
@@ -0,0 +1,189 @@
+//! Translations of tray menu texts, notifications and command results
+//!
+//! Messages are looked up by their English text, gettext style, so a message
+//! without a translation is shown in English. `{name}` placeholders are filled in
+//! by `tr_args`.
+//!
+//! Tray labels start with an emoji unless plain labels are turned on, for menu bar
+//! themes where emoji look out of place and for screen readers that read them out.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "zh-Hans")]
+    SimplifiedChinese,
+}
+
+static LANGUAGE: RwLock<Language> = RwLock::new(Language::English);
+
+/// Switches the language of every message looked up from now on
+pub fn set_language(language: Language) {
+    match LANGUAGE.write() {
+        Ok(mut current) => *current = language,
+        Err(poisoned) => *poisoned.into_inner() = language,
+    }
+}
+
+static PLAIN_LABELS: AtomicBool = AtomicBool::new(false);
+
+/// Switches tray labels to plain text, without emoji, from now on
+pub fn set_plain_labels(plain: bool) {
+    PLAIN_LABELS.store(plain, Ordering::Relaxed);
+}
+
+/// `text` prefixed with `emoji`, or just `text` with plain labels
+pub fn label(emoji: &str, text: impl Display) -> String {
+    if PLAIN_LABELS.load(Ordering::Relaxed) {
+        text.to_string()
+    } else {
+        format!("{} {}", emoji, text)
+    }
+}
+
+/// Label of an item that can be on or off: ✅ when on, `emoji` when off. Plain labels
+/// mark it with a check mark instead.
+pub fn check_label(checked: bool, emoji: &str, text: impl Display) -> String {
+    match (PLAIN_LABELS.load(Ordering::Relaxed), checked) {
+        (true, true) => format!("✓ {}", text),
+        (true, false) => text.to_string(),
+        (false, true) => format!("✅ {}", text),
+        (false, false) => format!("{} {}", emoji, text),
+    }
+}
+
+fn language() -> Language {
+    match LANGUAGE.read() {
+        Ok(language) => *language,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// Returns `message` in the configured language
+pub fn tr(message: &str) -> String {
+    let bundle = match language() {
+        Language::English => return message.to_string(),
+        Language::SimplifiedChinese => ZH_HANS,
+    };
+    bundle
+        .iter()
+        .find(|(english, _)| *english == message)
+        .map_or(message, |(_, translated)| translated)
+        .to_string()
+}
+
+/// Like `tr`, then replaces each `{name}` placeholder with its value
+pub fn tr_args(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(tr(message), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+const ZH_HANS: &[(&str, &str)] = &[
+    // Tray menu
+    ("Monitoring Active", "监控中"),
+    ("Monitoring Stopped", "监控已停止"),
+    ("Monitoring Degraded", "监控异常"),
+    ("Outside Monitoring Hours", "不在监控时段"),
+    ("Snoozed until {time} ({minutes} min left)", "暂停监控至 {time}（剩余 {minutes} 分钟）"),
+    ("Suspended while {app} runs", "{app} 运行中，已暂停自动操作"),
+    ("Pausing music in {secs}s — Cancel", "{secs} 秒后暂停音乐 — 取消"),
+    ("Music Playing", "音乐播放中"),
+    ("Music Paused", "音乐已暂停"),
+    ("Music Status Unknown", "音乐状态未知"),
+    ("No Track Info", "无曲目信息"),
+    ("In Meeting", "会议中"),
+    ("In Meeting (manual)", "会议中（手动）"),
+    ("In Meeting for {minutes} min", "会议已进行 {minutes} 分钟"),
+    ("Not in Meeting", "未在会议中"),
+    ("Meeting Status Unknown", "会议状态未知"),
+    ("Start Monitoring", "开始监控"),
+    ("Stop Monitoring", "停止监控"),
+    ("Pause Music Now", "立即暂停音乐"),
+    ("Resume Music", "恢复播放音乐"),
+    ("Skip This Meeting", "本次会议不处理"),
+    ("Start on Login", "登录时启动"),
+    ("Dry Run", "演练模式"),
+    ("Recent Activity", "最近活动"),
+    ("No recent activity", "暂无最近活动"),
+    ("{message} (failed)", "{message}（失败）"),
+    ("Meeting Apps", "会议应用"),
+    ("No meeting apps configured", "未配置会议应用"),
+    ("{app} (disabled)", "{app}（已停用）"),
+    ("Profile", "方案"),
+    ("Balanced", "均衡"),
+    ("Aggressive", "积极"),
+    ("Duck Only", "仅降低音量"),
+    ("Manual Resume", "手动恢复"),
+    ("Show SoundBreak", "显示 SoundBreak"),
+    ("Show Settings", "显示设置"),
+    ("Open Config File", "打开配置文件"),
+    ("Open Log Folder", "打开日志文件夹"),
+    ("Quit SoundBreak", "退出 SoundBreak"),
+    ("SoundBreak - Meeting Music Controller", "SoundBreak - 会议音乐控制"),
+    ("{app} meeting for {minutes} min", "{app} 会议已进行 {minutes} 分钟"),
+    ("{app} meeting", "{app} 会议"),
+    ("Last action: {action} at {time}", "上次操作：{action}（{time}）"),
+    // Notifications
+    ("Music paused — {reason}", "音乐已暂停 — {reason}"),
+    ("Music volume lowered — {reason}", "音乐音量已降低 — {reason}"),
+    ("Sound muted — {reason}", "已静音 — {reason}"),
+    ("Music resumed", "音乐已恢复播放"),
+    ("Music volume restored", "音乐音量已恢复"),
+    ("Sound unmuted", "已取消静音"),
+    ("Couldn't {action}: {error}", "无法{action}：{error}"),
+    ("pause music", "暂停音乐"),
+    ("resume music", "恢复播放音乐"),
+    ("lower the volume", "降低音量"),
+    ("restore the volume", "恢复音量"),
+    ("mute sound", "静音"),
+    ("unmute sound", "取消静音"),
+    ("Webhook delivered", "Webhook 已发送"),
+    ("call the webhook", "调用 Webhook"),
+    ("{app} meeting detected", "检测到 {app} 会议"),
+    ("Meeting started", "会议开始"),
+    ("Meeting ended", "会议结束"),
+    ("Screen locked", "屏幕已锁定"),
+    ("Screen unlocked", "屏幕已解锁"),
+    ("User away", "用户离开"),
+    ("User returned", "用户回来"),
+    ("Music played during meeting", "会议中音乐又开始播放"),
+    ("Long pause confirmed", "已确认长时间暂停"),
+    ("Resume prompt", "恢复播放提示"),
+    ("Pausing music in {secs}s — cancel from the menu bar", "{secs} 秒后暂停音乐 — 可在菜单栏取消"),
+    (
+        "Music has been paused for over {hours} hours — is your meeting still going on?",
+        "音乐已暂停超过 {hours} 小时 — 会议还在进行吗？",
+    ),
+    ("{app} meeting detected — music paused", "检测到 {app} 会议 — 音乐已暂停"),
+    ("Meeting started — music paused", "会议开始 — 音乐已暂停"),
+    ("Snooze 30m", "暂停监控 30 分钟"),
+    ("Keep Playing", "继续播放"),
+    ("OK", "好"),
+    ("Your meeting has ended. Resume music?", "会议已结束，要恢复播放音乐吗？"),
+    ("Not Now", "暂不"),
+    ("Resume Now", "立即恢复"),
+    ("Kept playing", "继续播放"),
+    ("Snoozed from notification", "已从通知暂停监控"),
+    ("Monitoring started", "监控已开始"),
+    ("Monitoring stopped", "监控已停止"),
+    // Command results
+    ("Meeting configuration updated successfully", "会议配置已更新"),
+    ("Check interval set to {ms} ms", "检查间隔已设为 {ms} 毫秒"),
+    ("Dry run enabled", "已开启演练模式"),
+    ("Dry run disabled", "已关闭演练模式"),
+    ("Switched to the {profile} profile", "已切换到“{profile}”方案"),
+    ("Configuration updated", "配置已更新"),
+    ("Settings exported to {path}", "设置已导出到 {path}"),
+    ("Settings imported from {path}", "已从 {path} 导入设置"),
+    ("Settings restored from backup", "已从备份恢复设置"),
+    ("Automation settings updated", "自动化设置已更新"),
+    ("Notification settings updated", "通知设置已更新"),
+    ("Tray menu updated", "托盘菜单已更新"),
+];
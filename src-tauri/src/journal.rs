@@ -0,0 +1,158 @@
+//! Daily markdown journal
+//!
+//! Writes a markdown summary of each day's meetings (times, apps, durations and how long
+//! music stayed paused) into a folder such as an Obsidian vault, for people who keep
+//! work logs. The day's file is rewritten after every meeting from the meetings kept in
+//! `journal.json`, which holds the last few days.
+
+use crate::config::ConfigManager;
+use crate::hooks::expand_home;
+use crate::sync::MutexExt;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// Days of meetings kept for rewriting their files
+const KEPT_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    pub enabled: bool,
+    /// Folder the files are written to, e.g. "~/Documents/Vault/Work Log"
+    pub folder: String,
+    /// File name as a `strftime` pattern of the day
+    pub file_name: String,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: String::new(),
+            file_name: "SoundBreak %Y-%m-%d.md".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub app: Option<String>,
+    pub paused_secs: u64,
+}
+
+/// Meetings of the last few days, persisted as `journal.json`.
+///
+/// Meetings are attributed to the day they started on.
+#[derive(Clone)]
+pub struct Journal {
+    days: Arc<Mutex<BTreeMap<NaiveDate, Vec<JournalEntry>>>>,
+    // Never written to disk, `journal.json` nor the Markdown files
+    in_memory: bool,
+}
+
+impl Journal {
+    pub fn load() -> Self {
+        let days = Self::get_journal_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            days: Arc::new(Mutex::new(days)),
+            in_memory: false,
+        }
+    }
+
+    /// An empty journal kept for the lifetime of the process only
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            days: Arc::default(),
+            in_memory: true,
+        }
+    }
+
+    /// Adds the meeting and rewrites its day's file, returning the file's path
+    pub fn record(&self, config: &JournalConfig, entry: JournalEntry) -> Result<PathBuf, String> {
+        if config.folder.trim().is_empty() {
+            return Err("Set journal.folder in config.json first".to_string());
+        }
+
+        let date = local_time(entry.started_at).date_naive();
+        let (snapshot, entries) = {
+            let mut days = self.days.lock_or_recover();
+            days.entry(date).or_default().push(entry);
+            let oldest = Local::now().date_naive() - ChronoDuration::days(KEPT_DAYS - 1);
+            days.retain(|day, _| *day >= oldest);
+            (days.clone(), days.get(&date).cloned().unwrap_or_default())
+        };
+
+        let folder = expand_home(config.folder.trim());
+        let path = folder.join(date.format(&config.file_name).to_string());
+        if self.in_memory {
+            return Ok(path);
+        }
+        if let Err(e) = Self::save(&snapshot) {
+            eprintln!("SoundBreak: Failed to save journal: {}", e);
+        }
+
+        std::fs::create_dir_all(&folder).map_err(|e| format!("Failed to create journal folder {:?}: {}", folder, e))?;
+        std::fs::write(&path, render(date, &entries)).map_err(|e| format!("Failed to write journal {:?}: {}", path, e))?;
+        Ok(path)
+    }
+
+    fn get_journal_path() -> Result<PathBuf, String> {
+        Ok(ConfigManager::get_app_dir()?.join("journal.json"))
+    }
+
+    fn save(days: &BTreeMap<NaiveDate, Vec<JournalEntry>>) -> Result<(), String> {
+        let journal_path = Self::get_journal_path()?;
+        let json_content = serde_json::to_string_pretty(days).map_err(|e| format!("Failed to serialize journal: {}", e))?;
+        std::fs::write(&journal_path, json_content).map_err(|e| format!("Failed to write journal: {}", e))
+    }
+}
+
+fn render(date: NaiveDate, entries: &[JournalEntry]) -> String {
+    let meeting_secs: u64 = entries.iter().map(|entry| entry.ended_at.saturating_sub(entry.started_at)).sum();
+    let paused_secs: u64 = entries.iter().map(|entry| entry.paused_secs).sum();
+
+    let mut markdown = format!("# Meetings on {}\n\n", date.format("%A, %Y-%m-%d"));
+    markdown += &format!("- Meetings: {}\n", entries.len());
+    markdown += &format!("- Time in meetings: {}\n", format_duration(meeting_secs));
+    markdown += &format!("- Music paused: {}\n\n", format_duration(paused_secs));
+    markdown += "| Start | End | Duration | App | Music paused |\n";
+    markdown += "|-------|-----|----------|-----|--------------|\n";
+    for entry in entries {
+        markdown += &format!(
+            "| {} | {} | {} | {} | {} |\n",
+            local_time(entry.started_at).format("%H:%M"),
+            local_time(entry.ended_at).format("%H:%M"),
+            format_duration(entry.ended_at.saturating_sub(entry.started_at)),
+            entry.app.as_deref().unwrap_or("Unknown").replace('|', "\\|"),
+            format_duration(entry.paused_secs),
+        );
+    }
+    markdown
+}
+
+// e.g. "1h 05m" or "42m"
+fn format_duration(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn local_time(timestamp: u64) -> DateTime<Local> {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.with_timezone(&Local))
+        .unwrap_or_else(Local::now)
+}
@@ -0,0 +1,110 @@
+//! Keyboard LED on-air cue
+//!
+//! Lights the Caps Lock LED of connected keyboards while in a meeting, as an ambient
+//! cue that doesn't need extra hardware. Mac keyboards have no colored backlight, and
+//! the Touch Bar and function keys have no LEDs apps can drive, so the Caps Lock LED is
+//! the one light available. It is set through IOKit without changing the Caps Lock
+//! state, and put back to match that state after the meeting. Pressing Caps Lock during
+//! a meeting sets the LED from the key again.
+
+#[cfg(target_os = "macos")]
+use crate::hid;
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+// Whether the LED is lit for a meeting. Also serializes updates between threads.
+static LIT: Mutex<bool> = Mutex::new(false);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyboardLedConfig {
+    pub enabled: bool,
+}
+
+/// Lights the LED or puts it back when the meeting state changed, on a background thread
+pub fn publish(config: &KeyboardLedConfig, status: &MonitoringStatus) {
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let wanted = config.enabled && in_meeting;
+    // A busy lock means an update is under way
+    if LIT.try_lock().is_ok_and(|lit| *lit == wanted) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut lit = LIT.lock_or_recover();
+        if *lit == wanted {
+            return;
+        }
+        let on = wanted || caps_lock_on();
+        match set_caps_lock_led(on) {
+            Ok(0) => eprintln!("SoundBreak: No keyboard with a Caps Lock LED found"),
+            Ok(_) => {}
+            Err(e) => eprintln!("SoundBreak: Failed to set the keyboard LED: {}", e),
+        }
+        // Remembered even when it failed, so the error isn't logged on every status change
+        *lit = wanted;
+    });
+}
+
+/// Puts the LED back if it is lit for a meeting, e.g. before quitting
+pub fn turn_off() {
+    let mut lit = LIT.lock_or_recover();
+    if !*lit {
+        return;
+    }
+    if let Err(e) = set_caps_lock_led(caps_lock_on()) {
+        eprintln!("SoundBreak: Failed to turn off the keyboard LED: {}", e);
+    }
+    *lit = false;
+}
+
+// Sets the Caps Lock LED of every keyboard, returning how many were set
+#[cfg(target_os = "macos")]
+fn set_caps_lock_led(on: bool) -> Result<usize, String> {
+    const USAGE_PAGE_GENERIC_DESKTOP: u32 = 0x01;
+    const USAGE_KEYBOARD: u32 = 0x06;
+    const USAGE_LED_CAPS_LOCK: u32 = 0x02;
+
+    let mut written = 0;
+    let mut errors = Vec::new();
+    hid::for_each_device(|device| {
+        if !device.conforms_to(USAGE_PAGE_GENERIC_DESKTOP, USAGE_KEYBOARD) {
+            return;
+        }
+        match device.set_led(USAGE_LED_CAPS_LOCK, on) {
+            Ok(true) => written += 1,
+            Ok(false) => {}
+            Err(e) => errors.push(e),
+        }
+    })?;
+
+    if written == 0 && !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(written)
+}
+
+#[cfg(target_os = "macos")]
+fn caps_lock_on() -> bool {
+    const K_CG_EVENT_SOURCE_STATE_HID_SYSTEM: i32 = 1;
+    const K_CG_EVENT_FLAG_MASK_ALPHA_SHIFT: u64 = 0x0001_0000;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceFlagsState(state: i32) -> u64;
+    }
+
+    unsafe { CGEventSourceFlagsState(K_CG_EVENT_SOURCE_STATE_HID_SYSTEM) & K_CG_EVENT_FLAG_MASK_ALPHA_SHIFT != 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_caps_lock_led(_on: bool) -> Result<usize, String> {
+    Err("Keyboard LEDs are only supported on macOS".to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn caps_lock_on() -> bool {
+    false
+}
@@ -1,30 +1,102 @@
+mod action_history;
+mod actions;
+mod audit_log;
+mod busylight;
+mod calendar;
+mod cli;
+mod companion;
 mod config;
+mod config_watcher;
+#[cfg(unix)]
+mod control;
+mod deep_link;
+mod discord;
+mod focus;
+mod google_chat;
+#[cfg(target_os = "macos")]
+mod hid;
+mod home_assistant;
+mod hooks;
+mod hue;
+mod i18n;
+mod journal;
+mod keyboard_led;
+mod local_api;
+mod log_file;
 mod meeting_detector;
 mod music_controller;
+mod notifications;
+mod onboarding;
 mod monitoring_service;
+mod monitoring_state;
+mod mqtt;
+mod mute_button;
+mod osc;
+mod peer;
+mod permissions;
+mod power;
+mod presence;
+mod profiles;
+mod prompt;
+mod schedule;
+mod secrets;
+#[cfg(desktop)]
+mod shortcuts;
+mod statistics;
+mod status_file;
+mod sync;
+mod teams;
+mod webhooks;
 
-use meeting_detector::{MeetingStatus, MeetingConfig};
+use meeting_detector::{MeetingConfig, MeetingDetector, MeetingStatus, ProcessCheck};
 use music_controller::{MusicAction, MusicStatus};
 use monitoring_service::{MonitoringService, MonitoringStatus};
+use std::collections::BTreeMap;
 use std::sync::Mutex;
-use tauri::{Manager, menu::{MenuBuilder, MenuItem}, tray::{TrayIcon, TrayIconBuilder}};
-
-#[cfg(not(debug_assertions))]
+use sync::MutexExt;
+use action_history::ActionRecord;
+use i18n::{check_label, label, tr, tr_args};
+use config::{AppConfig, ConfigBackup, ConfigDifference, ConfigManager, ConfigOverrides};
+use notifications::NotificationConfig;
+use log_file::{LogEntry, LogLevel};
+use onboarding::Onboarding;
+use permissions::{PermissionKind, PermissionState, PermissionStatus};
+use profiles::Profile;
 use tauri::Emitter;
+use tauri::{Manager, menu::{MenuBuilder, MenuItem, Submenu}, tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent}};
 
 // Global monitoring service state
 struct AppState {
-    monitoring_service: Mutex<MonitoringService>,
+    // Internally synchronized; clone it to use it from other threads
+    monitoring_service: MonitoringService,
     tray_icon: Mutex<Option<TrayIcon>>,
     last_status: Mutex<Option<MonitoringStatus>>,
     // Store menu item references for efficient updates
     monitoring_status_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     music_status_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    now_playing_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     meeting_status_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    skip_meeting_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     toggle_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     autostart_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    dry_run_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    activity_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
+    meeting_apps_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
+    profile_items: Mutex<Vec<(Profile, MenuItem<tauri::Wry>)>>,
+    // First launch, until the user finishes or skips onboarding
+    onboarding_pending: Mutex<bool>,
 }
 
+// Number of action history entries shown in the tray's "Recent Activity" submenu
+const RECENT_ACTIVITY_ITEMS: usize = 8;
+
+// Size of the status popover opened by left-clicking the tray icon, in logical pixels
+const POPOVER_WIDTH: f64 = 300.0;
+const POPOVER_HEIGHT: f64 = 260.0;
+
+// Longer track descriptions are cut off so the tray menu doesn't get too wide
+const NOW_PLAYING_MAX_CHARS: usize = 48;
+
 // Helper function to check if status has changed significantly
 fn has_status_changed(old_status: &Option<MonitoringStatus>, new_status: &MonitoringStatus) -> bool {
     match old_status {
@@ -32,75 +104,438 @@ fn has_status_changed(old_status: &Option<MonitoringStatus>, new_status: &Monito
         Some(old) => {
             // Check if any significant fields have changed
             old.is_active != new_status.is_active ||
+            old.within_schedule != new_status.within_schedule ||
+            old.snoozed_until != new_status.snoozed_until ||
+            snooze_minutes_left(old) != snooze_minutes_left(new_status) ||
+            old.inhibited_by != new_status.inhibited_by ||
+            old.dry_run != new_status.dry_run ||
+            old.pause_pending_until != new_status.pause_pending_until ||
+            old.manual_meeting != new_status.manual_meeting ||
+            old.meeting_skipped != new_status.meeting_skipped ||
+            old.profile != new_status.profile ||
+            old.meeting_duration_secs.map(|secs| secs / 60) != new_status.meeting_duration_secs.map(|secs| secs / 60) ||
+            old.health.is_healthy() != new_status.health.is_healthy() ||
             old.meeting_status.as_ref().map(|m| m.in_meeting) != new_status.meeting_status.as_ref().map(|m| m.in_meeting) ||
-            old.music_status.as_ref().map(|m| m.is_playing) != new_status.music_status.as_ref().map(|m| m.is_playing)
+            old.music_status.as_ref().map(|m| m.is_playing) != new_status.music_status.as_ref().map(|m| m.is_playing) ||
+            running_meeting_apps(old) != running_meeting_apps(new_status) ||
+            now_playing_text(old) != now_playing_text(new_status) ||
+            old.last_action != new_status.last_action
+        }
+    }
+}
+
+// Opens a file in its default app, or a folder in Finder
+fn open_path(app: &tauri::AppHandle, path: Result<std::path::PathBuf, String>) {
+    use tauri_plugin_opener::OpenerExt;
+
+    let result = path.and_then(|path| {
+        app.opener()
+            .open_path(path.to_string_lossy(), None::<&str>)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    });
+    if let Err(e) = result {
+        eprintln!("SoundBreak: {}", e);
+    }
+}
+
+// "Start on Login" item text, checked when autostart is enabled
+fn autostart_text(enabled: bool) -> String {
+    check_label(enabled, "🚀", tr("Start on Login"))
+}
+
+// Current track as "Title — Artist" for the tray, shortened if needed
+fn now_playing_text(status: &MonitoringStatus) -> String {
+    let music = status.music_status.as_ref();
+    let track = match (music.and_then(|m| m.title.as_deref()), music.and_then(|m| m.artist.as_deref())) {
+        (Some(title), Some(artist)) => format!("{} — {}", title, artist),
+        (Some(title), None) => title.to_string(),
+        _ => return label("🎶", tr("No Track Info")),
+    };
+
+    if track.chars().count() > NOW_PLAYING_MAX_CHARS {
+        let shortened: String = track.chars().take(NOW_PLAYING_MAX_CHARS - 1).collect();
+        label("🎶", format!("{}…", shortened.trim_end()))
+    } else {
+        label("🎶", track)
+    }
+}
+
+// Configured meeting apps that are running, for the "Meeting Apps" submenu
+fn running_meeting_apps(status: &MonitoringStatus) -> Vec<&str> {
+    status
+        .meeting_status
+        .iter()
+        .flat_map(|meeting| &meeting.active_apps)
+        .filter(|app| app.is_running)
+        .map(|app| app.process_name.as_str())
+        .collect()
+}
+
+// Updates the tray only when something it shows has changed since the last update
+//
+// Called from the monitoring thread, so the tray is updated on the main thread without
+// waiting for it: the main thread may itself be waiting for the monitoring thread.
+fn refresh_tray_if_changed(app: &tauri::AppHandle, status: &MonitoringStatus) {
+    let app_state = app.state::<AppState>();
+    {
+        let mut last_status = app_state.last_status.lock_or_recover();
+        if !has_status_changed(&last_status, status) {
+            return;
         }
+        *last_status = Some(status.clone());
+    }
+
+    let app_for_tray = app.clone();
+    let status_for_tray = status.clone();
+    let _ = app.run_on_main_thread(move || {
+        let _ = update_tray_menu_text(&app_for_tray, &status_for_tray);
+    });
+    if let Err(e) = status_file::write(status) {
+        eprintln!("SoundBreak: {}", e);
+    }
+    let app_config = app_state.monitoring_service.get_app_config();
+    home_assistant::publish(&app_config.home_assistant, status);
+    mqtt::publish(&app_config.mqtt, status);
+    osc::publish(&app_config.osc, status);
+    discord::publish(&app_config.discord, status);
+    busylight::publish(&app_config.busylight, status);
+    keyboard_led::publish(&app_config.keyboard_led, status);
+    google_chat::publish(&app_config.google_chat, status);
+    let service = app_state.monitoring_service.clone();
+    teams::publish(&app_config.teams, status, move |token| service.set_teams_refresh_token(token));
+}
+
+// Tray icon variant for the current state, so it shows at a glance without opening the menu.
+// The icons are black-on-transparent templates that macOS tints to match the menu bar.
+fn tray_icon_for(status: &MonitoringStatus) -> tauri::image::Image<'static> {
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let suspended = status.snoozed_until.is_some() || status.inhibited_by.is_some() || !status.within_schedule;
+    if !status.is_active || suspended {
+        tauri::include_image!("icons/tray/stopped.png")
+    } else if !status.health.is_healthy() {
+        tauri::include_image!("icons/tray/error.png")
+    } else if in_meeting {
+        tauri::include_image!("icons/tray/meeting.png")
+    } else {
+        tauri::include_image!("icons/tray/normal.png")
     }
 }
 
+// Local time of day for a unix timestamp, e.g. "14:05"
+fn clock_time(secs: u64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// Whole minutes until a snooze ends, rounded up so the last minute reads "1 min left"
+fn snooze_minutes_left(status: &MonitoringStatus) -> Option<u64> {
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    status.snoozed_until.map(|until| until.saturating_sub(now_secs).div_ceil(60))
+}
+
+// Tray tooltip: the monitoring state as shown in the menu, the meeting in progress and
+// the last action
+fn tray_tooltip(status: &MonitoringStatus, monitoring_status_text: &str) -> String {
+    let mut lines = vec![format!("SoundBreak — {}", monitoring_status_text)];
+
+    if status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting) {
+        let app = running_meeting_apps(status).into_iter().next();
+        let minutes = status.meeting_duration_secs.map(|secs| secs / 60);
+        lines.push(match (app, minutes) {
+            (Some(app), Some(minutes)) => tr_args("{app} meeting for {minutes} min", &[("app", &app), ("minutes", &minutes)]),
+            (Some(app), None) => tr_args("{app} meeting", &[("app", &app)]),
+            (None, Some(minutes)) => tr_args("In Meeting for {minutes} min", &[("minutes", &minutes)]),
+            (None, None) => tr("In Meeting"),
+        });
+    }
+
+    if let Some(action) = &status.last_action {
+        lines.push(tr_args(
+            "Last action: {action} at {time}",
+            &[("action", &action.message), ("time", &clock_time(action.timestamp))],
+        ));
+    }
+
+    lines.join("\n")
+}
+
+// One line for terminals and launchers such as Raycast: the monitoring state, then the
+// meeting and music
+fn status_summary(status: &MonitoringStatus) -> String {
+    let snooze = status.snoozed_until.zip(snooze_minutes_left(status));
+    let monitoring = match (status.monitoring_state(), snooze, &status.inhibited_by) {
+        ("snoozed", Some((until, minutes)), _) => {
+            tr_args("Snoozed until {time} ({minutes} min left)", &[("time", &clock_time(until)), ("minutes", &minutes)])
+        }
+        ("suspended", _, Some(app)) => tr_args("Suspended while {app} runs", &[("app", app)]),
+        ("outside_hours", _, _) => tr("Outside Monitoring Hours"),
+        ("stopped", _, _) => tr("Monitoring Stopped"),
+        _ => tr("Monitoring Active"),
+    };
+
+    let mut parts = vec![monitoring];
+    if status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting) {
+        parts.push(match status.meeting_app() {
+            Some(app) => tr_args("{app} meeting", &[("app", &app)]),
+            None => tr("In Meeting"),
+        });
+    }
+    parts.push(match &status.music_status {
+        Some(music) if music.is_playing => tr("Music Playing"),
+        Some(_) => tr("Music Paused"),
+        None => tr("Music Status Unknown"),
+    });
+    parts.join(" · ")
+}
+
+// Text next to the tray icon with `tray_title`: the minutes left in a snooze or spent in
+// the current meeting, and nothing otherwise to keep the menu bar tidy
+fn tray_title(status: &MonitoringStatus) -> Option<String> {
+    if !status.is_active {
+        return None;
+    }
+    if let Some(minutes) = snooze_minutes_left(status) {
+        return Some(label("💤", format!("{}m", minutes)));
+    }
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let minutes = status.meeting_duration_secs.unwrap_or_default() / 60;
+    in_meeting.then(|| label("🎤", format!("{}m", minutes)))
+}
+
 // Helper function to update tray menu with current status (using set_text on existing items)
 fn update_tray_menu_text(app: &tauri::AppHandle, status: &MonitoringStatus) -> Result<(), Box<dyn std::error::Error>> {
     let app_state = app.state::<AppState>();
 
-    let monitoring_status_text = if status.is_active {
-        "✅ Monitoring Active"
-    } else {
-        "⏸️ Monitoring Stopped"
+    let snoozed_text = status.snoozed_until.zip(snooze_minutes_left(status)).map(|(until, minutes)| {
+        label(
+            "💤",
+            tr_args("Snoozed until {time} ({minutes} min left)", &[("time", &clock_time(until)), ("minutes", &minutes)]),
+        )
+    });
+
+    let inhibited_text = status
+        .inhibited_by
+        .as_ref()
+        .map(|app| label("🚫", tr_args("Suspended while {app} runs", &[("app", app)])));
+
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let pending_pause_text = status
+        .pause_pending_until
+        .map(|until| label("⏳", tr_args("Pausing music in {secs}s — Cancel", &[("secs", &until.saturating_sub(now_secs))])));
+
+    let monitoring_status_text = match (&pending_pause_text, &snoozed_text, &inhibited_text) {
+        (Some(text), _, _) => text.clone(),
+        (None, Some(text), _) if status.is_active => text.clone(),
+        (None, None, Some(text)) if status.is_active => text.clone(),
+        _ if status.is_active && !status.within_schedule => label("🕘", tr("Outside Monitoring Hours")),
+        _ if status.is_active && !status.health.is_healthy() => label("⚠️", tr("Monitoring Degraded")),
+        _ if status.is_active => label("✅", tr("Monitoring Active")),
+        _ => label("⏸️", tr("Monitoring Stopped")),
     };
 
     let music_status_text = match &status.music_status {
-        Some(music) if music.is_playing => "🎵 Music Playing",
-        Some(_) => "⏸️ Music Paused",
-        None => "❓ Music Status Unknown",
+        Some(music) if music.is_playing => label("🎵", tr("Music Playing")),
+        Some(_) => label("⏸️", tr("Music Paused")),
+        None => label("❓", tr("Music Status Unknown")),
     };
 
-    let meeting_status_text = match &status.meeting_status {
-        Some(meeting) if meeting.in_meeting => "🎤 In Meeting",
-        Some(_) => "📵 Not in Meeting",
-        None => "❓ Meeting Status Unknown",
+    let meeting_status_text = match (&status.meeting_status, status.meeting_duration_secs) {
+        (Some(meeting), _) if meeting.in_meeting && status.manual_meeting => label("🎤", tr("In Meeting (manual)")),
+        (Some(meeting), Some(secs)) if meeting.in_meeting => label("🎤", tr_args("In Meeting for {minutes} min", &[("minutes", &(secs / 60))])),
+        (Some(meeting), None) if meeting.in_meeting => label("🎤", tr("In Meeting")),
+        (Some(_), _) => label("📵", tr("Not in Meeting")),
+        (None, _) => label("❓", tr("Meeting Status Unknown")),
     };
 
     let toggle_text = if status.is_active {
-        "⏸️ Stop Monitoring"
+        label("⏸️", tr("Stop Monitoring"))
     } else {
-        "▶️ Start Monitoring"
+        label("▶️", tr("Start Monitoring"))
     };
 
     // Update menu item texts using set_text()
-    if let Some(item) = app_state.monitoring_status_item.lock().unwrap().as_ref() {
-        item.set_text(monitoring_status_text)?;
+    if let Some(item) = app_state.monitoring_status_item.lock_or_recover().as_ref() {
+        item.set_text(&monitoring_status_text)?;
+        // Clickable only while it offers to cancel a pending pause
+        item.set_enabled(pending_pause_text.is_some())?;
     }
 
-    if let Some(item) = app_state.music_status_item.lock().unwrap().as_ref() {
-        item.set_text(music_status_text)?;
+    if let Some(item) = app_state.music_status_item.lock_or_recover().as_ref() {
+        item.set_text(&music_status_text)?;
     }
 
-    if let Some(item) = app_state.meeting_status_item.lock().unwrap().as_ref() {
-        item.set_text(meeting_status_text)?;
+    if let Some(item) = app_state.now_playing_item.lock_or_recover().as_ref() {
+        item.set_text(now_playing_text(status))?;
     }
 
-    if let Some(item) = app_state.toggle_item.lock().unwrap().as_ref() {
-        item.set_text(toggle_text)?;
+    if let Some(item) = app_state.meeting_status_item.lock_or_recover().as_ref() {
+        item.set_text(&meeting_status_text)?;
     }
 
+    if let Some(tray) = app_state.tray_icon.lock_or_recover().as_ref() {
+        tray.set_tooltip(Some(tray_tooltip(status, &monitoring_status_text)))?;
+        let show_title = app_state.monitoring_service.get_app_config().tray_title;
+        tray.set_title(tray_title(status).filter(|_| show_title))?;
+        tray.set_icon(Some(tray_icon_for(status)))?;
+        // macOS forgets the template flag whenever the icon changes
+        tray.set_icon_as_template(true)?;
+    }
+
+    if let Some(item) = app_state.skip_meeting_item.lock_or_recover().as_ref() {
+        let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+        item.set_text(check_label(status.meeting_skipped, "⏭️", tr("Skip This Meeting")))?;
+        item.set_enabled(in_meeting && !status.meeting_skipped)?;
+    }
+
+    if let Some(item) = app_state.toggle_item.lock_or_recover().as_ref() {
+        item.set_text(&toggle_text)?;
+    }
+
+    if let Some(item) = app_state.dry_run_item.lock_or_recover().as_ref() {
+        item.set_text(check_label(status.dry_run, "🧪", tr("Dry Run")))?;
+    }
+
+    for (profile, item) in app_state.profile_items.lock_or_recover().iter() {
+        item.set_text(check_label(status.profile == Some(*profile), "⬜", tr(profile.label())))?;
+    }
+
+    update_activity_submenu(app)?;
+    update_meeting_apps_submenu(app, status)?;
+
     Ok(())
 }
 
+// Helper function to rebuild the "Meeting Apps" submenu; clicking an app turns it off or on
+fn update_meeting_apps_submenu(app: &tauri::AppHandle, status: &MonitoringStatus) -> Result<(), Box<dyn std::error::Error>> {
+    let app_state = app.state::<AppState>();
+    let app_config = app_state.monitoring_service.get_app_config();
+    let disabled_apps = app_config.disabled_meeting_apps();
+    let running_apps = running_meeting_apps(status);
+
+    let submenu_guard = app_state.meeting_apps_submenu.lock_or_recover();
+    let Some(submenu) = submenu_guard.as_ref() else {
+        return Ok(());
+    };
+
+    while submenu.remove_at(0)?.is_some() {}
+
+    if app_config.meeting_config.process_names.is_empty() {
+        let item = MenuItem::new(app, tr("No meeting apps configured"), false, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+
+    for process_name in &app_config.meeting_config.process_names {
+        let text = if disabled_apps.contains(process_name) {
+            label("🚫", tr_args("{app} (disabled)", &[("app", process_name)]))
+        } else {
+            check_label(running_apps.contains(&process_name.as_str()), "⬚", process_name)
+        };
+        let item = MenuItem::with_id(app, format!("meeting_app:{}", process_name), text, true, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+
+    Ok(())
+}
+
+// Helper function to rebuild the "Recent Activity" submenu from the action history
+fn update_activity_submenu(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let app_state = app.state::<AppState>();
+    let history = app_state.monitoring_service.get_action_history(RECENT_ACTIVITY_ITEMS);
+
+    let submenu_guard = app_state.activity_submenu.lock_or_recover();
+    let Some(submenu) = submenu_guard.as_ref() else {
+        return Ok(());
+    };
+
+    while submenu.remove_at(0)?.is_some() {}
+
+    if history.is_empty() {
+        let item = MenuItem::new(app, tr("No recent activity"), false, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+
+    for record in &history {
+        let time = chrono::DateTime::from_timestamp(record.timestamp as i64, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%H:%M").to_string())
+            .unwrap_or_default();
+        let message = if record.success {
+            label("✅", &record.message)
+        } else {
+            label("⚠️", tr_args("{message} (failed)", &[("message", &record.message)]))
+        };
+        let item = MenuItem::new(app, format!("{} {}", time, message), false, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+
+    Ok(())
+}
+
+// Shows the status popover below the tray icon, or hides it if it is already showing.
+// The window is created on first use and hidden again whenever it loses focus.
+fn toggle_popover(app: &tauri::AppHandle, tray_rect: tauri::Rect) -> Result<(), Box<dyn std::error::Error>> {
+    let popover = match app.get_webview_window("popover") {
+        Some(popover) => popover,
+        None => {
+            let popover = tauri::WebviewWindowBuilder::new(app, "popover", tauri::WebviewUrl::App("popover".into()))
+                .title("SoundBreak")
+                .inner_size(POPOVER_WIDTH, POPOVER_HEIGHT)
+                .resizable(false)
+                .decorations(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .visible(false)
+                .build()?;
+            let popover_clone = popover.clone();
+            popover.on_window_event(move |event| {
+                if let tauri::WindowEvent::Focused(false) = event {
+                    let _ = popover_clone.hide();
+                }
+            });
+            popover
+        }
+    };
+
+    if popover.is_visible()? {
+        popover.hide()?;
+        return Ok(());
+    }
+
+    // Centered horizontally under the icon
+    let scale_factor = popover.scale_factor()?;
+    let tray_position = tray_rect.position.to_physical::<f64>(scale_factor);
+    let tray_size = tray_rect.size.to_physical::<f64>(scale_factor);
+    let x = tray_position.x + tray_size.width / 2.0 - POPOVER_WIDTH * scale_factor / 2.0;
+    let y = tray_position.y + tray_size.height;
+    popover.set_position(tauri::PhysicalPosition::new(x.max(0.0), y))?;
+    popover.show()?;
+    popover.set_focus()?;
+    Ok(())
+}
+
+// Runs blocking work (osascript, pgrep, saving settings through the keychain) without
+// holding up the async runtime that serves other commands
+async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| format!("Background task failed: {}", e))
+}
+
 // Tauri commands
 #[tauri::command]
 async fn start_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let result = {
-        let service = state.monitoring_service.lock().unwrap();
-        service.start_monitoring()
-    };
+    let service = state.monitoring_service.clone();
+    let result = service.start_monitoring();
 
     // Update tray menu after starting monitoring
     if result.is_ok() {
-        let status = {
-            let service = state.monitoring_service.lock().unwrap();
-            service.get_status()
-        };
-        let _ = update_tray_menu_text(&app, &status);
+        let _ = update_tray_menu_text(&app, &service.get_status());
     }
 
     result
@@ -108,18 +543,16 @@ async fn start_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppStat
 
 #[tauri::command]
 async fn stop_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let result = {
-        let service = state.monitoring_service.lock().unwrap();
-        service.stop_monitoring()
-    };
+    let service = state.monitoring_service.clone();
+    let result = run_blocking({
+        let service = service.clone();
+        move || service.stop_monitoring()
+    })
+    .await?;
 
     // Update tray menu after stopping monitoring
     if result.is_ok() {
-        let status = {
-            let service = state.monitoring_service.lock().unwrap();
-            service.get_status()
-        };
-        let _ = update_tray_menu_text(&app, &status);
+        let _ = update_tray_menu_text(&app, &service.get_status());
     }
 
     result
@@ -127,33 +560,111 @@ async fn stop_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppState
 
 #[tauri::command]
 async fn toggle_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let result = {
-        let service = state.monitoring_service.lock().unwrap();
-        service.toggle_monitoring()
-    };
+    let service = state.monitoring_service.clone();
+    let result = run_blocking({
+        let service = service.clone();
+        move || service.toggle_monitoring()
+    })
+    .await?;
 
     // Update tray menu after toggling monitoring
     if result.is_ok() {
-        let status = {
-            let service = state.monitoring_service.lock().unwrap();
-            service.get_status()
-        };
-        let _ = update_tray_menu_text(&app, &status);
+        let _ = update_tray_menu_text(&app, &service.get_status());
     }
 
     result
 }
 
+/// Everything the UI shows, fetched once on load; after that it follows the
+/// `monitoring-status-changed` and `config-changed` events instead of polling
+#[derive(serde::Serialize)]
+struct StatusSnapshot {
+    status: MonitoringStatus,
+    config: AppConfig,
+}
+
+#[tauri::command]
+async fn subscribe_status(state: tauri::State<'_, AppState>) -> Result<StatusSnapshot, String> {
+    let service = &state.monitoring_service;
+    Ok(StatusSnapshot {
+        status: service.get_status(),
+        config: service.get_app_config(),
+    })
+}
+
 #[tauri::command]
 async fn get_monitoring_status(state: tauri::State<'_, AppState>) -> Result<MonitoringStatus, String> {
-    let service = state.monitoring_service.lock().unwrap();
-    Ok(service.get_status())
+    Ok(state.monitoring_service.get_status())
+}
+
+#[tauri::command]
+async fn check_now(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<MonitoringStatus, String> {
+    let service = state.monitoring_service.clone();
+    let status = run_blocking(move || {
+        service.tick();
+        service.get_status()
+    })
+    .await?;
+    let _ = update_tray_menu_text(&app, &status);
+    Ok(status)
+}
+
+#[tauri::command]
+async fn snooze_monitoring(app: tauri::AppHandle, state: tauri::State<'_, AppState>, minutes: u64) -> Result<String, String> {
+    let service = &state.monitoring_service;
+    let result = service.snooze(minutes);
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    result
+}
+
+#[tauri::command]
+async fn cancel_snooze(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let service = &state.monitoring_service;
+    let result = service.cancel_snooze();
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    result
+}
+
+#[tauri::command]
+async fn cancel_pending_pause(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let service = &state.monitoring_service;
+    let result = service.cancel_pending_pause();
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    result
+}
+
+#[tauri::command]
+async fn skip_meeting(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    let result = run_blocking({
+        let service = service.clone();
+        move || service.skip_meeting()
+    })
+    .await?;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    result
+}
+
+#[tauri::command]
+async fn set_manual_meeting(app: tauri::AppHandle, state: tauri::State<'_, AppState>, enabled: bool) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    let result = run_blocking({
+        let service = service.clone();
+        move || service.set_manual_meeting(enabled)
+    })
+    .await?;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    result
 }
 
 #[tauri::command]
 async fn get_music_status() -> Result<MusicStatus, String> {
-    let controller = music_controller::MusicController::new();
-    Ok(controller.get_music_status())
+    run_blocking(|| music_controller::MusicController::new().get_music_status()).await
+}
+
+#[tauri::command]
+async fn get_now_playing_debug() -> Result<std::collections::BTreeMap<String, String>, String> {
+    run_blocking(|| music_controller::MusicController::new().get_now_playing_info()).await?
 }
 
 #[tauri::command]
@@ -164,38 +675,234 @@ async fn control_music(action: String) -> Result<String, String> {
         _ => return Err("Invalid music action. Only 'play' and 'pause' are supported.".to_string()),
     };
 
-    let controller = music_controller::MusicController::new();
-    controller.execute_action(music_action)
+    run_blocking(move || music_controller::MusicController::new().execute_action(music_action)).await?
 }
 
 #[tauri::command]
 async fn detect_meetings(state: tauri::State<'_, AppState>) -> Result<MeetingStatus, String> {
-    let _service = state.monitoring_service.lock().unwrap();
-    let mut detector = meeting_detector::MeetingDetector::new();
-    Ok(detector.detect_meetings())
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.detect_meetings()).await
+}
+
+#[tauri::command]
+async fn get_action_history(state: tauri::State<'_, AppState>, limit: Option<usize>) -> Result<Vec<ActionRecord>, String> {
+    Ok(state.monitoring_service.get_action_history(limit.unwrap_or(50)))
+}
+
+#[tauri::command]
+async fn get_statistics(state: tauri::State<'_, AppState>) -> Result<statistics::UsageStatistics, String> {
+    Ok(state.monitoring_service.get_statistics())
 }
 
 #[tauri::command]
 async fn get_meeting_config(state: tauri::State<'_, AppState>) -> Result<MeetingConfig, String> {
-    let service = state.monitoring_service.lock().unwrap();
-    Ok(service.get_meeting_config())
+    Ok(state.monitoring_service.get_meeting_config())
 }
 
 #[tauri::command]
 async fn update_meeting_config(state: tauri::State<'_, AppState>, config: MeetingConfig) -> Result<String, String> {
-    let mut service = state.monitoring_service.lock().unwrap();
-    service.update_meeting_config(config);
-    Ok("Meeting configuration updated successfully".to_string())
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.update_meeting_config(config)).await??;
+    Ok(tr("Meeting configuration updated successfully"))
+}
+
+#[tauri::command]
+async fn add_meeting_process(state: tauri::State<'_, AppState>, name: String) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.add_meeting_process(&name)).await?
+}
+
+#[tauri::command]
+async fn remove_meeting_process(state: tauri::State<'_, AppState>, name: String) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.remove_meeting_process(&name)).await?
+}
+
+// Runs a one-off detection pass with `config`, e.g. unsaved settings, reporting on
+// each process name
+#[tauri::command]
+async fn test_detection(config: MeetingConfig) -> Result<Vec<ProcessCheck>, String> {
+    run_blocking(move || MeetingDetector::new().check_process_names(&config)).await
+}
+
+#[tauri::command]
+async fn check_permissions() -> Result<Vec<PermissionStatus>, String> {
+    run_blocking(permissions::check_all).await
+}
+
+// Checks `kind` again, which is when macOS asks for Automation access, and opens its
+// System Settings pane if it still isn't granted
+#[tauri::command]
+async fn request_permission(app: tauri::AppHandle, kind: PermissionKind) -> Result<PermissionStatus, String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let status = run_blocking(move || permissions::check(kind)).await?;
+    if status.state != PermissionState::Granted {
+        app.opener()
+            .open_url(kind.settings_url(), None::<&str>)
+            .map_err(|e| format!("Failed to open System Settings: {}", e))?;
+    }
+    Ok(status)
+}
+
+// The last `lines` log entries, leaving out those below `level` (info by default)
+#[tauri::command]
+async fn get_recent_logs(lines: usize, level: Option<LogLevel>) -> Result<Vec<LogEntry>, String> {
+    run_blocking(move || log_file::recent_entries(lines, level.unwrap_or(LogLevel::Info))).await?
+}
+
+// Installed meeting apps and a music status check for the onboarding page, or None once
+// onboarding is over
+#[tauri::command]
+async fn get_onboarding(state: tauri::State<'_, AppState>) -> Result<Option<Onboarding>, String> {
+    if !*state.onboarding_pending.lock_or_recover() {
+        return Ok(None);
+    }
+    let meeting_config = state.monitoring_service.get_meeting_config();
+    run_blocking(move || Some(Onboarding::check(&meeting_config))).await
+}
+
+#[tauri::command]
+async fn finish_onboarding(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    *state.onboarding_pending.lock_or_recover() = false;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_check_interval(state: tauri::State<'_, AppState>) -> Result<u64, String> {
+    Ok(state.monitoring_service.check_interval().as_millis() as u64)
+}
+
+#[tauri::command]
+async fn set_check_interval(state: tauri::State<'_, AppState>, interval_ms: u64) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.set_check_interval(interval_ms)).await??;
+    Ok(tr_args("Check interval set to {ms} ms", &[("ms", &interval_ms)]))
+}
+
+#[tauri::command]
+async fn set_dry_run(app: tauri::AppHandle, state: tauri::State<'_, AppState>, enabled: bool) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking({
+        let service = service.clone();
+        move || service.set_dry_run(enabled)
+    })
+    .await??;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+
+    Ok(tr(if enabled { "Dry run enabled" } else { "Dry run disabled" }))
+}
+
+#[tauri::command]
+async fn set_profile(app: tauri::AppHandle, state: tauri::State<'_, AppState>, profile: Profile) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking({
+        let service = service.clone();
+        move || service.apply_profile(profile)
+    })
+    .await??;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+    Ok(tr_args("Switched to the {profile} profile", &[("profile", &tr(profile.label()))]))
+}
+
+#[tauri::command]
+async fn get_app_config(state: tauri::State<'_, AppState>) -> Result<AppConfig, String> {
+    Ok(state.monitoring_service.get_app_config())
+}
+
+#[tauri::command]
+async fn get_config_diff(state: tauri::State<'_, AppState>) -> Result<BTreeMap<String, ConfigDifference>, String> {
+    Ok(state.monitoring_service.get_app_config().diff_from_default())
+}
+
+#[tauri::command]
+async fn update_app_config(app: tauri::AppHandle, state: tauri::State<'_, AppState>, config: AppConfig) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking({
+        let service = service.clone();
+        move || service.update_app_config(config)
+    })
+    .await??;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+
+    Ok(tr("Configuration updated"))
+}
+
+#[tauri::command]
+async fn export_config(state: tauri::State<'_, AppState>, path: String) -> Result<String, String> {
+    let config = state.monitoring_service.get_app_config();
+    let export_path = path.clone();
+    run_blocking(move || ConfigManager::write_config_file(&config, std::path::Path::new(&export_path))).await??;
+    Ok(tr_args("Settings exported to {path}", &[("path", &path)]))
+}
+
+#[tauri::command]
+async fn import_config(app: tauri::AppHandle, state: tauri::State<'_, AppState>, path: String) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    let import_path = path.clone();
+    run_blocking({
+        let service = service.clone();
+        move || {
+            let config = ConfigManager::with_overrides(ConfigManager::import_config_file(std::path::Path::new(&import_path))?);
+            service.update_app_config(config)
+        }
+    })
+    .await??;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+
+    Ok(tr_args("Settings imported from {path}", &[("path", &path)]))
+}
+
+#[tauri::command]
+async fn list_config_backups() -> Result<Vec<ConfigBackup>, String> {
+    ConfigManager::list_config_backups()
+}
+
+#[tauri::command]
+async fn restore_config_backup(app: tauri::AppHandle, state: tauri::State<'_, AppState>, index: usize) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking({
+        let service = service.clone();
+        move || {
+            let config = ConfigManager::with_overrides(ConfigManager::read_config_backup(index)?);
+            service.update_app_config(config)
+        }
+    })
+    .await??;
+    let _ = update_tray_menu_text(&app, &service.get_status());
+
+    Ok(tr("Settings restored from backup"))
+}
+
+#[tauri::command]
+async fn set_automation_toggles(
+    state: tauri::State<'_, AppState>,
+    pause_on_meeting_start: bool,
+    resume_on_meeting_end: bool,
+    ask_before_resume: bool,
+) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.set_automation_toggles(pause_on_meeting_start, resume_on_meeting_end, ask_before_resume)).await??;
+    Ok(tr("Automation settings updated"))
+}
+
+#[tauri::command]
+async fn get_notification_config(state: tauri::State<'_, AppState>) -> Result<NotificationConfig, String> {
+    Ok(state.monitoring_service.get_app_config().notifications)
+}
+
+#[tauri::command]
+async fn update_notification_config(state: tauri::State<'_, AppState>, config: NotificationConfig) -> Result<String, String> {
+    let service = state.monitoring_service.clone();
+    run_blocking(move || service.update_notification_config(config)).await??;
+    Ok(tr("Notification settings updated"))
 }
 
 #[tauri::command]
 async fn refresh_tray_menu(app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let status = {
-        let service = state.monitoring_service.lock().unwrap();
-        service.get_status()
-    };
+    let status = state.monitoring_service.get_status();
     update_tray_menu_text(&app, &status).map_err(|e| e.to_string())?;
-    Ok("Tray menu updated".to_string())
+    Ok(tr("Tray menu updated"))
 }
 
 #[tauri::command]
@@ -228,13 +935,8 @@ async fn toggle_autostart(app: tauri::AppHandle, state: tauri::State<'_, AppStat
         let new_status = !is_enabled;
 
         // Update autostart menu item text
-        if let Some(item) = state.autostart_item.lock().unwrap().as_ref() {
-            let new_text = if new_status {
-                "✅ Start on Login"
-            } else {
-                "🚀 Start on Login"
-            };
-            let _ = item.set_text(new_text);
+        if let Some(item) = state.autostart_item.lock_or_recover().as_ref() {
+            let _ = item.set_text(autostart_text(new_status));
         }
 
         Ok(new_status)
@@ -245,102 +947,312 @@ async fn toggle_autostart(app: tauri::AppHandle, state: tauri::State<'_, AppStat
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let cli_options = cli::CliOptions::from_env();
+    #[cfg(unix)]
+    if !cli_options.command.is_empty() {
+        std::process::exit(control::run_client(&cli_options.command));
+    }
+    #[cfg(unix)]
+    if let Err(e) = log_file::init() {
+        eprintln!("SoundBreak: {}", e);
+    }
+    if let Some(config_path) = cli_options.config_path.clone() {
+        ConfigManager::set_config_path(config_path);
+    }
+
+    // Options on the command line win over the environment; like it, they are not saved
+    let mut overrides = ConfigOverrides::from_env();
+    if cli_options.profile.is_some() {
+        overrides.profile = cli_options.profile;
+    }
+    if cli_options.dry_run {
+        overrides.dry_run = Some(true);
+    }
+    ConfigManager::set_overrides(overrides);
+
+    // Settings saved by earlier runs, or defaults on first launch
+    let app_config = ConfigManager::load_config();
+    i18n::set_language(app_config.language);
+    i18n::set_plain_labels(app_config.plain_tray_labels);
+
     let app_state = AppState {
-        monitoring_service: Mutex::new(MonitoringService::new()),
+        monitoring_service: MonitoringService::new(app_config),
         tray_icon: Mutex::new(None),
         last_status: Mutex::new(None),
+        onboarding_pending: Mutex::new(ConfigManager::is_first_launch()),
         monitoring_status_item: Mutex::new(None),
         music_status_item: Mutex::new(None),
+        now_playing_item: Mutex::new(None),
         meeting_status_item: Mutex::new(None),
+        skip_meeting_item: Mutex::new(None),
         toggle_item: Mutex::new(None),
         autostart_item: Mutex::new(None),
+        dry_run_item: Mutex::new(None),
+        activity_submenu: Mutex::new(None),
+        meeting_apps_submenu: Mutex::new(None),
+        profile_items: Mutex::new(Vec::new()),
     };
 
     tauri::Builder::default()
         .manage(app_state)
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .setup(move |app| {
             // Hide the app from the Dock on macOS - do this first before any other setup
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
             // Ensure single instance
             #[cfg(desktop)]
-            let _ = app.handle().plugin(tauri_plugin_single_instance::init(|_app, _args, _cwd| {}));
+            let _ = app.handle().plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+                // Run deep links passed to a second instance here instead
+                for url in args.iter().filter(|arg| deep_link::is_deep_link(arg)) {
+                    deep_link::open(app, url);
+                }
+            }));
             // Initialize autostart plugin for desktop platforms
             #[cfg(desktop)]
             let _ = app.handle().plugin(tauri_plugin_autostart::init(
                 tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-                None::<Vec<&str>> // No additional arguments needed
+                // Login launches stay in the menu bar, even in development builds
+                Some(vec!["--minimized"])
             ));
+            // Toggle monitoring from anywhere with the configured shortcut
+            #[cfg(desktop)]
+            {
+                use tauri::Listener;
+                if let Err(e) = shortcuts::init(app.handle()) {
+                    eprintln!("SoundBreak: {}", e);
+                }
+                let app_handle = app.handle().clone();
+                app.listen_any("config-changed", move |_| {
+                    if let Err(e) = shortcuts::register(&app_handle) {
+                        eprintln!("SoundBreak: {}", e);
+                    }
+                });
+            }
 
             let app_state = app.state::<AppState>();
 
             // Start monitoring automatically on startup
             {
-                let service = app_state.monitoring_service.lock().unwrap();
-                if let Ok(result) = service.start_monitoring() {
-                    println!("SoundBreak: Auto-started monitoring - {}", result);
+                let service = &app_state.monitoring_service;
+                service.set_app_handle(app.handle().clone());
+                if !cli_options.no_autostart_monitoring && service.start_on_launch() {
+                    if let Ok(result) = service.start_monitoring() {
+                        println!("SoundBreak: Auto-started monitoring - {}", result);
+                    }
+                }
+                service.start_scheduler();
+            }
+
+            // Answer `sound-break status` and other commands from the terminal
+            #[cfg(unix)]
+            if let Err(e) = control::listen(app.handle()) {
+                eprintln!("SoundBreak: {}", e);
+            }
+
+            for url in &cli_options.deep_links {
+                deep_link::open(app.handle(), url);
+            }
+
+            // Apply edits made to config.json outside the app
+            {
+                let app_handle = app.handle().clone();
+                let watch_result = config_watcher::watch(move |config| {
+                    let service = &app_handle.state::<AppState>().monitoring_service;
+                    if service.reload_app_config(config) {
+                        println!("SoundBreak: Applied changes made to the config file");
+                        let _ = update_tray_menu_text(&app_handle, &service.get_status());
+                    }
+                });
+                if let Err(e) = watch_result {
+                    eprintln!("SoundBreak: {}", e);
+                }
+            }
+
+            // Accept pause/resume requests from other instances if peer mode is enabled
+            {
+                let peer_config = app_state.monitoring_service.get_app_config().peer;
+                if peer_config.listen {
+                    if let Err(e) = peer::PeerServer::start(&peer_config.listen_address, peer_config.listen_port, peer_config.token) {
+                        eprintln!("SoundBreak: Failed to start peer server: {}", e);
+                    }
+                }
+            }
+
+            // Serve the local HTTP API for Stream Deck plugins if it is enabled
+            {
+                let local_api = app_state.monitoring_service.get_app_config().local_api;
+                if local_api.enabled {
+                    if let Err(e) = local_api::start(app.handle(), local_api.port) {
+                        eprintln!("SoundBreak: Failed to start local API: {}", e);
+                    }
+                }
+            }
+
+            // Serve the phone companion on the local network if it is enabled
+            {
+                let companion = app_state.monitoring_service.get_app_config().companion;
+                if companion.enabled {
+                    if let Err(e) = local_api::start_companion(app.handle(), companion.port) {
+                        eprintln!("SoundBreak: Failed to start companion: {}", e);
+                    }
+                }
+            }
+
+            // Watch for mute button presses if enabled
+            if app_state.monitoring_service.get_app_config().mute_button.enabled {
+                if let Err(e) = mute_button::start(app.handle()) {
+                    eprintln!("SoundBreak: Failed to start mute button: {}", e);
                 }
             }
 
             // Create initial tray menu with all items
-            let monitoring_status = MenuItem::with_id(app, "monitoring_status", "⏸️ Monitoring Stopped", false, None::<&str>)?;
-            let music_status = MenuItem::with_id(app, "music_status", "❓ Music Status Unknown", false, None::<&str>)?;
-            let meeting_status = MenuItem::with_id(app, "meeting_status", "❓ Meeting Status Unknown", false, None::<&str>)?;
-            let toggle = MenuItem::with_id(app, "toggle", "▶️ Start Monitoring", true, None::<&str>)?;
-            let autostart = MenuItem::with_id(app, "autostart", "🚀 Start on Login", true, None::<&str>)?;
+            let monitoring_status = MenuItem::with_id(app, "monitoring_status", label("⏸️", tr("Monitoring Stopped")), false, None::<&str>)?;
+            let music_status = MenuItem::with_id(app, "music_status", label("❓", tr("Music Status Unknown")), false, None::<&str>)?;
+            let now_playing = MenuItem::with_id(app, "now_playing", label("🎶", tr("No Track Info")), false, None::<&str>)?;
+            let meeting_status = MenuItem::with_id(app, "meeting_status", label("❓", tr("Meeting Status Unknown")), false, None::<&str>)?;
+            let pause_music = MenuItem::with_id(app, "pause_music", label("⏸️", tr("Pause Music Now")), true, None::<&str>)?;
+            let resume_music = MenuItem::with_id(app, "resume_music", label("▶️", tr("Resume Music")), true, None::<&str>)?;
+            let skip_meeting = MenuItem::with_id(app, "skip_meeting", label("⏭️", tr("Skip This Meeting")), false, None::<&str>)?;
+            let toggle = MenuItem::with_id(app, "toggle", label("▶️", tr("Start Monitoring")), true, None::<&str>)?;
+            let autostart = MenuItem::with_id(app, "autostart", autostart_text(false), true, None::<&str>)?;
+            let dry_run = MenuItem::with_id(app, "dry_run", label("🧪", tr("Dry Run")), true, None::<&str>)?;
+            let activity = Submenu::with_id(app, "recent_activity", label("📜", tr("Recent Activity")), true)?;
+            let meeting_apps = Submenu::with_id(app, "meeting_apps", label("🎧", tr("Meeting Apps")), true)?;
+            let profile_menu = Submenu::with_id(app, "profile", label("🎛️", tr("Profile")), true)?;
+            let mut profile_items = Vec::new();
+            for profile in Profile::ALL {
+                let item = MenuItem::with_id(app, format!("profile:{}", profile.id()), label("⬜", tr(profile.label())), true, None::<&str>)?;
+                profile_menu.append(&item)?;
+                profile_items.push((profile, item));
+            }
             #[cfg(debug_assertions)]
             let show_window_text = "Show SoundBreak";
             #[cfg(not(debug_assertions))]
             let show_window_text = "Show Settings";
 
-            let show_window = MenuItem::with_id(app, "show_window", show_window_text, true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "Quit SoundBreak", true, None::<&str>)?;
+            let show_window = MenuItem::with_id(app, "show_window", tr(show_window_text), true, None::<&str>)?;
+            let open_config = MenuItem::with_id(app, "open_config", label("📝", tr("Open Config File")), true, None::<&str>)?;
+            let open_logs = MenuItem::with_id(app, "open_logs", label("📂", tr("Open Log Folder")), true, None::<&str>)?;
+            let quit = MenuItem::with_id(app, "quit", tr("Quit SoundBreak"), true, None::<&str>)?;
 
             // Store menu item references for later updates
             {
-                *app_state.monitoring_status_item.lock().unwrap() = Some(monitoring_status.clone());
-                *app_state.music_status_item.lock().unwrap() = Some(music_status.clone());
-                *app_state.meeting_status_item.lock().unwrap() = Some(meeting_status.clone());
-                *app_state.toggle_item.lock().unwrap() = Some(toggle.clone());
-                *app_state.autostart_item.lock().unwrap() = Some(autostart.clone());
+                *app_state.monitoring_status_item.lock_or_recover() = Some(monitoring_status.clone());
+                *app_state.music_status_item.lock_or_recover() = Some(music_status.clone());
+                *app_state.now_playing_item.lock_or_recover() = Some(now_playing.clone());
+                *app_state.meeting_status_item.lock_or_recover() = Some(meeting_status.clone());
+                *app_state.skip_meeting_item.lock_or_recover() = Some(skip_meeting.clone());
+                *app_state.toggle_item.lock_or_recover() = Some(toggle.clone());
+                *app_state.autostart_item.lock_or_recover() = Some(autostart.clone());
+                *app_state.dry_run_item.lock_or_recover() = Some(dry_run.clone());
+                *app_state.activity_submenu.lock_or_recover() = Some(activity.clone());
+                *app_state.meeting_apps_submenu.lock_or_recover() = Some(meeting_apps.clone());
+                *app_state.profile_items.lock_or_recover() = profile_items;
             }
 
             let menu = MenuBuilder::new(app)
                 .item(&monitoring_status)
                 .item(&music_status)
+                .item(&now_playing)
                 .item(&meeting_status)
+                .item(&activity)
+                .item(&meeting_apps)
+                .separator()
+                .item(&pause_music)
+                .item(&resume_music)
+                .item(&skip_meeting)
                 .separator()
                 .item(&toggle)
                 .item(&autostart)
+                .item(&dry_run)
+                .item(&profile_menu)
                 .item(&show_window)
+                .item(&open_config)
+                .item(&open_logs)
                 .separator()
                 .item(&quit)
-                .build()?;            let app_handle = app.handle().clone();
+                .build()?;
+            // There is no window to show in headless mode
+            if cli_options.headless {
+                menu.remove(&show_window)?;
+            }
+            let app_handle = app.handle().clone();
             let app_handle_for_menu = app_handle.clone();
+            let headless = cli_options.headless;
             let tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
+                .icon(tauri::include_image!("icons/tray/stopped.png"))
+                .icon_as_template(true)
                 .menu(&menu)
-                .tooltip("SoundBreak - Meeting Music Controller")
+                .tooltip(tr("SoundBreak - Meeting Music Controller"))
+                // Left click opens the status popover, right click the menu
+                .show_menu_on_left_click(headless)
+                .on_tray_icon_event(move |tray, event| {
+                    if headless {
+                        return;
+                    }
+                    if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, rect, .. } = event {
+                        if let Err(e) = toggle_popover(tray.app_handle(), rect) {
+                            eprintln!("SoundBreak: Failed to show status popover: {}", e);
+                        }
+                    }
+                })
                 .on_menu_event(move |_app, event| {
                     let app_state = app_handle_for_menu.state::<AppState>();
                     match event.id().as_ref() {
                         "toggle" => {
-                            let result = {
-                                let service = app_state.monitoring_service.lock().unwrap();
-                                service.toggle_monitoring()
-                            };
+                            let result = app_state.monitoring_service.toggle_monitoring();
                             if let Ok(msg) = result {
                                 println!("SoundBreak: {}", msg);
                                 // Update tray menu after toggling
-                                let status = {
-                                    let service = app_state.monitoring_service.lock().unwrap();
-                                    service.get_status()
-                                };
+                                let status = app_state.monitoring_service.get_status();
                                 let _ = update_tray_menu_text(&app_handle_for_menu, &status);
                             }
                         }
+                        "monitoring_status" => {
+                            let service = &app_state.monitoring_service;
+                            if let Ok(msg) = service.cancel_pending_pause() {
+                                println!("SoundBreak: {}", msg);
+                            }
+                            let _ = update_tray_menu_text(&app_handle_for_menu, &service.get_status());
+                        }
+                        // Work even while monitoring is off; osascript blocks, so keep it off the menu thread
+                        "pause_music" | "resume_music" => {
+                            let action = if event.id().as_ref() == "pause_music" { MusicAction::Pause } else { MusicAction::Play };
+                            std::thread::spawn(move || {
+                                match music_controller::MusicController::new().execute_action(action) {
+                                    Ok(msg) => println!("SoundBreak: {}", msg),
+                                    Err(e) => eprintln!("SoundBreak: {}", e),
+                                }
+                            });
+                        }
+                        "skip_meeting" => {
+                            let service = app_state.monitoring_service.clone();
+                            let app_handle = app_handle_for_menu.clone();
+                            // Resuming music runs osascript, which blocks
+                            std::thread::spawn(move || {
+                                match service.skip_meeting() {
+                                    Ok(msg) => println!("SoundBreak: {}", msg),
+                                    Err(e) => eprintln!("SoundBreak: {}", e),
+                                }
+                                let _ = update_tray_menu_text(&app_handle, &service.get_status());
+                            });
+                        }
+                        "dry_run" => {
+                            let status = {
+                                let service = &app_state.monitoring_service;
+                                let enabled = !service.is_dry_run();
+                                match service.set_dry_run(enabled) {
+                                    Ok(()) => println!("SoundBreak: Dry run {}", if enabled { "enabled" } else { "disabled" }),
+                                    Err(e) => eprintln!("SoundBreak: Failed to save dry run setting: {}", e),
+                                }
+                                service.get_status()
+                            };
+                            let _ = update_tray_menu_text(&app_handle_for_menu, &status);
+                        }
                         "show_window" => {
                             // Show the main window
                             if let Some(window) = app_handle_for_menu.get_webview_window("main") {
@@ -370,21 +1282,38 @@ pub fn run() {
                                     }
 
                                     // Update autostart menu item text
-                                    if let Some(item) = app_state.autostart_item.lock().unwrap().as_ref() {
-                                        let new_text = if is_enabled {
-                                            "🚀 Start on Login"
-                                        } else {
-                                            "✅ Start on Login"
-                                        };
-                                        let _ = item.set_text(new_text);
+                                    if let Some(item) = app_state.autostart_item.lock_or_recover().as_ref() {
+                                        let _ = item.set_text(autostart_text(!is_enabled));
                                     }
                                 }
                             }
                         }
+                        "open_config" => open_path(&app_handle_for_menu, ConfigManager::get_config_path()),
+                        // The audit log and other state files live in the app directory
+                        "open_logs" => open_path(&app_handle_for_menu, ConfigManager::get_app_dir()),
                         "quit" => {
-                            std::process::exit(0);
+                            // Shutdown happens in the RunEvent::Exit handler below
+                            app_handle_for_menu.exit(0);
+                        }
+                        id => {
+                            if let Some(process_name) = id.strip_prefix("meeting_app:") {
+                                let service = &app_state.monitoring_service;
+                                let enabled = service.get_app_config().disabled_meeting_apps().iter().any(|app| app == process_name);
+                                match service.set_meeting_app_enabled(process_name, enabled) {
+                                    Ok(()) => println!("SoundBreak: {} {}", process_name, if enabled { "enabled" } else { "disabled" }),
+                                    Err(e) => eprintln!("SoundBreak: Failed to save meeting app setting: {}", e),
+                                }
+                                let _ = update_tray_menu_text(&app_handle_for_menu, &service.get_status());
+                            }
+                            if let Some(profile) = id.strip_prefix("profile:").and_then(Profile::from_id) {
+                                let service = &app_state.monitoring_service;
+                                match service.apply_profile(profile) {
+                                    Ok(()) => println!("SoundBreak: Switched to the {} profile", profile.label()),
+                                    Err(e) => eprintln!("SoundBreak: Failed to save profile: {}", e),
+                                }
+                                let _ = update_tray_menu_text(&app_handle_for_menu, &service.get_status());
+                            }
                         }
-                        _ => {}
                     }
                 })
                 .build(app)?;
@@ -392,7 +1321,7 @@ pub fn run() {
             // Store the tray icon reference
             let app_state = app.state::<AppState>();
             {
-                let mut tray_guard = app_state.tray_icon.lock().unwrap();
+                let mut tray_guard = app_state.tray_icon.lock_or_recover();
                 *tray_guard = Some(tray);
             }
 
@@ -402,26 +1331,39 @@ pub fn run() {
                 use tauri_plugin_autostart::ManagerExt;
                 let autostart_manager = app.autolaunch();
                 if let Ok(is_enabled) = autostart_manager.is_enabled() {
-                    if let Some(item) = app_state.autostart_item.lock().unwrap().as_ref() {
-                        let text = if is_enabled {
-                            "✅ Start on Login"
-                        } else {
-                            "🚀 Start on Login"
-                        };
-                        let _ = item.set_text(text);
+                    // Rewrite login items created by older versions so they pass --minimized
+                    if is_enabled {
+                        let _ = autostart_manager.enable();
+                    }
+                    if let Some(item) = app_state.autostart_item.lock_or_recover().as_ref() {
+                        let _ = item.set_text(autostart_text(is_enabled));
                     }
                 }
             }
 
+            // The main window is created here instead of at startup, so headless runs never
+            // load a webview
+            if !cli_options.headless {
+                if let Some(window_config) = app.config().app.windows.first() {
+                    tauri::WebviewWindowBuilder::from_config(app.handle(), window_config)?.build()?;
+                }
+            }
+
             // Set up window close event to hide instead of close
             if let Some(window) = app.get_webview_window("main") {
                 // Show window only in development mode
-                #[cfg(debug_assertions)]
-                {
+                if cfg!(debug_assertions) && !cli_options.minimized {
                     let _ = window.show();
                     let _ = window.set_focus();
                 }
 
+                // Walk new users through setting up their meeting apps
+                if *app_state.onboarding_pending.lock_or_recover() {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                    let _ = window.emit("onboarding", ());
+                }
+
                 let window_clone = window.clone();
                 window.on_window_event(move |event| {
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -431,46 +1373,22 @@ pub fn run() {
                 });
             }
 
-            // Update tray menu with initial status after a short delay to allow monitoring to start
-            let app_handle_clone = app_handle.clone();
-            std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_millis(500));
-                let app_state = app_handle_clone.state::<AppState>();
-                let status = {
-                    let service = app_state.monitoring_service.lock().unwrap();
-                    service.get_status()
-                };
-                let _ = update_tray_menu_text(&app_handle_clone, &status);
-
-                // Store initial status
-                {
-                    let mut last_status = app_state.last_status.lock().unwrap();
-                    *last_status = Some(status);
-                }
+            // Refresh the tray from the monitoring loop whenever the status changes
+            let app_handle_for_status = app_handle.clone();
+            app_state.monitoring_service.set_status_listener(std::sync::Arc::new(move |status| {
+                refresh_tray_if_changed(&app_handle_for_status, status);
+            }));
+            refresh_tray_if_changed(&app_handle, &app_state.monitoring_service.get_status());
 
-                // Start periodic status check every 2 seconds
-                loop {
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-
-                    let current_status = {
-                        let service = app_state.monitoring_service.lock().unwrap();
-                        service.get_status()
-                    };
-
-                    // Check if status has changed
-                    let should_update = {
-                        let last_status = app_state.last_status.lock().unwrap();
-                        has_status_changed(&*last_status, &current_status)
-                    };
-
-                    if should_update {
-                        let _ = update_tray_menu_text(&app_handle_clone, &current_status);
-                        // Update stored status
-                        let mut last_status = app_state.last_status.lock().unwrap();
-                        *last_status = Some(current_status);
-                    }
-                }
-            });
+            // Settings such as the tray title change how the same status is shown
+            {
+                use tauri::Listener;
+                let app_handle_for_config = app_handle.clone();
+                app.listen_any("config-changed", move |_| {
+                    let status = app_handle_for_config.state::<AppState>().monitoring_service.get_status();
+                    let _ = update_tray_menu_text(&app_handle_for_config, &status);
+                });
+            }
 
             Ok(())
         })
@@ -479,15 +1397,60 @@ pub fn run() {
             stop_monitoring,
             toggle_monitoring,
             get_monitoring_status,
+            subscribe_status,
+            check_now,
+            snooze_monitoring,
+            cancel_snooze,
+            cancel_pending_pause,
+            skip_meeting,
+            set_manual_meeting,
             get_music_status,
+            get_now_playing_debug,
             control_music,
             detect_meetings,
+            get_action_history,
+            get_statistics,
             get_meeting_config,
             update_meeting_config,
+            add_meeting_process,
+            remove_meeting_process,
+            test_detection,
+            get_onboarding,
+            finish_onboarding,
+            get_recent_logs,
+            check_permissions,
+            request_permission,
+            get_check_interval,
+            set_check_interval,
+            set_dry_run,
+            set_profile,
+            get_app_config,
+            get_config_diff,
+            update_app_config,
+            export_config,
+            import_config,
+            list_config_backups,
+            restore_config_backup,
+            set_automation_toggles,
+            get_notification_config,
+            update_notification_config,
             refresh_tray_menu,
             get_autostart_status,
             toggle_autostart
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            match event {
+                tauri::RunEvent::Exit => app.state::<AppState>().monitoring_service.shutdown(),
+                // soundbreak:// URLs opened while running, registered in Info.plist
+                #[cfg(target_os = "macos")]
+                tauri::RunEvent::Opened { urls } => {
+                    for url in urls {
+                        deep_link::open(app, url.as_str());
+                    }
+                }
+                _ => {}
+            }
+        });
 }
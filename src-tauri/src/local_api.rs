@@ -0,0 +1,298 @@
+//! Local HTTP API
+//!
+//! A small HTTP server on `127.0.0.1` for Stream Deck plugins and similar tools that
+//! want live state for their key icons and buttons for the main actions:
+//!
+//! - `GET /status`: the current status as JSON
+//! - `GET /events`: Server-Sent Events, one `data:` line with the status whenever it changes
+//! - `POST /<action>`: the actions of `soundbreak://` URLs, e.g. `POST /toggle`,
+//!   `POST /snooze?min=30` or `POST /meeting/start`
+//!
+//! Actions must carry an `X-SoundBreak-Client` header. Browsers can't add it to
+//! cross-site requests without asking first, so web pages can't trigger actions.
+//!
+//! The phone companion is the same API on all interfaces, where every request but
+//! `GET /` for the companion page and `POST /pair` must carry the paired token instead.
+//! `POST /pair` trades a pairing code from `sound-break pair` for that token. Requests
+//! and headers are capped in size and connections in number, so clients on the network
+//! can't exhaust memory or threads.
+
+use crate::companion;
+use crate::deep_link;
+use crate::monitoring_service::{status_diff, MonitoringStatus};
+use crate::peer::tokens_match;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const CLIENT_HEADER: &str = "x-soundbreak-client";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+// Longest request or header line, and most headers, read from a request
+const MAX_LINE_BYTES: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+// Connections served at once per listener, including open `/events` streams
+const MAX_CONNECTIONS: usize = 16;
+// How often `/events` looks for status changes, and how often it proves it is alive
+const EVENTS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const EVENTS_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+struct Request {
+    method: String,
+    target: String,
+    has_client_header: bool,
+    bearer_token: Option<String>,
+}
+
+// Who may use the API: tools on this Mac, or paired phones on the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Local,
+    Companion,
+}
+
+/// Starts answering requests on `127.0.0.1:port` on a background thread
+pub fn start(app: &AppHandle, port: u16) -> Result<(), String> {
+    listen(app, "127.0.0.1", port, Access::Local)?;
+    println!("SoundBreak: Local API listening on http://127.0.0.1:{}", port);
+    Ok(())
+}
+
+/// Starts answering paired phones on port `port` of every interface, on a background thread
+pub fn start_companion(app: &AppHandle, port: u16) -> Result<(), String> {
+    listen(app, "0.0.0.0", port, Access::Companion)?;
+    println!("SoundBreak: Companion listening on port {}", port);
+    Ok(())
+}
+
+fn listen(app: &AppHandle, host: &str, port: u16, access: Access) -> Result<(), String> {
+    let listener = TcpListener::bind((host, port)).map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+
+    let app = app.clone();
+    let connections = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let Some(slot) = ConnectionSlot::take(&connections, MAX_CONNECTIONS) else {
+                let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+                let _ = write_json(&mut stream, "503 Service Unavailable", &json!({ "ok": false, "error": "Too many connections" }));
+                continue;
+            };
+            let app = app.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = serve(&app, stream, access) {
+                    eprintln!("SoundBreak: Local API request failed: {}", e);
+                }
+                drop(slot);
+            });
+        }
+    });
+    Ok(())
+}
+
+/// One of the connections a listener serves at once, given back when dropped
+pub struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    /// Counts a new connection in `connections`, unless `limit` are already being served
+    pub fn take(connections: &Arc<AtomicUsize>, limit: usize) -> Option<Self> {
+        connections
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| (count < limit).then_some(count + 1))
+            .ok()
+            .map(|_| Self(connections.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn serve(app: &AppHandle, mut stream: TcpStream, access: Access) -> Result<(), String> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    let request = read_request(&stream)?;
+
+    if access == Access::Companion {
+        match (request.method.as_str(), request.target.as_str()) {
+            ("GET", "/") => return write_response(&mut stream, "200 OK", "text/html; charset=utf-8", companion::PAGE),
+            ("POST", "/pair") => {
+                return match request.bearer_token.as_deref().map(|code| companion::redeem_pairing_code(app, code)) {
+                    Some(Ok(token)) => write_json(&mut stream, "200 OK", &json!({ "ok": true, "token": token })),
+                    Some(Err(e)) => write_json(&mut stream, "401 Unauthorized", &json!({ "ok": false, "error": e })),
+                    None => write_json(&mut stream, "401 Unauthorized", &json!({ "ok": false, "error": "Missing pairing code" })),
+                };
+            }
+            _ => {}
+        }
+        // Read for every request, so a new token from pairing takes effect right away
+        let token = app.state::<AppState>().monitoring_service.get_app_config().companion.token;
+        if !request.bearer_token.as_deref().is_some_and(|provided| tokens_match(provided, &token)) {
+            return write_json(&mut stream, "401 Unauthorized", &json!({ "ok": false, "error": "Invalid or missing token" }));
+        }
+    }
+
+    match (request.method.as_str(), request.target.as_str()) {
+        ("GET", "/status") => {
+            let status = app.state::<AppState>().monitoring_service.get_status();
+            let body = serde_json::to_value(&status).map_err(|e| format!("Failed to serialize status: {}", e))?;
+            write_json(&mut stream, "200 OK", &body)
+        }
+        ("GET", "/summary") => {
+            let summary = crate::status_summary(&app.state::<AppState>().monitoring_service.get_status());
+            write_json(&mut stream, "200 OK", &json!({ "summary": summary }))
+        }
+        ("GET", "/events") => stream_events(app, stream),
+        ("POST", _) if access == Access::Local && !request.has_client_header => write_json(
+            &mut stream,
+            "403 Forbidden",
+            &json!({ "ok": false, "error": format!("Missing {} header", CLIENT_HEADER) }),
+        ),
+        ("POST", target) => {
+            let url = format!("{}://{}", deep_link::SCHEME, target.trim_start_matches('/'));
+            match deep_link::run(app, &url) {
+                Ok(message) => write_json(&mut stream, "200 OK", &json!({ "ok": true, "message": message })),
+                Err(e) => write_json(&mut stream, "400 Bad Request", &json!({ "ok": false, "error": e })),
+            }
+        }
+        _ => write_json(&mut stream, "404 Not Found", &json!({ "ok": false, "error": "Not found" })),
+    }
+}
+
+// Reads the request line and headers; requests have no body worth reading
+fn read_request(stream: &TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+    let request_line = read_line_limited(&mut reader, MAX_LINE_BYTES)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(format!("Invalid request line {:?}", request_line.trim()));
+    };
+
+    let mut has_client_header = false;
+    let mut bearer_token = None;
+    for header_count in 0.. {
+        let header = read_line_limited(&mut reader, MAX_LINE_BYTES)?;
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if header_count == MAX_HEADERS {
+            return Err("Too many request headers".to_string());
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            has_client_header |= name.trim().eq_ignore_ascii_case(CLIENT_HEADER);
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                bearer_token = value.trim().strip_prefix("Bearer ").map(|token| token.trim().to_string());
+            }
+        }
+    }
+
+    Ok(Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        has_client_header,
+        bearer_token,
+    })
+}
+
+/// Reads a line of at most `limit` bytes, failing on longer ones instead of buffering them
+pub fn read_line_limited(reader: &mut impl BufRead, limit: usize) -> Result<String, String> {
+    let mut line = String::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read: {}", e))?;
+    if line.len() > limit {
+        return Err(format!("Line longer than {} bytes", limit));
+    }
+    Ok(line)
+}
+
+fn write_json(stream: &mut TcpStream, status_line: &str, body: &Value) -> Result<(), String> {
+    write_response(stream, status_line, "application/json", &body.to_string())
+}
+
+fn write_response(stream: &mut TcpStream, status_line: &str, content_type: &str, body: &str) -> Result<(), String> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_line,
+        content_type,
+        body.len(),
+        body
+    )
+    .map_err(|e| format!("Failed to write response: {}", e))
+}
+
+// Sends the status whenever something other than the timestamps that change on every
+// check changed, until the client disconnects
+fn stream_events(app: &AppHandle, mut stream: TcpStream) -> Result<(), String> {
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n")
+        .map_err(|e| format!("Failed to write response: {}", e))?;
+
+    let service = app.state::<AppState>().monitoring_service.clone();
+    let mut last_sent: Option<MonitoringStatus> = None;
+    let mut last_write = Instant::now();
+    loop {
+        let event = match status_event(&mut last_sent, service.get_status())? {
+            Some(event) => Some(event),
+            None if last_write.elapsed() >= EVENTS_KEEPALIVE_INTERVAL => Some(": keepalive\n\n".to_string()),
+            None => None,
+        };
+        if let Some(event) = event {
+            // The client went away
+            if stream.write_all(event.as_bytes()).and_then(|_| stream.flush()).is_err() {
+                return Ok(());
+            }
+            last_write = Instant::now();
+        }
+        std::thread::sleep(EVENTS_POLL_INTERVAL);
+    }
+}
+
+// The `data:` event for `status`, unless it only differs from the last one sent in what
+// `status_diff` ignores, e.g. the check time and the meeting's running duration
+fn status_event(last_sent: &mut Option<MonitoringStatus>, status: MonitoringStatus) -> Result<Option<String>, String> {
+    if last_sent.as_ref().is_some_and(|last_sent| status_diff(last_sent, &status).is_empty()) {
+        return Ok(None);
+    }
+    let data = serde_json::to_string(&status).map_err(|e| format!("Failed to serialize status: {}", e))?;
+    *last_sent = Some(status);
+    Ok(Some(format!("data: {}\n\n", data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::meeting_detector::MeetingStatus;
+    use crate::monitoring_service::MonitoringService;
+    use crate::monitoring_state::MemoryStateStore;
+
+    #[test]
+    fn sends_one_event_while_only_timestamps_change() {
+        let service = MonitoringService::in_memory(AppConfig::default(), Arc::new(MemoryStateStore::default()));
+        let mut first = service.get_status();
+        first.meeting_status = Some(MeetingStatus {
+            in_meeting: true,
+            active_apps: Vec::new(),
+            timestamp: 1_700_000_000,
+        });
+        let mut second = first.clone();
+        second.meeting_status.as_mut().unwrap().timestamp += 2;
+        second.last_check += 2;
+        second.meeting_duration_secs = Some(62);
+        second.timings.total_ms = 40;
+
+        let mut last_sent = None;
+        assert!(status_event(&mut last_sent, first).unwrap().is_some());
+        assert!(status_event(&mut last_sent, second.clone()).unwrap().is_none());
+
+        second.is_active = !second.is_active;
+        assert!(status_event(&mut last_sent, second).unwrap().is_some());
+    }
+}
@@ -0,0 +1,135 @@
+//! Log file
+//!
+//! Everything printed to stdout and stderr, i.e. the `SoundBreak: ...` lines, is also
+//! appended to `soundbreak.log` in the app directory with a timestamp and a level:
+//! `info` for stdout and `error` for stderr. Logs then survive launches from Finder,
+//! where there is no terminal, and can be shown in Settings.
+
+use crate::config::ConfigManager;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// The log is rotated to `soundbreak.1.log` once it grows past this size
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+// Serializes appends and rotation between the stdout and stderr threads
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Info,
+    Error,
+}
+
+impl LogLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "INFO" => Some(LogLevel::Info),
+            "ERROR" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Local time, e.g. "2025-01-31 14:05:09"
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+fn log_path(index: usize) -> Result<PathBuf, String> {
+    let app_dir = ConfigManager::get_app_dir()?;
+    Ok(if index == 0 {
+        app_dir.join("soundbreak.log")
+    } else {
+        app_dir.join(format!("soundbreak.{}.log", index))
+    })
+}
+
+/// Starts copying stdout and stderr to the log file; output still reaches the terminal
+#[cfg(unix)]
+pub fn init() -> Result<(), String> {
+    tee(libc::STDOUT_FILENO, LogLevel::Info)?;
+    tee(libc::STDERR_FILENO, LogLevel::Error)
+}
+
+// Points `fd` at a pipe, and copies each line read from it to the original `fd` and
+// to the log file on a background thread
+#[cfg(unix)]
+fn tee(fd: std::os::fd::RawFd, level: LogLevel) -> Result<(), String> {
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+    use std::os::fd::FromRawFd;
+
+    let (reader, mut original) = unsafe {
+        let original = libc::dup(fd);
+        let mut pipe_fds = [0; 2];
+        if original < 0 || libc::pipe(pipe_fds.as_mut_ptr()) != 0 {
+            return Err(format!("Failed to set up the log file: {}", std::io::Error::last_os_error()));
+        }
+        if libc::dup2(pipe_fds[1], fd) < 0 {
+            return Err(format!("Failed to set up the log file: {}", std::io::Error::last_os_error()));
+        }
+        libc::close(pipe_fds[1]);
+        (File::from_raw_fd(pipe_fds[0]), File::from_raw_fd(original))
+    };
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            let _ = writeln!(original, "{}", line);
+            // Failures can't be reported: the report would end up back here
+            let _ = append(level, &line);
+        }
+    });
+    Ok(())
+}
+
+fn append(level: LogLevel, message: &str) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock_or_recover();
+    let path = log_path(0)?;
+    if std::fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_BYTES) {
+        std::fs::rename(&path, log_path(1)?).map_err(|e| e.to_string())?;
+    }
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    let mut file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| e.to_string())?;
+    writeln!(file, "{} {} {}", timestamp, level.as_str(), message).map_err(|e| e.to_string())
+}
+
+/// The last `lines` entries at `min_level` or above, oldest first
+pub fn recent_entries(lines: usize, min_level: LogLevel) -> Result<Vec<LogEntry>, String> {
+    let mut entries = Vec::new();
+    for index in [1, 0] {
+        let Ok(content) = std::fs::read_to_string(log_path(index)?) else {
+            continue;
+        };
+        entries.extend(content.lines().filter_map(parse_line).filter(|entry| entry.level >= min_level));
+    }
+    let skip = entries.len().saturating_sub(lines);
+    Ok(entries.split_off(skip))
+}
+
+// Parses "<date> <time> <LEVEL> <message>"
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let mut parts = line.splitn(4, ' ');
+    let (date, time, level, message) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    Some(LogEntry {
+        timestamp: format!("{} {}", date, time),
+        level: LogLevel::parse(level)?,
+        message: message.to_string(),
+    })
+}
@@ -61,6 +61,7 @@ pub struct MeetingStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MeetingConfig {
     pub process_names: Vec<String>,
 }
@@ -76,6 +77,26 @@ impl Default for MeetingConfig {
     }
 }
 
+/// Outcome of checking one configured process name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessCheckResult {
+    Running,
+    NotRunning,
+    /// The name can never match, e.g. it's empty or a duplicate
+    Invalid,
+    /// Processes couldn't be listed
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessCheck {
+    pub process_name: String,
+    pub result: ProcessCheckResult,
+    /// Human-readable result, e.g. "zoom.us: not running"
+    pub message: String,
+}
+
 pub struct MeetingDetector {
     config: MeetingConfig,
 }
@@ -96,11 +117,27 @@ impl MeetingDetector {
     }
 
     pub fn detect_meetings(&mut self) -> MeetingStatus {
+        self.try_detect_meetings().unwrap_or_else(|e| {
+            eprintln!("SoundBreak: {}", e);
+            MeetingStatus {
+                in_meeting: false,
+                active_apps: Vec::new(),
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs(),
+            }
+        })
+    }
+
+    /// Like `detect_meetings`, but fails instead of reporting "no meeting" when
+    /// processes can't be listed
+    pub fn try_detect_meetings(&mut self) -> Result<MeetingStatus, String> {
         let mut active_apps = Vec::new();
         let mut in_meeting = false;
 
         for process_name in &self.config.process_names {
-            let is_running = self.is_process_running(process_name);
+            let is_running = self.is_process_running(process_name)?;
 
             // Always add the app to the list with its current status
             active_apps.push(MeetingApp {
@@ -114,17 +151,56 @@ impl MeetingDetector {
             }
         }
 
-        MeetingStatus {
+        Ok(MeetingStatus {
             in_meeting,
             active_apps,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
+        })
+    }
+
+    /// Returns the first of `process_names` that is running, if any
+    pub fn find_running(&self, process_names: &[String]) -> Result<Option<String>, String> {
+        for process_name in process_names {
+            if self.is_process_running(process_name)? {
+                return Ok(Some(process_name.clone()));
+            }
         }
+        Ok(None)
     }
 
-    fn is_process_running(&self, process_name: &str) -> bool {
+    /// Checks each of `config.process_names` once, without changing the detector's config,
+    /// so names can be tried out before they are saved
+    pub fn check_process_names(&self, config: &MeetingConfig) -> Vec<ProcessCheck> {
+        let mut checks: Vec<ProcessCheck> = Vec::new();
+        for process_name in &config.process_names {
+            let problem = validate_process_name(process_name).err().or_else(|| {
+                checks
+                    .iter()
+                    .any(|check| check.process_name == *process_name)
+                    .then(|| "listed more than once".to_string())
+            });
+
+            let (result, detail) = match problem {
+                Some(problem) => (ProcessCheckResult::Invalid, problem),
+                None => match self.is_process_running(process_name) {
+                    Ok(true) => (ProcessCheckResult::Running, "running".to_string()),
+                    Ok(false) => (ProcessCheckResult::NotRunning, "not running".to_string()),
+                    Err(e) => (ProcessCheckResult::Error, e),
+                },
+            };
+            checks.push(ProcessCheck {
+                process_name: process_name.clone(),
+                result,
+                message: format!("{}: {}", process_name, detail),
+            });
+        }
+        checks
+    }
+
+    fn is_process_running(&self, process_name: &str) -> Result<bool, String> {
         use std::process::Command;
 
         // Use pgrep for exact process name matching
@@ -136,18 +212,34 @@ impl MeetingDetector {
             .output();
 
         match output {
+            // pgrep exits with 1 when nothing matched and above 1 on errors
+            Ok(result) if result.status.code().is_some_and(|code| code > 1) => Err(format!(
+                "pgrep failed: {}",
+                String::from_utf8_lossy(&result.stderr).trim()
+            )),
             Ok(result) => {
                 // Process exists if pgrep returns success and has output
-                result.status.success() && !result.stdout.is_empty()
-            }
-            Err(_) => {
-                // If pgrep command fails, assume process doesn't exist
-                false
+                Ok(result.status.success() && !result.stdout.is_empty())
             }
+            Err(e) => Err(format!("Failed to run pgrep: {}", e)),
         }
     }
 }
 
+/// Rejects process names that can never match a running process
+pub fn validate_process_name(process_name: &str) -> Result<(), String> {
+    if process_name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if process_name.trim() != process_name {
+        return Err("name has leading or trailing spaces".to_string());
+    }
+    if process_name.contains('/') {
+        return Err("use the process name, not the path to the app".to_string());
+    }
+    Ok(())
+}
+
 impl Default for MeetingDetector {
     fn default() -> Self {
         Self::new()
@@ -1,95 +1,516 @@
-use crate::config::ConfigManager;
-use crate::meeting_detector::{MeetingDetector, MeetingStatus, MeetingConfig};
+use crate::action_history::{ActionHistory, ActionKind, ActionRecord, LastAction, LastActionKind};
+use crate::actions::{ActionResult, TransitionAction};
+use crate::audit_log::{AuditEvent, AuditLog};
+use crate::busylight;
+use crate::calendar::{self, CalendarLookup};
+use crate::config::{validate_check_interval, AppConfig, ConfigManager, MeetingAppOverride, PodcastResumePolicy, MIN_CHECK_INTERVAL_MS};
+use crate::focus;
+use crate::google_chat;
+use crate::hooks::{self, HookConfig};
+use crate::hue;
+use crate::i18n::{self, tr, tr_args};
+use crate::journal::{Journal, JournalEntry};
+use crate::keyboard_led;
+use crate::meeting_detector::{validate_process_name, MeetingApp, MeetingDetector, MeetingStatus, MeetingConfig};
+use crate::monitoring_state::{default_state_store, MeetingState, PersistedState, PlaybackInitiator, PlaybackTracker, StateStore};
 use crate::music_controller::{MusicController, MusicAction, MusicStatus};
+use crate::notifications::{self, NotificationConfig};
+use crate::peer::{PeerClient, PeerEvent};
+use crate::power;
+use crate::presence;
+use crate::profiles::Profile;
+use crate::prompt;
+use crate::statistics::{StatisticsStore, UsageStatistics};
+use crate::sync::MutexExt;
+use crate::teams;
+use crate::webhooks::{self, Webhook, WebhookContext, WebhookEvent};
+use chrono::Local;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+// How often the scheduler checks whether monitoring hours started or ended
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// How long the "Resume music?" dialog waits for an answer before leaving music paused
+const RESUME_PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+// How long the "Still in a meeting?" dialog waits for an answer before leaving music paused
+const STALE_PAUSE_PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+// How long the meeting start buttons stay up, and how long "Snooze 30m" snoozes for
+const MEETING_ACTIONS_PROMPT_TIMEOUT: Duration = Duration::from_secs(60);
+const MEETING_ACTIONS_SNOOZE_MINUTES: u64 = 30;
+
+// How long a power source reading is reused before asking pmset again
+const POWER_SOURCE_CACHE: Duration = Duration::from_secs(60);
+
+// Stands in for the meeting app while the manual meeting override is on
+const MANUAL_MEETING_APP: &str = "Manual override";
+
+// An external source that said a meeting started but never said it ended is forgotten
+// after this long, so a crashed softphone can't keep music paused for good
+const EXTERNAL_MEETING_TIMEOUT: Duration = Duration::from_secs(4 * 60 * 60);
+const MAX_EXTERNAL_SOURCE_LEN: usize = 64;
+
+// How long quitting waits for a check in progress to finish before restoring music
+const SHUTDOWN_TICK_WAIT: Duration = Duration::from_secs(5);
+
+// Consecutive failures after which a subsystem is reported as unhealthy
+const HEALTH_FAILURE_THRESHOLD: u32 = 3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringStatus {
     pub is_active: bool,
     pub meeting_status: Option<MeetingStatus>,
     pub music_status: Option<MusicStatus>,
-    pub last_action: Option<String>,
+    pub last_action: Option<LastAction>,
     pub last_check: u64,
+    /// False outside the configured monitoring hours, when playback is never touched
+    pub within_schedule: bool,
+    /// Automatic actions are suspended until this time (unix secs)
+    pub snoozed_until: Option<u64>,
+    /// Inhibitor app whose presence currently suspends automatic actions
+    pub inhibited_by: Option<String>,
+    /// Who caused the most recent observed play/pause change
+    pub last_playback_change_by: Option<PlaybackInitiator>,
+    /// Per-action outcome of the most recent meeting start or end
+    pub last_action_results: Vec<ActionResult>,
+    /// Actions are only logged, never performed
+    pub dry_run: bool,
+    pub health: MonitoringHealth,
+    /// When the current meeting started (unix secs), if in a meeting
+    pub meeting_started_at: Option<u64>,
+    /// How long the current meeting has lasted as of `last_check`
+    pub meeting_duration_secs: Option<u64>,
+    /// Check interval currently in use after adaptive polling, in milliseconds
+    pub effective_check_interval_ms: u64,
+    pub on_battery: bool,
+    /// Profile the settings were last switched to, if any
+    pub profile: Option<Profile>,
+    /// Music will be paused at this time (unix secs) unless cancelled
+    pub pause_pending_until: Option<u64>,
+    /// Last screen lock state seen by the screen lock trigger
+    pub screen_locked: bool,
+    /// Whether the user was idle long enough to count as away, per the idle trigger
+    pub user_away: bool,
+    /// New meetings are ignored until this time (unix secs) after a resume
+    pub resume_cooldown_until: Option<u64>,
+    /// Time spent in each part of the most recent check
+    pub timings: TickTimings,
+    /// The user said they are in a meeting, whatever detection says
+    pub manual_meeting: bool,
+    /// Music is left alone for the rest of the current meeting
+    pub meeting_skipped: bool,
+}
+
+impl MonitoringStatus {
+    /// Whether automatic actions run, in one word for integrations: `active`, `snoozed`,
+    /// `suspended` (by an inhibitor app), `outside_hours` or `stopped`
+    pub fn monitoring_state(&self) -> &'static str {
+        if !self.is_active {
+            "stopped"
+        } else if self.snoozed_until.is_some() {
+            "snoozed"
+        } else if self.inhibited_by.is_some() {
+            "suspended"
+        } else if !self.within_schedule {
+            "outside_hours"
+        } else {
+            "active"
+        }
+    }
+
+    /// Name of the meeting app in use, if in a meeting
+    pub fn meeting_app(&self) -> Option<String> {
+        let meeting = self.meeting_status.as_ref().filter(|meeting| meeting.in_meeting)?;
+        meeting.active_apps.iter().find(|app| app.is_running).map(|app| app.name.clone())
+    }
+}
+
+/// How long the parts of a monitoring check took, in milliseconds. Detection and the
+/// music status query run concurrently, so `total_ms` is less than the sum.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickTimings {
+    pub detection_ms: u64,
+    pub music_status_ms: u64,
+    /// Running transition actions and other automations
+    pub actions_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonitoringHealth {
+    /// Meeting detection (process listing)
+    pub detector: BackendHealth,
+    /// Now-playing queries and playback control
+    pub music: BackendHealth,
+    /// Most recent error from any subsystem or action
+    pub last_error: Option<String>,
+    /// Music has been paused for longer than `stale_pause_alert_hours`, which usually
+    /// means a meeting app was left running after the call
+    pub stale_pause: bool,
+}
+
+impl MonitoringHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.detector.ok && self.music.ok && !self.stale_pause
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendHealth {
+    pub ok: bool,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+impl Default for BackendHealth {
+    fn default() -> Self {
+        Self {
+            ok: true,
+            last_error: None,
+            consecutive_failures: 0,
+        }
+    }
+}
+
+impl BackendHealth {
+    fn record<T>(&mut self, result: &Result<T, String>) {
+        match result {
+            Ok(_) => {
+                self.consecutive_failures = 0;
+                self.ok = true;
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                self.ok = self.consecutive_failures < HEALTH_FAILURE_THRESHOLD;
+                self.last_error = Some(e.clone());
+            }
+        }
+    }
+}
+
+/// Called with the latest status after every check and state change
+pub type StatusListener = Arc<dyn Fn(&MonitoringStatus) + Send + Sync>;
+
+/// Payload of the `monitoring-status-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChangedEvent {
+    pub status: MonitoringStatus,
+    /// New values of the top-level status fields that changed, keyed by field name
+    pub changes: BTreeMap<String, serde_json::Value>,
+}
+
+// Consecutive detections disagreeing with the current meeting state, per direction
+#[derive(Debug, Default)]
+struct TransitionCounter {
+    entering: u32,
+    leaving: u32,
+    /// When detection first disagreed with the current state (unix secs)
+    disagreeing_since: Option<u64>,
+}
+
+enum WorkerSignal {
+    Stop,
+    IntervalChanged,
+}
+
+// Background thread driving the monitoring loop while monitoring is active. It is never
+// joined: the main thread may be the one stopping it, and a check in progress can wait
+// on the main thread, so it is told to stop and left to exit after that check.
+struct MonitoringWorker {
+    signal_tx: Sender<WorkerSignal>,
 }
 
+#[derive(Clone)]
 pub struct MonitoringService {
     detector: Arc<Mutex<MeetingDetector>>,
+    app_config: Arc<Mutex<AppConfig>>,
     is_running: Arc<Mutex<bool>>,
-    was_in_meeting: Arc<Mutex<bool>>,
-    music_was_playing_before_meeting: Arc<Mutex<bool>>,
+    meeting_state: Arc<Mutex<MeetingState>>,
+    transition_counter: Arc<Mutex<TransitionCounter>>,
+    snoozed_until: Arc<Mutex<Option<u64>>>,
+    playback_tracker: Arc<Mutex<PlaybackTracker>>,
+    history: ActionHistory,
+    audit_log: AuditLog,
+    statistics: StatisticsStore,
+    journal: Journal,
+    state_store: Arc<dyn StateStore>,
+    // Last state saved to the store, to only save when something changed
+    persisted_state: Arc<Mutex<Option<PersistedState>>>,
     status: Arc<Mutex<MonitoringStatus>>,
-    last_check_time: Arc<Mutex<SystemTime>>,
+    worker: Arc<Mutex<Option<MonitoringWorker>>>,
+    // Held for the whole of a check, so checks asked for by the user or other tools
+    // never run at the same time as the worker's
+    tick_lock: Arc<Mutex<()>>,
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
+    status_listener: Arc<Mutex<Option<StatusListener>>>,
+    // Last power source reading and when it was taken
+    power_source: Arc<Mutex<Option<(Instant, bool)>>>,
+    screen_locked: Arc<Mutex<bool>>,
+    user_away: Arc<Mutex<bool>>,
+    // When SoundBreak last brought music back (unix secs), for the resume cool-down
+    last_resumed_at: Arc<Mutex<Option<u64>>>,
+    manual_meeting: Arc<Mutex<bool>>,
+    // Set when the manual override was flipped or an external source reported a
+    // meeting, so the next check acts on it without waiting for detection confirmations
+    manual_meeting_changed: Arc<Mutex<bool>>,
+    // Tools that said they are in a meeting, and when they said so (unix secs)
+    external_meetings: Arc<Mutex<BTreeMap<String, u64>>>,
 }
 
 impl MonitoringService {
-    pub fn new() -> Self {
-        // Load configuration from persistent storage
-        let app_config = ConfigManager::load_config();
+    /// Creates a service using `app_config`, usually loaded with `ConfigManager::load_config`
+    pub fn new(app_config: AppConfig) -> Self {
+        Self::with_state_store(app_config, default_state_store())
+    }
+
+    /// Creates a service that restores and saves its state through `state_store`
+    pub fn with_state_store(app_config: AppConfig, state_store: Arc<dyn StateStore>) -> Self {
+        Self::with_stores(
+            app_config,
+            state_store,
+            ActionHistory::load(),
+            AuditLog::new(),
+            StatisticsStore::load(),
+            Journal::load(),
+        )
+    }
+
+    /// Like `with_state_store`, with a history, audit log, statistics and journal that
+    /// start empty and are never written to disk
+    #[cfg(test)]
+    pub fn in_memory(app_config: AppConfig, state_store: Arc<dyn StateStore>) -> Self {
+        Self::with_stores(
+            app_config,
+            state_store,
+            ActionHistory::in_memory(),
+            AuditLog::in_memory(),
+            StatisticsStore::in_memory(),
+            Journal::in_memory(),
+        )
+    }
+
+    fn with_stores(
+        app_config: AppConfig,
+        state_store: Arc<dyn StateStore>,
+        history: ActionHistory,
+        audit_log: AuditLog,
+        statistics: StatisticsStore,
+        journal: Journal,
+    ) -> Self {
         let mut detector = MeetingDetector::new();
-        detector.update_config(app_config.meeting_config);
+        detector.update_config(app_config.meeting_config.clone());
+        let dry_run = app_config.dry_run;
+
+        // Restore state saved before the last quit or crash
+        let persisted_state = state_store.load();
+        let restored = persisted_state.clone().unwrap_or_default();
+        let meeting_started_at = restored.meeting.meeting_started_at;
+        let last_playback_change_by = restored.playback.last_change_by;
+        let check_interval_ms = app_config.check_interval_ms;
+        let profile = app_config.profile;
 
         Self {
             detector: Arc::new(Mutex::new(detector)),
+            app_config: Arc::new(Mutex::new(app_config)),
             is_running: Arc::new(Mutex::new(false)),
-            was_in_meeting: Arc::new(Mutex::new(false)),
-            music_was_playing_before_meeting: Arc::new(Mutex::new(false)),
+            meeting_state: Arc::new(Mutex::new(restored.meeting)),
+            transition_counter: Arc::new(Mutex::new(TransitionCounter::default())),
+            snoozed_until: Arc::new(Mutex::new(restored.snoozed_until)),
+            playback_tracker: Arc::new(Mutex::new(restored.playback)),
+            history,
+            audit_log,
+            statistics,
+            journal,
+            state_store,
+            persisted_state: Arc::new(Mutex::new(persisted_state)),
             status: Arc::new(Mutex::new(MonitoringStatus {
                 is_active: false,
                 meeting_status: None,
                 music_status: None,
                 last_action: None,
                 last_check: 0,
+                within_schedule: true,
+                snoozed_until: restored.snoozed_until,
+                inhibited_by: None,
+                last_playback_change_by,
+                last_action_results: Vec::new(),
+                dry_run,
+                health: MonitoringHealth::default(),
+                meeting_started_at,
+                meeting_duration_secs: None,
+                effective_check_interval_ms: check_interval_ms,
+                on_battery: false,
+                profile,
+                pause_pending_until: None,
+                screen_locked: false,
+                user_away: false,
+                resume_cooldown_until: None,
+                timings: TickTimings::default(),
+                manual_meeting: false,
+                meeting_skipped: false,
             })),
-            last_check_time: Arc::new(Mutex::new(SystemTime::now())),
+            power_source: Arc::new(Mutex::new(None)),
+            screen_locked: Arc::new(Mutex::new(false)),
+            user_away: Arc::new(Mutex::new(false)),
+            last_resumed_at: Arc::new(Mutex::new(None)),
+            manual_meeting: Arc::new(Mutex::new(false)),
+            manual_meeting_changed: Arc::new(Mutex::new(false)),
+            external_meetings: Arc::new(Mutex::new(BTreeMap::new())),
+            worker: Arc::new(Mutex::new(None)),
+            tick_lock: Arc::new(Mutex::new(())),
+            app_handle: Arc::new(Mutex::new(None)),
+            status_listener: Arc::new(Mutex::new(None)),
         }
     }
 
     pub fn start_monitoring(&self) -> Result<String, String> {
-        let mut is_running = self.is_running.lock().unwrap();
-        if *is_running {
-            return Ok("Monitoring is already running".to_string());
+        {
+            let mut is_running = self.is_running.lock_or_recover();
+            if *is_running {
+                return Ok("Monitoring is already running".to_string());
+            }
+            *is_running = true;
         }
-        *is_running = true;
+
+        self.spawn_worker();
 
         // Update status
+        let previous_status = self.get_status();
         {
-            let mut status = self.status.lock().unwrap();
+            let mut status = self.status.lock_or_recover();
             status.is_active = true;
-            status.last_action = Some("Monitoring started".to_string());
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::MonitoringStarted, "Monitoring started"));
             status.last_check = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
         }
 
+        self.persist_state();
+        self.emit_status_change(&previous_status);
         Ok("Monitoring started successfully".to_string())
     }
 
     pub fn stop_monitoring(&self) -> Result<String, String> {
-        let mut is_running = self.is_running.lock().unwrap();
-        if !*is_running {
-            return Ok("Monitoring is not running".to_string());
+        {
+            let mut is_running = self.is_running.lock_or_recover();
+            if !*is_running {
+                return Ok("Monitoring is not running".to_string());
+            }
+            *is_running = false;
         }
-        *is_running = false;
+
+        self.stop_worker();
+        *self.manual_meeting.lock_or_recover() = false;
 
         // Update status
+        let previous_status = self.get_status();
         {
-            let mut status = self.status.lock().unwrap();
+            let mut status = self.status.lock_or_recover();
             status.is_active = false;
-            status.last_action = Some("Monitoring stopped".to_string());
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::MonitoringStopped, "Monitoring stopped"));
             status.last_check = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
         }
 
+        self.persist_state();
+        self.emit_status_change(&previous_status);
         Ok("Monitoring stopped successfully".to_string())
     }
 
+    /// Stops the worker before the app quits. Unlike `stop_monitoring`, whether monitoring
+    /// was on is kept for the next launch. With `restore_music_on_quit`, music paused,
+    /// ducked or muted for a meeting, and a Focus or on-air light turned on for it, are
+    /// restored first.
+    pub fn shutdown(&self) {
+        let was_running = std::mem::replace(&mut *self.is_running.lock_or_recover(), false);
+        self.stop_worker();
+
+        // Let a check in progress finish first, without hanging the quit on a stuck one
+        let deadline = Instant::now() + SHUTDOWN_TICK_WAIT;
+        let _tick = loop {
+            match self.tick_lock.try_lock() {
+                Ok(guard) => break Some(guard),
+                Err(TryLockError::Poisoned(poisoned)) => break Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+                Err(TryLockError::WouldBlock) => {
+                    eprintln!("SoundBreak: Quitting while a check is still running");
+                    break None;
+                }
+            }
+        };
+
+        if self.app_config.lock_or_recover().restore_music_on_quit {
+            let meeting_state = self.meeting_state.lock_or_recover().clone();
+            if meeting_state.muted_by_soundbreak {
+                if let Err(e) = MusicController::new().set_output_muted(false) {
+                    eprintln!("SoundBreak: Failed to unmute before quitting: {}", e);
+                }
+            }
+            if meeting_state.focus_enabled_by_soundbreak {
+                let config = self.app_config.lock_or_recover().focus.clone();
+                if let Err(e) = focus::set_focus(&config, false) {
+                    eprintln!("SoundBreak: Failed to turn Focus off before quitting: {}", e);
+                }
+            }
+            if !meeting_state.hue_previous_states.is_empty() {
+                let config = self.app_config.lock_or_recover().hue.clone();
+                if let Err(e) = hue::restore(&config, &meeting_state.hue_previous_states) {
+                    eprintln!("SoundBreak: {} before quitting", e);
+                }
+            }
+            self.undo_meeting_pause("Quit");
+            self.reset_meeting_state();
+        }
+        busylight::turn_off();
+        keyboard_led::turn_off();
+        let teams_config = self.app_config.lock_or_recover().teams.clone();
+        teams::clear(&teams_config, |token| self.set_teams_refresh_token(token));
+        let google_chat_config = self.app_config.lock_or_recover().google_chat.clone();
+        google_chat::clear(&google_chat_config);
+
+        self.save_state(was_running);
+        println!("SoundBreak: Shut down");
+    }
+
+    fn spawn_worker(&self) {
+        let service = self.clone();
+        let (signal_tx, signal_rx) = mpsc::channel();
+
+        std::thread::spawn(move || loop {
+            service.tick();
+
+            // Sleep until the next check, waking up early if asked to stop and
+            // restarting the wait when the interval changes
+            loop {
+                match signal_rx.recv_timeout(service.effective_check_interval()) {
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Ok(WorkerSignal::IntervalChanged) => continue,
+                    Ok(WorkerSignal::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+
+        *self.worker.lock_or_recover() = Some(MonitoringWorker { signal_tx });
+    }
+
+    fn stop_worker(&self) {
+        let worker = self.worker.lock_or_recover().take();
+        if let Some(worker) = worker {
+            let _ = worker.signal_tx.send(WorkerSignal::Stop);
+        }
+    }
+
     pub fn toggle_monitoring(&self) -> Result<String, String> {
-        let is_running = *self.is_running.lock().unwrap();
+        let is_running = *self.is_running.lock_or_recover();
         if is_running {
             self.stop_monitoring()
         } else {
@@ -97,107 +518,2017 @@ impl MonitoringService {
         }
     }
 
-    fn perform_monitoring_check(&self) {
-        let is_running = *self.is_running.lock().unwrap();
+    /// Performs one monitoring check: detects meetings, reads music status and
+    /// pauses/resumes playback on meeting transitions.
+    ///
+    /// Driven by the background loop while monitoring is active. Does nothing when
+    /// monitoring is stopped.
+    pub fn tick(&self) {
+        let _tick = self.tick_lock.lock_or_recover();
+        let is_running = *self.is_running.lock_or_recover();
         if !is_running {
             return;
         }
 
-        // Check if enough time has passed since last check (avoid too frequent checks)
         let now = SystemTime::now();
-        {
-            let mut last_check = self.last_check_time.lock().unwrap();
-            if now.duration_since(*last_check).unwrap_or(Duration::from_secs(0)) < Duration::from_secs(1) {
-                return; // Too soon since last check
+        let tick_started = Instant::now();
+        let previous_status = self.get_status();
+
+        // Outside monitoring hours, end any meeting in progress and leave playback alone,
+        // except for apps set to ignore the schedule
+        let (within_schedule, schedule_exempt_apps, disabled_apps) = {
+            let app_config = self.app_config.lock_or_recover();
+            (
+                app_config.schedule.is_within(Local::now()),
+                app_config.schedule_exempt_apps(),
+                app_config.disabled_meeting_apps(),
+            )
+        };
+        let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        if (!within_schedule && schedule_exempt_apps.is_empty()) || self.is_snoozed(now) {
+            self.end_meeting_in_progress(now_secs);
+            self.update_effective_interval(false);
+            {
+                let mut status_guard = self.status.lock_or_recover();
+                status_guard.within_schedule = within_schedule;
+                status_guard.snoozed_until = *self.snoozed_until.lock_or_recover();
+                status_guard.inhibited_by = None;
+                status_guard.meeting_started_at = None;
+                status_guard.meeting_duration_secs = None;
+                status_guard.pause_pending_until = None;
+                status_guard.last_check = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            }
+            self.persist_state();
+            self.emit_status_change(&previous_status);
+            return;
+        }
+        if !within_schedule {
+            let meeting_app = self.meeting_state.lock_or_recover().meeting_app.clone();
+            if meeting_app.is_some_and(|app| !schedule_exempt_apps.contains(&app)) {
+                self.end_meeting_in_progress(now_secs);
             }
-            *last_check = now;
         }
 
-        // Detect meeting status
-        let meeting_status = {
-            let mut detector = self.detector.lock().unwrap();
-            detector.detect_meetings()
-        };
+        // While an inhibitor app runs, leave playback and meeting state exactly as they are,
+        // so a meeting that ends meanwhile is still handled once the app quits
+        let inhibitor_apps = self.app_config.lock_or_recover().inhibitor_apps.clone();
+        if !inhibitor_apps.is_empty() {
+            match self.detector.lock_or_recover().find_running(&inhibitor_apps) {
+                Ok(Some(app)) => {
+                    {
+                        let mut status_guard = self.status.lock_or_recover();
+                        status_guard.within_schedule = within_schedule;
+                        status_guard.snoozed_until = None;
+                        status_guard.inhibited_by = Some(app);
+                        status_guard.last_check = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+                    }
+                    self.emit_status_change(&previous_status);
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("SoundBreak: Failed to check inhibitor apps: {}", e),
+            }
+        }
 
-        // Check music status
+        // Detect meetings and read the music status at the same time; both shell out
+        // to external processes, so running them concurrently roughly halves the tick
         let music_controller = MusicController::new();
-        let music_status = music_controller.get_music_status();
+        let (screen_lock_enabled, idle_away_enabled) = {
+            let app_config = self.app_config.lock_or_recover();
+            (app_config.screen_lock.enabled, app_config.idle_away.enabled)
+        };
+        let ((detection, detection_ms), (music_result, music_status_ms), lock_result, idle_result) = std::thread::scope(|scope| {
+            let music_query = scope.spawn(|| timed(|| music_controller.try_get_music_status()));
+            let lock_query = screen_lock_enabled.then(|| scope.spawn(presence::is_screen_locked));
+            let idle_query = idle_away_enabled.then(|| scope.spawn(presence::idle_secs));
+            let detection = timed(|| self.detector.lock_or_recover().try_detect_meetings());
+            let music_result = music_query
+                .join()
+                .unwrap_or_else(|_| (Err("Music status query panicked".to_string()), 0));
+            let lock_result = lock_query.map(|query| {
+                query
+                    .join()
+                    .unwrap_or_else(|_| Err("Screen lock query panicked".to_string()))
+            });
+            let idle_result = idle_query.map(|query| {
+                query
+                    .join()
+                    .unwrap_or_else(|_| Err("Idle time query panicked".to_string()))
+            });
+            (detection, music_result, lock_result, idle_result)
+        });
+        self.record_health(|health| &mut health.detector, &detection);
+        self.record_health(|health| &mut health.music, &music_result);
+        let actions_started = Instant::now();
+        let timings = || TickTimings {
+            detection_ms,
+            music_status_ms,
+            actions_ms: actions_started.elapsed().as_millis() as u64,
+            total_ms: tick_started.elapsed().as_millis() as u64,
+        };
+
+        // Without a detection result, keep the current meeting state rather than
+        // treating the failure as the meeting having ended
+        let Ok(mut meeting_status) = detection else {
+            {
+                let mut status_guard = self.status.lock_or_recover();
+                status_guard.last_check = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+                status_guard.timings = timings();
+            }
+            self.emit_status_change(&previous_status);
+            return;
+        };
+
+        meeting_status.active_apps.splice(0..0, self.external_meeting_apps(now));
+        self.update_effective_interval(meeting_status.active_apps.iter().any(|app| app.is_running));
+
+        let music_status = match music_result {
+            Ok(music_status) => {
+                self.playback_tracker.lock_or_recover().observe(music_status.is_playing);
+                music_status
+            }
+            Err(_) => MusicStatus::default(),
+        };
+
+        let manual_meeting = *self.manual_meeting.lock_or_recover();
+        let detected_app = if manual_meeting {
+            Some(MANUAL_MEETING_APP.to_string())
+        } else {
+            meeting_status
+                .active_apps
+                .iter()
+                .find(|app| {
+                    app.is_running
+                        && !disabled_apps.contains(&app.process_name)
+                        && (within_schedule || schedule_exempt_apps.contains(&app.process_name))
+                })
+                .map(|app| app.name.clone())
+        };
+        meeting_status.in_meeting = detected_app.is_some();
 
-        let now_in_meeting = meeting_status.in_meeting;
-        let was_previously_in_meeting = *self.was_in_meeting.lock().unwrap();
+        let (was_previously_in_meeting, current_app) = {
+            let meeting_state = self.meeting_state.lock_or_recover();
+            (meeting_state.in_meeting, meeting_state.meeting_app.clone())
+        };
+        let transition_app = if was_previously_in_meeting { current_app } else { detected_app.clone() };
+        let now_in_meeting = if std::mem::take(&mut *self.manual_meeting_changed.lock_or_recover()) {
+            meeting_status.in_meeting
+        } else {
+            self.confirm_meeting_state(
+                meeting_status.in_meeting,
+                was_previously_in_meeting,
+                transition_app.as_deref(),
+                now_secs,
+            )
+        };
 
         // Handle meeting state transitions
         if now_in_meeting && !was_previously_in_meeting {
-            // Entering meeting - pause music if playing
-            if music_status.is_playing {
-                *self.music_was_playing_before_meeting.lock().unwrap() = true;
-                if let Ok(result) = music_controller.execute_action(MusicAction::Pause) {
-                    let mut status_guard = self.status.lock().unwrap();
-                    status_guard.last_action = Some(format!("Meeting started: {}", result));
-                }
-            } else {
-                *self.music_was_playing_before_meeting.lock().unwrap() = false;
+            let meeting_app = detected_app;
+            {
+                // Music may already be paused, e.g. by the screen lock trigger; the meeting
+                // takes that pause over so it is undone once the meeting ends
+                let mut meeting_state = self.meeting_state.lock_or_recover();
+                let carried_over = meeting_state.pause_only();
+                *meeting_state = MeetingState {
+                    in_meeting: true,
+                    meeting_app,
+                    meeting_started_at: Some(now_secs),
+                    paused_at: carried_over.paused_at.map(|_| now_secs),
+                    ..carried_over
+                };
             }
-            *self.was_in_meeting.lock().unwrap() = true;
+
+            let actions = self.app_config.lock_or_recover().on_meeting_start.clone();
+            self.run_transition_actions(&actions, Transition::MeetingStarted, &music_controller, &music_status, now_secs);
         } else if !now_in_meeting && was_previously_in_meeting {
-            // Exiting meeting - resume music if it was playing before
-            let should_resume = *self.music_was_playing_before_meeting.lock().unwrap();
+            self.end_meeting(&music_controller, &music_status, now_secs);
+        } else if now_in_meeting {
+            self.run_pending_pause(&music_controller, &music_status, now_secs);
+            self.guard_against_playback(&music_controller, &music_status, now_secs);
+            self.check_stale_pause(now_secs);
+        }
 
-            if should_resume {
-                if let Ok(result) = music_controller.execute_action(MusicAction::Play) {
-                    let mut status_guard = self.status.lock().unwrap();
-                    status_guard.last_action = Some(format!("Meeting ended: {}", result));
-                }
-                *self.music_was_playing_before_meeting.lock().unwrap() = false;
-            }
-            *self.was_in_meeting.lock().unwrap() = false;
+        match lock_result {
+            Some(Ok(locked)) => self.handle_screen_lock(locked, &music_controller, &music_status, now_secs),
+            Some(Err(e)) => eprintln!("SoundBreak: {}", e),
+            None => *self.screen_locked.lock_or_recover() = false,
+        }
+        match idle_result {
+            Some(Ok(idle_secs)) => self.handle_idle_time(idle_secs, &music_controller, &music_status, now_secs),
+            Some(Err(e)) => eprintln!("SoundBreak: {}", e),
+            None => *self.user_away.lock_or_recover() = false,
         }
 
         // Update status
         {
-            let mut status_guard = self.status.lock().unwrap();
+            let mut status_guard = self.status.lock_or_recover();
             status_guard.meeting_status = Some(meeting_status);
             status_guard.music_status = Some(music_status);
-            status_guard.last_check = now
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            status_guard.within_schedule = within_schedule;
+            status_guard.snoozed_until = None;
+            status_guard.inhibited_by = None;
+            status_guard.last_playback_change_by = self.playback_tracker.lock_or_recover().last_change_by;
+            {
+                let meeting_state = self.meeting_state.lock_or_recover();
+                status_guard.meeting_started_at = meeting_state.meeting_started_at;
+                status_guard.pause_pending_until = meeting_state.pause_pending_until;
+                status_guard.meeting_skipped = meeting_state.in_meeting && meeting_state.pause_cancelled;
+                status_guard.health.stale_pause = meeting_state.stale_pause_alerted && meeting_state.music_was_playing;
+            }
+            status_guard.screen_locked = *self.screen_locked.lock_or_recover();
+            status_guard.user_away = *self.user_away.lock_or_recover();
+            status_guard.resume_cooldown_until = self.resume_cooldown_until().filter(|until| *until > now_secs);
+            status_guard.timings = timings();
+            status_guard.manual_meeting = manual_meeting;
+            status_guard.meeting_duration_secs = status_guard
+                .meeting_started_at
+                .map(|started_at| now_secs.saturating_sub(started_at));
+            status_guard.last_check = now_secs;
         }
+
+        self.persist_state();
+        self.emit_status_change(&previous_status);
     }
 
-    pub fn get_status(&self) -> MonitoringStatus {
-        // Perform a monitoring check each time status is requested
-        self.perform_monitoring_check();
+    // Runs the meeting end actions and forgets the meeting
+    fn end_meeting(&self, music_controller: &MusicController, music_status: &MusicStatus, now_secs: u64) {
+        let actions = self.app_config.lock_or_recover().on_meeting_end.clone();
+        self.run_transition_actions(&actions, Transition::MeetingEnded, music_controller, music_status, now_secs);
 
-        // Return current status
-        self.status.lock().unwrap().clone()
+        let meeting_state = std::mem::take(&mut *self.meeting_state.lock_or_recover());
+        self.record_meeting_statistics(&meeting_state, now_secs);
+        self.log_meeting_to_calendar(&meeting_state, now_secs);
+
+        // Music left paused while the user is away comes back on their return instead
+        if self.is_away() {
+            *self.meeting_state.lock_or_recover() = meeting_state.pause_only();
+        }
     }
 
-    pub fn get_meeting_config(&self) -> MeetingConfig {
-        let detector = self.detector.lock().unwrap();
-        detector.get_config().clone()
+    // Ends a meeting in progress when monitoring hours end or the user snoozes, the same
+    // way as when detection sees it end, so music paused or ducked for it, and anything
+    // muted or turned on for it, is restored rather than forgotten
+    fn end_meeting_in_progress(&self, now_secs: u64) {
+        let music_controller = MusicController::new();
+        self.end_meeting_in_progress_with(
+            &music_controller,
+            || music_controller.try_get_music_status().unwrap_or_default(),
+            now_secs,
+        );
     }
 
-    pub fn update_meeting_config(&mut self, config: MeetingConfig) {
-        // Update the detector with the new config
+    // `end_meeting_in_progress`, reading the music status with `music_status` only if a
+    // meeting is in progress
+    fn end_meeting_in_progress_with(
+        &self,
+        music_controller: &MusicController,
+        music_status: impl FnOnce() -> MusicStatus,
+        now_secs: u64,
+    ) {
+        if self.meeting_state.lock_or_recover().in_meeting {
+            self.end_meeting(music_controller, &music_status(), now_secs);
+        }
+        *self.transition_counter.lock_or_recover() = TransitionCounter::default();
+    }
+
+    // Runs the screen lock actions when the lock state changed since the last check
+    fn handle_screen_lock(&self, locked: bool, music_controller: &MusicController, music_status: &MusicStatus, now_secs: u64) {
+        let was_locked = std::mem::replace(&mut *self.screen_locked.lock_or_recover(), locked);
+        if locked == was_locked {
+            return;
+        }
+
+        let screen_lock = self.app_config.lock_or_recover().screen_lock.clone();
+        let (actions, transition) = if locked {
+            (screen_lock.on_lock, Transition::ScreenLocked)
+        } else {
+            (screen_lock.on_unlock, Transition::ScreenUnlocked)
+        };
+        self.run_transition_actions(&actions, transition, music_controller, music_status, now_secs);
+    }
+
+    // Runs the idle actions when the user became away or came back since the last check
+    fn handle_idle_time(&self, idle_secs: u64, music_controller: &MusicController, music_status: &MusicStatus, now_secs: u64) {
+        let idle_away = self.app_config.lock_or_recover().idle_away.clone();
+        let away = idle_secs >= idle_away.idle_minutes.saturating_mul(60);
+        let was_away = std::mem::replace(&mut *self.user_away.lock_or_recover(), away);
+        if away == was_away {
+            return;
+        }
+
+        let (actions, transition) = if away {
+            (idle_away.on_away, Transition::UserAway)
+        } else {
+            (idle_away.on_return, Transition::UserReturned)
+        };
+        self.run_transition_actions(&actions, transition, music_controller, music_status, now_secs);
+    }
+
+    // The screen is locked or the user is idle, per the triggers that are enabled
+    fn is_away(&self) -> bool {
+        *self.screen_locked.lock_or_recover() || *self.user_away.lock_or_recover()
+    }
+
+    // Why music paused for something else must stay paused after `transition`, if it must
+    fn resume_blocked_by(&self, transition: Transition, meeting_state: &MeetingState) -> Option<&'static str> {
+        if transition != Transition::MeetingEnded && meeting_state.in_meeting {
+            Some("Meeting in progress, resuming when it ends")
+        } else if transition != Transition::ScreenUnlocked && *self.screen_locked.lock_or_recover() {
+            Some("Screen is locked, resuming on unlock")
+        } else if transition != Transition::UserReturned && *self.user_away.lock_or_recover() {
+            Some("User is away, resuming on return")
+        } else {
+            None
+        }
+    }
+
+    fn run_transition_actions(
+        &self,
+        actions: &[TransitionAction],
+        transition: Transition,
+        music_controller: &MusicController,
+        music_status: &MusicStatus,
+        now_secs: u64,
+    ) {
+        let results: Vec<ActionResult> = actions
+            .iter()
+            .map(|action| {
+                let result = self.execute_transition_action(action, transition, music_controller, music_status, now_secs);
+                ActionResult::new(action.clone(), result)
+            })
+            .collect();
+
+        self.audit_log.record(&AuditEvent::Transition {
+            timestamp: now_secs,
+            transition: transition.hook_event().to_string(),
+            app: if transition.is_meeting() {
+                self.meeting_state.lock_or_recover().meeting_app.clone()
+            } else {
+                None
+            },
+            results: results.clone(),
+        });
+
         {
-            let mut detector = self.detector.lock().unwrap();
-            detector.update_config(config.clone());
+            let mut status_guard = self.status.lock_or_recover();
+            if let Some(failed) = results.iter().rev().find(|result| !result.success) {
+                status_guard.health.last_error = Some(failed.message.clone());
+            }
+            status_guard.last_action_results = results;
         }
-        
-        // Save the configuration to persistent storage
-        let app_config = crate::config::AppConfig {
-            meeting_config: config,
+
+        if let Some(event) = transition.webhook_event() {
+            let meeting_app = self.meeting_state.lock_or_recover().meeting_app.clone();
+            self.fire_webhooks(event, meeting_app);
+        }
+    }
+
+    fn execute_transition_action(
+        &self,
+        action: &TransitionAction,
+        transition: Transition,
+        music_controller: &MusicController,
+        music_status: &MusicStatus,
+        now_secs: u64,
+    ) -> Result<String, String> {
+        if self.is_dry_run() {
+            return Ok(self.simulate_transition_action(action, transition, music_status));
+        }
+
+        let context = transition.context();
+        let meeting_app = if transition.is_meeting() {
+            self.meeting_state.lock_or_recover().meeting_app.clone()
+        } else {
+            None
         };
-        
-        if let Err(e) = ConfigManager::save_config(&app_config) {
-            eprintln!("SoundBreak: Failed to save configuration: {}", e);
+
+        match action {
+            TransitionAction::PauseMusic => {
+                if !music_status.is_playing {
+                    return Ok("Music is not playing".to_string());
+                }
+                if transition == Transition::MeetingStarted {
+                    if !self.app_config.lock_or_recover().pause_on_meeting_start {
+                        return Ok("Pausing on meeting start is turned off".to_string());
+                    }
+
+                    // Give the user a moment to cancel; the pause itself happens in a later tick
+                    let countdown_secs = self.app_config.lock_or_recover().pause_countdown_secs;
+                    if countdown_secs > 0 {
+                        self.meeting_state.lock_or_recover().pause_pending_until = Some(now_secs + countdown_secs);
+                        self.notify_pending_pause(countdown_secs);
+                        return Ok(format!("Pausing music in {}s", countdown_secs));
+                    }
+                }
+
+                let result = self.pause_music(music_controller, music_status, meeting_app.clone(), context, now_secs);
+                if result.is_ok() && transition == Transition::MeetingStarted {
+                    self.offer_meeting_actions(meeting_app);
+                }
+                result
+            }
+            TransitionAction::ResumeMusic => {
+                let meeting_state = self.meeting_state.lock_or_recover().clone();
+
+                // Leave music for whichever of the meeting, screen lock and idle time ends last
+                if let Some(reason) = self.resume_blocked_by(transition, &meeting_state) {
+                    return Ok(reason.to_string());
+                }
+
+                if let Some(volume) = meeting_state.ducked_from_volume {
+                    let result = self.restore_ducked_volume(music_controller, &meeting_state, volume);
+                    if result.is_ok() {
+                        self.mark_resumed();
+                    }
+                    self.notify_action(ActionKind::RestoreVolume, meeting_app.as_deref(), context, &result);
+                    self.record_action(ActionKind::RestoreVolume, meeting_app, context, &result);
+                    return result;
+                }
+
+                if !meeting_state.music_was_playing || meeting_state.paused_at.is_none() {
+                    return Ok("Music was not paused by SoundBreak".to_string());
+                }
+                match transition {
+                    Transition::ScreenUnlocked if !self.app_config.lock_or_recover().screen_lock.resume_on_unlock => {
+                        return Ok("Resuming on unlock is turned off".to_string());
+                    }
+                    Transition::UserReturned if !self.app_config.lock_or_recover().idle_away.resume_on_return => {
+                        return Ok("Resuming on return is turned off".to_string());
+                    }
+                    Transition::MeetingEnded => {
+                        if !self.app_config.lock_or_recover().resume_on_meeting_end {
+                            if !self.app_config.lock_or_recover().notifications.wants_actions() {
+                                return Ok("Resuming on meeting end is turned off".to_string());
+                            }
+                            // Leave music paused, but offer to resume it from the notification
+                            self.meeting_state.lock_or_recover().music_was_playing = false;
+                            self.ask_to_resume(meeting_state.paused_spoken_word, meeting_app);
+                            return Ok("Offered to resume music".to_string());
+                        }
+
+                        if self.app_config.lock_or_recover().ask_before_resume {
+                            self.meeting_state.lock_or_recover().music_was_playing = false;
+                            self.ask_to_resume(meeting_state.paused_spoken_word, meeting_app);
+                            return Ok("Asked whether to resume music".to_string());
+                        }
+                    }
+                    _ => {}
+                }
+
+                let result = self.resume_paused_music(music_controller, meeting_state.paused_spoken_word, meeting_app, context);
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().music_was_playing = false;
+                }
+                result
+            }
+            TransitionAction::MuteSystem => {
+                // A failed check is recorded like a failed mute
+                let result = match music_controller.is_output_muted() {
+                    Ok(true) => return Ok("System output already muted".to_string()),
+                    Ok(false) => music_controller.set_output_muted(true),
+                    Err(e) => Err(e),
+                };
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().muted_by_soundbreak = true;
+                }
+                self.notify_action(ActionKind::Mute, meeting_app.as_deref(), context, &result);
+                self.record_action(ActionKind::Mute, meeting_app, context, &result);
+                result
+            }
+            TransitionAction::UnmuteSystem => {
+                if !self.meeting_state.lock_or_recover().muted_by_soundbreak {
+                    return Ok("System output was not muted by SoundBreak".to_string());
+                }
+                let result = music_controller.set_output_muted(false);
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().muted_by_soundbreak = false;
+                }
+                self.notify_action(ActionKind::Unmute, meeting_app.as_deref(), context, &result);
+                self.record_action(ActionKind::Unmute, meeting_app, context, &result);
+                result
+            }
+            TransitionAction::EnableFocus => {
+                // Without Full Disk Access the state can't be read; assume no Focus is on
+                if focus::is_focus_on().unwrap_or(false) {
+                    return Ok("A Focus is already on".to_string());
+                }
+                let config = self.app_config.lock_or_recover().focus.clone();
+                let result = focus::set_focus(&config, true);
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().focus_enabled_by_soundbreak = true;
+                }
+                result
+            }
+            TransitionAction::RestoreFocus => {
+                if !self.meeting_state.lock_or_recover().focus_enabled_by_soundbreak {
+                    return Ok("Focus was not turned on by SoundBreak".to_string());
+                }
+                let config = self.app_config.lock_or_recover().focus.clone();
+                let result = focus::set_focus(&config, false);
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().focus_enabled_by_soundbreak = false;
+                }
+                result
+            }
+            TransitionAction::HueOnAir => {
+                if !self.meeting_state.lock_or_recover().hue_previous_states.is_empty() {
+                    return Ok("The on-air light is already on".to_string());
+                }
+                let config = self.app_config.lock_or_recover().hue.clone();
+                hue::on_air(&config).map(|(previous_states, message)| {
+                    self.meeting_state.lock_or_recover().hue_previous_states = previous_states;
+                    message
+                })
+            }
+            TransitionAction::HueRestore => {
+                let previous_states = self.meeting_state.lock_or_recover().hue_previous_states.clone();
+                if previous_states.is_empty() {
+                    return Ok("The on-air light was not turned on by SoundBreak".to_string());
+                }
+                let config = self.app_config.lock_or_recover().hue.clone();
+                let result = hue::restore(&config, &previous_states);
+                if result.is_ok() {
+                    self.meeting_state.lock_or_recover().hue_previous_states.clear();
+                }
+                result
+            }
+            TransitionAction::NotifyPeers => match transition.peer_event() {
+                Some(event) => {
+                    self.notify_peers(event);
+                    Ok("Peers notified".to_string())
+                }
+                None => Ok("Peers are only notified about meetings".to_string()),
+            },
+            TransitionAction::RunHook => self.run_hook(transition, music_status, now_secs),
         }
     }
-}
 
-impl Default for MonitoringService {
-    fn default() -> Self {
-        Self::new()
+    // Pauses music (or only ducks it for short meetings) and records the outcome
+    fn pause_music(
+        &self,
+        music_controller: &MusicController,
+        music_status: &MusicStatus,
+        meeting_app: Option<String>,
+        context: &str,
+        now_secs: u64,
+    ) -> Result<String, String> {
+        let is_spoken_word = self.app_config.lock_or_recover().podcast.is_spoken_word(music_status);
+        {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            meeting_state.music_was_playing = true;
+            meeting_state.paused_spoken_word = is_spoken_word;
+        }
+
+        let (kind, result) = match self.soft_pause_percent(meeting_app.as_deref()) {
+            Some(percent) => match self.duck_volume(music_controller, music_status, percent) {
+                Ok(result) => (ActionKind::Duck, Ok(result)),
+                Err(_) => (ActionKind::Pause, music_controller.execute_action(MusicAction::Pause)),
+            },
+            None => (ActionKind::Pause, music_controller.execute_action(MusicAction::Pause)),
+        };
+        if result.is_ok() {
+            self.meeting_state.lock_or_recover().paused_at = Some(now_secs);
+            if kind == ActionKind::Pause {
+                self.playback_tracker.lock_or_recover().changed_by_soundbreak(false);
+            }
+        }
+        self.notify_action(kind, meeting_app.as_deref(), context, &result);
+        self.record_action(kind, meeting_app, context, &result);
+        result
+    }
+
+    // Carries out a pause whose countdown has run out
+    fn run_pending_pause(&self, music_controller: &MusicController, music_status: &MusicStatus, now_secs: u64) {
+        let meeting_app = {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            match meeting_state.pause_pending_until {
+                Some(until) if until <= now_secs => meeting_state.pause_pending_until = None,
+                _ => return,
+            }
+            meeting_state.meeting_app.clone()
+        };
+
+        if music_status.is_playing
+            && self.pause_music(music_controller, music_status, meeting_app.clone(), "Meeting started", now_secs).is_ok()
+        {
+            self.offer_meeting_actions(meeting_app);
+        }
+    }
+
+    // Pauses music that started playing mid-meeting once it has played for the
+    // configured delay, if the re-pause guard is enabled
+    fn guard_against_playback(&self, music_controller: &MusicController, music_status: &MusicStatus, now_secs: u64) {
+        let (guard, pause_on_meeting_start, dry_run) = {
+            let app_config = self.app_config.lock_or_recover();
+            (app_config.repause_guard.clone(), app_config.pause_on_meeting_start, app_config.dry_run)
+        };
+
+        let meeting_app = {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            let guarded = guard.enabled
+                && pause_on_meeting_start
+                && !dry_run
+                && !meeting_state.pause_cancelled
+                && meeting_state.pause_pending_until.is_none()
+                && meeting_state.ducked_from_volume.is_none();
+            if !guarded || !music_status.is_playing {
+                meeting_state.playing_during_meeting_since = None;
+                return;
+            }
+
+            let since = *meeting_state.playing_during_meeting_since.get_or_insert(now_secs);
+            if now_secs.saturating_sub(since) < guard.delay_secs {
+                return;
+            }
+            meeting_state.playing_during_meeting_since = None;
+            meeting_state.meeting_app.clone()
+        };
+
+        let _ = self.pause_music(music_controller, music_status, meeting_app, "Music played during meeting", now_secs);
+    }
+
+    // Asks the user once per meeting whether a meeting that has kept music paused for
+    // more than `stale_pause_alert_hours` is still going on
+    fn check_stale_pause(&self, now_secs: u64) {
+        let alert_hours = self.app_config.lock_or_recover().stale_pause_alert_hours;
+        {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            let Some(paused_at) = meeting_state.paused_at else {
+                return;
+            };
+            if alert_hours == 0
+                || !meeting_state.music_was_playing
+                || meeting_state.stale_pause_alerted
+                || now_secs.saturating_sub(paused_at) < alert_hours.saturating_mul(3600)
+            {
+                return;
+            }
+            meeting_state.stale_pause_alerted = true;
+        }
+
+        eprintln!("SoundBreak: Music has been paused for over {} hours", alert_hours);
+        if let Some(app_handle) = self.app_handle.lock_or_recover().clone() {
+            let config = self.app_config.lock_or_recover().notifications.clone();
+            notifications::notify_stale_pause(&app_handle, &config, alert_hours);
+        }
+        self.ask_about_stale_pause(alert_hours);
+    }
+
+    // Asks on a background thread whether the meeting is still on, resuming music if not
+    fn ask_about_stale_pause(&self, hours: u64) {
+        let service = self.clone();
+        std::thread::spawn(move || {
+            let message = format!(
+                "Music has been paused for over {} hours for a meeting. Is the meeting still going on?",
+                hours
+            );
+            let answer = prompt::ask(&message, &["Resume Music", "Still in Meeting"], STALE_PAUSE_PROMPT_TIMEOUT);
+            if answer.as_deref() != Some("Resume Music") {
+                return;
+            }
+
+            let previous_status = service.get_status();
+            let (was_spoken_word, meeting_app) = {
+                let mut meeting_state = service.meeting_state.lock_or_recover();
+                // Keep the re-pause guard from pausing the music again for this meeting
+                meeting_state.pause_cancelled = true;
+                (meeting_state.paused_spoken_word, meeting_state.meeting_app.clone())
+            };
+            let result = service.resume_paused_music(&MusicController::new(), was_spoken_word, meeting_app, "Long pause confirmed");
+            if result.is_ok() {
+                service.meeting_state.lock_or_recover().music_was_playing = false;
+            }
+            service.status.lock_or_recover().health.stale_pause = false;
+            service.persist_state();
+            service.emit_status_change(&previous_status);
+        });
+    }
+
+    // Offers, on a background thread, to undo the pause that a meeting just started,
+    // either for the rest of the meeting or by snoozing automatic actions
+    fn offer_meeting_actions(&self, meeting_app: Option<String>) {
+        if !self.app_config.lock_or_recover().notifications.wants_actions() {
+            return;
+        }
+
+        let service = self.clone();
+        std::thread::spawn(move || {
+            let message = match &meeting_app {
+                Some(app) => tr_args("{app} meeting detected — music paused", &[("app", app)]),
+                None => tr("Meeting started — music paused"),
+            };
+            let snooze = tr("Snooze 30m");
+            let keep_playing = tr("Keep Playing");
+            let answer = prompt::ask(&message, &[&snooze, &keep_playing, &tr("OK")], MEETING_ACTIONS_PROMPT_TIMEOUT);
+            let context = match answer {
+                Some(answer) if answer == keep_playing => "Kept playing",
+                Some(answer) if answer == snooze => {
+                    let _ = service.snooze(MEETING_ACTIONS_SNOOZE_MINUTES);
+                    "Snoozed from notification"
+                }
+                _ => return,
+            };
+            service.undo_meeting_pause(context);
+        });
+    }
+
+    // Restores the music paused or ducked for the current meeting, keeping the re-pause
+    // guard from pausing it again
+    fn undo_meeting_pause(&self, context: &str) {
+        let previous_status = self.get_status();
+        let music_controller = MusicController::new();
+        let meeting_state = {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            meeting_state.pause_cancelled = true;
+            meeting_state.clone()
+        };
+
+        if let Some(volume) = meeting_state.ducked_from_volume {
+            let result = self.restore_ducked_volume(&music_controller, &meeting_state, volume);
+            if result.is_ok() {
+                self.mark_resumed();
+            }
+            self.record_action(ActionKind::RestoreVolume, meeting_state.meeting_app, context, &result);
+        } else if meeting_state.music_was_playing {
+            let result =
+                self.resume_paused_music(&music_controller, meeting_state.paused_spoken_word, meeting_state.meeting_app, context);
+            if result.is_ok() {
+                self.meeting_state.lock_or_recover().music_was_playing = false;
+            }
+        }
+
+        self.persist_state();
+        self.emit_status_change(&previous_status);
+    }
+
+    /// Treats the user as in a meeting (or not) whatever detection says, e.g. during a
+    /// phone call; music is paused or resumed right away
+    pub fn set_manual_meeting(&self, enabled: bool) -> Result<String, String> {
+        if !*self.is_running.lock_or_recover() {
+            return Err("Start monitoring to use the meeting override".to_string());
+        }
+        if std::mem::replace(&mut *self.manual_meeting.lock_or_recover(), enabled) == enabled {
+            return Ok(format!("Manual meeting is already {}", if enabled { "on" } else { "off" }));
+        }
+        *self.manual_meeting_changed.lock_or_recover() = true;
+
+        let message = if enabled { "Manual meeting started" } else { "Manual meeting ended" };
+        self.set_last_action(&mut self.status.lock_or_recover(), LastAction::new(LastActionKind::ManualMeeting, message));
+        self.tick();
+        Ok(message.to_string())
+    }
+
+    pub fn is_manual_meeting(&self) -> bool {
+        *self.manual_meeting.lock_or_recover()
+    }
+
+    /// Records that another tool, e.g. a softphone or a browser extension, says a meeting
+    /// started or ended. Detection treats each `source` as a meeting app of that name
+    /// while its meeting lasts, and acts on the change right away.
+    pub fn set_external_meeting(&self, source: &str, active: bool) -> Result<String, String> {
+        let source = source.trim();
+        if source.is_empty() {
+            return Err("The external meeting source needs a name".to_string());
+        }
+        if source.chars().count() > MAX_EXTERNAL_SOURCE_LEN || source.chars().any(char::is_control) {
+            return Err(format!("Invalid external meeting source {:?}", source));
+        }
+
+        let changed = {
+            let mut external_meetings = self.external_meetings.lock_or_recover();
+            if active {
+                let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                external_meetings.insert(source.to_string(), now_secs).is_none()
+            } else {
+                external_meetings.remove(source).is_some()
+            }
+        };
+        let state = if active { "started" } else { "ended" };
+        if !changed {
+            return Ok(format!("{} meeting has already {}", source, state));
+        }
+        println!("SoundBreak: {} says a meeting {}", source, state);
+
+        if *self.is_running.lock_or_recover() {
+            *self.manual_meeting_changed.lock_or_recover() = true;
+            self.tick();
+        }
+        Ok(format!("{} meeting {}", source, state))
+    }
+
+    // Stand-ins for the meetings external sources reported, dropping the ones that timed out
+    fn external_meeting_apps(&self, now: SystemTime) -> Vec<MeetingApp> {
+        let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut external_meetings = self.external_meetings.lock_or_recover();
+        external_meetings.retain(|_, since| now_secs.saturating_sub(*since) < EXTERNAL_MEETING_TIMEOUT.as_secs());
+        external_meetings
+            .keys()
+            .map(|source| MeetingApp {
+                name: source.clone(),
+                process_name: source.clone(),
+                is_running: true,
+            })
+            .collect()
+    }
+
+    /// Skips the pending pause for the current meeting
+    pub fn cancel_pending_pause(&self) -> Result<String, String> {
+        let previous_status = self.get_status();
+        {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            if meeting_state.pause_pending_until.take().is_none() {
+                return Err("No pause is pending".to_string());
+            }
+            meeting_state.pause_cancelled = true;
+        }
+        {
+            let mut status = self.status.lock_or_recover();
+            status.pause_pending_until = None;
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::PauseCancelled, "Pause cancelled for this meeting"));
+        }
+
+        self.persist_state();
+        self.emit_status_change(&previous_status);
+        Ok("Music will keep playing during this meeting".to_string())
+    }
+
+    /// Leaves music alone for the rest of the current meeting: a pending pause is dropped
+    /// and music paused or lowered for it comes back. The next meeting is handled as usual.
+    pub fn skip_meeting(&self) -> Result<String, String> {
+        {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            if !meeting_state.in_meeting {
+                return Err("No meeting in progress".to_string());
+            }
+            if meeting_state.pause_cancelled && meeting_state.pause_pending_until.is_none() {
+                return Ok("This meeting is already skipped".to_string());
+            }
+            meeting_state.pause_pending_until = None;
+        }
+        {
+            let mut status = self.status.lock_or_recover();
+            status.pause_pending_until = None;
+            status.meeting_skipped = true;
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::PauseCancelled, "Skipped this meeting"));
+        }
+
+        self.undo_meeting_pause("Meeting skipped");
+        Ok("Music will be left alone until this meeting ends".to_string())
+    }
+
+    fn notify_pending_pause(&self, countdown_secs: u64) {
+        let Some(app_handle) = self.app_handle.lock_or_recover().clone() else {
+            return;
+        };
+        let config = self.app_config.lock_or_recover().notifications.clone();
+        notifications::notify_pending_pause(&app_handle, &config, countdown_secs);
+    }
+
+    // Resumes playback paused by SoundBreak, unless the user has played or paused
+    // music since then, and records the outcome
+    fn resume_paused_music(
+        &self,
+        music_controller: &MusicController,
+        was_spoken_word: bool,
+        meeting_app: Option<String>,
+        context: &str,
+    ) -> Result<String, String> {
+        let paused_by_soundbreak =
+            self.playback_tracker.lock_or_recover().last_change_by == Some(PlaybackInitiator::SoundBreak);
+        let mut resumed = false;
+        let result = if paused_by_soundbreak {
+            self.resume_playback(music_controller, was_spoken_word)
+                .map(|(message, did_resume)| {
+                    resumed = did_resume;
+                    message
+                })
+        } else {
+            Ok("Playback was changed manually, not resuming".to_string())
+        };
+        if resumed {
+            self.playback_tracker.lock_or_recover().changed_by_soundbreak(true);
+            self.mark_resumed();
+        }
+        if resumed || result.is_err() {
+            self.notify_action(ActionKind::Resume, meeting_app.as_deref(), context, &result);
+        }
+        self.record_action(ActionKind::Resume, meeting_app, context, &result);
+        result
+    }
+
+    // Asks on a background thread whether to resume, so monitoring carries on meanwhile
+    fn ask_to_resume(&self, was_spoken_word: bool, meeting_app: Option<String>) {
+        let service = self.clone();
+        std::thread::spawn(move || {
+            let resume_now = tr("Resume Now");
+            let answer = prompt::ask(&tr("Your meeting has ended. Resume music?"), &[&tr("Not Now"), &resume_now], RESUME_PROMPT_TIMEOUT);
+            if answer.as_deref() != Some(resume_now.as_str()) {
+                println!("SoundBreak: Left music paused after the meeting");
+                return;
+            }
+
+            let previous_status = service.get_status();
+            let _ = service.resume_paused_music(&MusicController::new(), was_spoken_word, meeting_app, "Resume prompt");
+            service.emit_status_change(&previous_status);
+        });
+    }
+
+    // Describes what `execute_transition_action` would do, tracking just enough meeting
+    // state that the end of the meeting reports the matching resume
+    fn simulate_transition_action(&self, action: &TransitionAction, transition: Transition, music_status: &MusicStatus) -> String {
+        let message = match action {
+            TransitionAction::PauseMusic if music_status.is_playing => {
+                self.meeting_state.lock_or_recover().music_was_playing = true;
+                "Would pause music".to_string()
+            }
+            TransitionAction::PauseMusic => "Music is not playing".to_string(),
+            TransitionAction::ResumeMusic if self.meeting_state.lock_or_recover().music_was_playing => {
+                if self.app_config.lock_or_recover().ask_before_resume {
+                    "Would ask whether to resume music".to_string()
+                } else {
+                    "Would resume music".to_string()
+                }
+            }
+            TransitionAction::ResumeMusic => "Music was not paused by SoundBreak".to_string(),
+            TransitionAction::MuteSystem => "Would mute system output".to_string(),
+            TransitionAction::UnmuteSystem => "Would unmute system output".to_string(),
+            TransitionAction::EnableFocus => "Would turn Focus on".to_string(),
+            TransitionAction::RestoreFocus => "Would turn Focus off".to_string(),
+            TransitionAction::HueOnAir => "Would turn the on-air light on".to_string(),
+            TransitionAction::HueRestore => "Would turn the on-air light off".to_string(),
+            TransitionAction::NotifyPeers => "Would notify peers".to_string(),
+            TransitionAction::RunHook => {
+                let hooks = self.app_config.lock_or_recover().hooks.clone();
+                match transition.hook_script(&hooks).filter(|script| !script.trim().is_empty()) {
+                    Some(script) => format!("Would run hook {}", script),
+                    None => "No hook configured".to_string(),
+                }
+            }
+        };
+
+        println!("SoundBreak: [dry run] {}: {}", transition.context(), message);
+        let kind = match action {
+            TransitionAction::PauseMusic => LastActionKind::Pause,
+            TransitionAction::ResumeMusic => LastActionKind::Resume,
+            TransitionAction::MuteSystem => LastActionKind::Mute,
+            TransitionAction::UnmuteSystem => LastActionKind::Unmute,
+            TransitionAction::EnableFocus => LastActionKind::FocusOn,
+            TransitionAction::RestoreFocus => LastActionKind::FocusOff,
+            TransitionAction::HueOnAir => LastActionKind::OnAirLight,
+            TransitionAction::HueRestore => LastActionKind::OnAirLightOff,
+            TransitionAction::NotifyPeers => LastActionKind::NotifyPeers,
+            TransitionAction::RunHook => LastActionKind::RunHook,
+        };
+        self.set_last_action(&mut self.status.lock_or_recover(), LastAction {
+            trigger: Some(transition.context().to_string()),
+            app: self.meeting_state.lock_or_recover().meeting_app.clone(),
+            dry_run: true,
+            ..LastAction::new(kind, message.clone())
+        });
+        message
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.app_config.lock_or_recover().dry_run
+    }
+
+    pub fn set_dry_run(&self, enabled: bool) -> Result<(), String> {
+        let previous_status = self.get_status();
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            app_config.dry_run = enabled;
+            app_config.clone()
+        };
+        self.status.lock_or_recover().dry_run = enabled;
+
+        self.emit_status_change(&previous_status);
+        self.save_app_config(&app_config)
+    }
+
+    // Shows the outcome in the status and appends it to the action history
+    fn record_action(&self, kind: ActionKind, app: Option<String>, context: &str, result: &Result<String, String>) {
+        let message = match result {
+            Ok(message) | Err(message) => message.clone(),
+        };
+        self.set_last_action(&mut self.status.lock_or_recover(), LastAction {
+            trigger: Some(context.to_string()),
+            app: app.clone(),
+            success: result.is_ok(),
+            ..LastAction::new(kind.into(), message)
+        });
+        self.history.record(kind, app.clone(), result);
+
+        let webhook_event = match kind {
+            ActionKind::Pause => Some(WebhookEvent::MusicPaused),
+            ActionKind::Resume => Some(WebhookEvent::MusicResumed),
+            _ => None,
+        };
+        if let Some(event) = webhook_event.filter(|_| result.is_ok()) {
+            self.fire_webhooks(event, app);
+        }
+    }
+
+    // Delivers the webhooks configured for `event` on background threads, recording
+    // each delivery in the action history
+    fn fire_webhooks(&self, event: WebhookEvent, app: Option<String>) {
+        let webhooks: Vec<Webhook> = {
+            let config = self.app_config.lock_or_recover();
+            config
+                .webhooks
+                .iter()
+                .filter(|webhook| webhook.fires_on(event))
+                .cloned()
+                .chain(config.ifttt.webhook(event))
+                .chain(config.zapier.webhook(event))
+                .collect()
+        };
+        if self.is_dry_run() {
+            for webhook in &webhooks {
+                println!("SoundBreak: [dry run] Would call webhook {} ({})", webhook.display_name(), event.name());
+            }
+            return;
+        }
+
+        let music = self.status.lock_or_recover().music_status.clone().unwrap_or_default();
+        let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let meeting_started_at = self.meeting_state.lock_or_recover().meeting_started_at;
+        let context = WebhookContext {
+            event,
+            app,
+            timestamp: now_secs,
+            duration_secs: meeting_started_at.map(|started_at| now_secs.saturating_sub(started_at)),
+            title: music.title,
+            artist: music.artist,
+        };
+        for webhook in webhooks {
+            let service = self.clone();
+            let context = context.clone();
+            std::thread::spawn(move || {
+                let result = webhooks::deliver(&webhook, &context);
+                match &result {
+                    Ok(message) => println!("SoundBreak: {}", message),
+                    Err(e) => eprintln!("SoundBreak: {}", e),
+                }
+                service.history.record(ActionKind::Webhook, context.app, &result);
+            });
+        }
+    }
+
+    // Shows `last_action` in the status and appends it to the audit log
+    fn set_last_action(&self, status: &mut MonitoringStatus, last_action: LastAction) {
+        self.audit_log.record(&AuditEvent::Action(last_action.clone()));
+        status.last_action = Some(last_action);
+    }
+
+    fn record_health<T>(&self, backend: impl FnOnce(&mut MonitoringHealth) -> &mut BackendHealth, result: &Result<T, String>) {
+        let mut status_guard = self.status.lock_or_recover();
+        backend(&mut status_guard.health).record(result);
+        if let Err(e) = result {
+            eprintln!("SoundBreak: {}", e);
+            status_guard.health.last_error = Some(e.clone());
+        }
+    }
+
+    fn notify_action(&self, kind: ActionKind, app: Option<&str>, context: &str, result: &Result<String, String>) {
+        let Some(app_handle) = self.app_handle.lock_or_recover().clone() else {
+            return;
+        };
+        let config = self.app_config.lock_or_recover().notifications.clone();
+        let reason = match app {
+            Some(app) => tr_args("{app} meeting detected", &[("app", &app)]),
+            None => tr(context).to_lowercase(),
+        };
+        notifications::notify_action(&app_handle, &config, kind, &reason, result);
+    }
+
+    fn record_meeting_statistics(&self, meeting_state: &MeetingState, ended_at: u64) {
+        let paused_secs = meeting_state
+            .paused_at
+            .map(|paused_at| ended_at.saturating_sub(paused_at))
+            .unwrap_or(0);
+
+        if let Some(started_at) = meeting_state.meeting_started_at {
+            self.statistics.record_meeting(started_at, ended_at, paused_secs);
+
+            let journal_config = self.app_config.lock_or_recover().journal.clone();
+            if journal_config.enabled {
+                let entry = JournalEntry {
+                    started_at,
+                    ended_at,
+                    app: meeting_state.meeting_app.clone(),
+                    paused_secs,
+                };
+                if let Err(e) = self.journal.record(&journal_config, entry) {
+                    eprintln!("SoundBreak: {}", e);
+                }
+            }
+        }
+    }
+
+    // Adds the meeting to the calendar on a background thread if it wasn't scheduled
+    fn log_meeting_to_calendar(&self, meeting_state: &MeetingState, ended_at: u64) {
+        let config = self.app_config.lock_or_recover().calendar_log.clone();
+        let Some(started_at) = meeting_state.meeting_started_at else {
+            return;
+        };
+        if !config.enabled || ended_at.saturating_sub(started_at) < config.min_minutes * 60 {
+            return;
+        }
+
+        let title = match meeting_state.meeting_app.as_deref() {
+            Some(app) if app != MANUAL_MEETING_APP => format!("{} meeting", app),
+            _ => "Meeting".to_string(),
+        };
+        if self.is_dry_run() {
+            println!("SoundBreak: [dry run] Would add {} to the calendar", title);
+            return;
+        }
+        std::thread::spawn(move || match calendar::log_meeting(&config, &title, started_at, ended_at) {
+            Ok(message) => println!("SoundBreak: {}", message),
+            Err(e) => eprintln!("SoundBreak: {}", e),
+        });
+    }
+
+    pub fn get_statistics(&self) -> UsageStatistics {
+        self.statistics.get_statistics()
+    }
+
+    pub fn get_action_history(&self, limit: usize) -> Vec<ActionRecord> {
+        self.history.recent(limit)
+    }
+
+    fn reset_meeting_state(&self) {
+        *self.meeting_state.lock_or_recover() = MeetingState::default();
+        *self.transition_counter.lock_or_recover() = TransitionCounter::default();
+    }
+
+    // Saves monitoring state to disk if it changed since the last save
+    fn persist_state(&self) {
+        self.save_state(*self.is_running.lock_or_recover());
+    }
+
+    fn save_state(&self, is_active: bool) {
+        let state = PersistedState {
+            is_active,
+            snoozed_until: *self.snoozed_until.lock_or_recover(),
+            meeting: self.meeting_state.lock_or_recover().clone(),
+            playback: self.playback_tracker.lock_or_recover().clone(),
+        };
+
+        let mut persisted_state = self.persisted_state.lock_or_recover();
+        if persisted_state.as_ref() == Some(&state) {
+            return;
+        }
+
+        match self.state_store.save(&state) {
+            Ok(()) => *persisted_state = Some(state),
+            Err(e) => eprintln!("SoundBreak: Failed to save monitoring state: {}", e),
+        }
+    }
+
+    /// Whether monitoring should start on launch: restores the state from before the last
+    /// quit, defaulting to active on first launch
+    pub fn start_on_launch(&self) -> bool {
+        self.persisted_state
+            .lock_or_recover()
+            .as_ref()
+            .map(|state| state.is_active)
+            .unwrap_or(true)
+    }
+
+    fn is_snoozed(&self, now: SystemTime) -> bool {
+        let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut snoozed_until = self.snoozed_until.lock_or_recover();
+        match *snoozed_until {
+            Some(until) if until > now_secs => true,
+            Some(_) => {
+                *snoozed_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Suspends automatic actions for the given number of minutes
+    pub fn snooze(&self, minutes: u64) -> Result<String, String> {
+        if minutes == 0 {
+            return Err("Snooze duration must be at least one minute".to_string());
+        }
+
+        let previous_status = self.get_status();
+        let until = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + minutes * 60;
+        *self.snoozed_until.lock_or_recover() = Some(until);
+        {
+            let mut status = self.status.lock_or_recover();
+            status.snoozed_until = Some(until);
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::Snoozed, format!("Snoozed for {} min", minutes)));
+        }
+
+        self.persist_state();
+        self.emit_status_change(&previous_status);
+        Ok(format!("Automatic actions snoozed for {} minutes", minutes))
+    }
+
+    pub fn cancel_snooze(&self) -> Result<String, String> {
+        let previous_status = self.get_status();
+        *self.snoozed_until.lock_or_recover() = None;
+        {
+            let mut status = self.status.lock_or_recover();
+            status.snoozed_until = None;
+            self.set_last_action(&mut status, LastAction::new(LastActionKind::SnoozeCancelled, "Snooze cancelled"));
+        }
+
+        self.persist_state();
+        self.emit_status_change(&previous_status);
+        Ok("Snooze cancelled".to_string())
+    }
+
+    /// Starts and stops monitoring when the configured work hours begin and end
+    ///
+    /// Only acts on schedule boundaries, so toggling monitoring manually still works
+    /// until the next boundary.
+    pub fn start_scheduler(&self) {
+        let service = self.clone();
+        std::thread::spawn(move || {
+            let mut was_within_schedule: Option<bool> = None;
+            loop {
+                let schedule = service.app_config.lock_or_recover().schedule.clone();
+                if schedule.enabled {
+                    let within_schedule = schedule.is_within(Local::now());
+                    if was_within_schedule != Some(within_schedule) {
+                        // Apps that ignore the schedule still need the monitoring loop
+                        let keep_monitoring = !service.app_config.lock_or_recover().schedule_exempt_apps().is_empty();
+                        let result = if within_schedule {
+                            service.start_monitoring()
+                        } else if keep_monitoring {
+                            Ok("Monitoring hours ended; still watching apps that ignore them".to_string())
+                        } else {
+                            service.stop_monitoring()
+                        };
+                        if let Ok(msg) = result {
+                            println!("SoundBreak: Schedule - {}", msg);
+                        }
+                        was_within_schedule = Some(within_schedule);
+                    }
+                } else {
+                    was_within_schedule = None;
+                }
+
+                std::thread::sleep(SCHEDULE_CHECK_INTERVAL);
+            }
+        });
+    }
+
+    // Only reports a new meeting state once enough consecutive detections agree on it
+    // and it has held for the configured dwell time, so a flapping detection doesn't
+    // pause and resume music over and over
+    fn confirm_meeting_state(&self, detected: bool, current: bool, meeting_app: Option<&str>, now_secs: u64) -> bool {
+        let (required, flap_suppression) = {
+            let app_config = self.app_config.lock_or_recover().for_meeting_app(meeting_app);
+            (app_config.detection_confirmations.max(1), app_config.flap_suppression)
+        };
+        let mut counter = self.transition_counter.lock_or_recover();
+
+        // Right after a resume, a new meeting is ignored so back-to-back meetings don't
+        // ping-pong playback
+        if detected && !current && self.resume_cooldown_until().is_some_and(|until| now_secs < until) {
+            *counter = TransitionCounter::default();
+            return current;
+        }
+
+        if detected == current {
+            *counter = TransitionCounter::default();
+            return current;
+        }
+
+        let (count, min_dwell_secs) = if detected {
+            counter.leaving = 0;
+            counter.entering += 1;
+            (counter.entering, flap_suppression.min_secs_before_start)
+        } else {
+            counter.entering = 0;
+            counter.leaving += 1;
+            (counter.leaving, flap_suppression.min_secs_before_end)
+        };
+        let disagreeing_since = *counter.disagreeing_since.get_or_insert(now_secs);
+        let dwelled = now_secs.saturating_sub(disagreeing_since) >= min_dwell_secs;
+
+        if count >= required && dwelled {
+            *counter = TransitionCounter::default();
+            detected
+        } else {
+            current
+        }
+    }
+
+    fn resume_cooldown_until(&self) -> Option<u64> {
+        let cooldown_secs = self.app_config.lock_or_recover().flap_suppression.resume_cooldown_secs;
+        let last_resumed_at = (*self.last_resumed_at.lock_or_recover())?;
+        (cooldown_secs > 0).then(|| last_resumed_at + cooldown_secs)
+    }
+
+    fn mark_resumed(&self) {
+        let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        *self.last_resumed_at.lock_or_recover() = Some(now_secs);
+    }
+
+    pub fn set_app_handle(&self, app_handle: AppHandle) {
+        *self.app_handle.lock_or_recover() = Some(app_handle);
+    }
+
+    pub fn set_status_listener(&self, listener: StatusListener) {
+        *self.status_listener.lock_or_recover() = Some(listener);
+    }
+
+    // Passes the current status to the status listener, then emits
+    // `monitoring-status-changed` if anything besides check timestamps changed
+    fn emit_status_change(&self, previous_status: &MonitoringStatus) {
+        let status = self.get_status();
+        let listener = self.status_listener.lock_or_recover().clone();
+        if let Some(listener) = listener {
+            listener(&status);
+        }
+
+        let Some(app_handle) = self.app_handle.lock_or_recover().clone() else {
+            return;
+        };
+
+        let changes = status_diff(previous_status, &status);
+        if changes.is_empty() {
+            return;
+        }
+
+        if let Err(e) = app_handle.emit("monitoring-status-changed", StatusChangedEvent { status, changes }) {
+            eprintln!("SoundBreak: Failed to emit status change: {}", e);
+        }
+    }
+
+    // Returns the duck volume percentage when the current meeting is short enough to soft-pause
+    fn soft_pause_percent(&self, meeting_app: Option<&str>) -> Option<u8> {
+        let soft_pause = self.app_config.lock_or_recover().for_meeting_app(meeting_app).soft_pause;
+        if !soft_pause.enabled {
+            return None;
+        }
+        if soft_pause.always {
+            return Some(soft_pause.duck_volume_percent);
+        }
+
+        let expected_duration = CalendarLookup::new().current_event_duration()?;
+        if expected_duration > Duration::from_secs(soft_pause.max_meeting_minutes * 60) {
+            return None;
+        }
+
+        Some(soft_pause.duck_volume_percent)
+    }
+
+    // Lowers the volume of the player itself, so the meeting stays as loud as it was.
+    // Fails for players without a volume of their own, which are paused instead.
+    fn duck_volume(&self, music_controller: &MusicController, music_status: &MusicStatus, percent: u8) -> Result<String, String> {
+        let player = MusicController::scriptable_player(music_status).ok_or("The player's volume can't be changed")?;
+        let volume = music_controller.get_player_volume(player)?;
+        let ducked_volume = (volume as u32 * percent.min(100) as u32 / 100) as u8;
+        music_controller.set_player_volume(player, ducked_volume)?;
+        {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            meeting_state.ducked_from_volume = Some(volume);
+            meeting_state.ducked_player = Some(player.to_string());
+        }
+        Ok(format!("{} volume ducked from {}% to {}%", player, volume, ducked_volume))
+    }
+
+    // Puts back the volume lowered by `duck_volume`
+    fn restore_ducked_volume(&self, music_controller: &MusicController, meeting_state: &MeetingState, volume: u8) -> Result<String, String> {
+        let result = match &meeting_state.ducked_player {
+            Some(player) => music_controller.set_player_volume(player, volume),
+            // Ducked by an earlier version, which lowered the system output
+            None => music_controller.set_output_volume(volume),
+        };
+        if result.is_ok() {
+            let mut meeting_state = self.meeting_state.lock_or_recover();
+            meeting_state.ducked_from_volume = None;
+            meeting_state.ducked_player = None;
+        }
+        result
+    }
+
+    // Resumes playback, applying the spoken-word policy if a podcast or audiobook was paused.
+    // Returns the result message and whether playback was actually resumed.
+    fn resume_playback(&self, music_controller: &MusicController, was_spoken_word: bool) -> Result<(String, bool), String> {
+        if !was_spoken_word {
+            return music_controller.execute_action(MusicAction::Play).map(|result| (result, true));
+        }
+
+        let podcast = self.app_config.lock_or_recover().podcast.clone();
+        match podcast.resume_policy {
+            PodcastResumePolicy::Resume => music_controller.execute_action(MusicAction::Play).map(|result| (result, true)),
+            PodcastResumePolicy::RewindAndResume => {
+                if let Err(e) = music_controller.rewind(podcast.rewind_seconds) {
+                    eprintln!("SoundBreak: Failed to rewind before resuming: {}", e);
+                }
+                music_controller
+                    .execute_action(MusicAction::Play)
+                    .map(|result| (format!("{} (rewound {}s)", result, podcast.rewind_seconds), true))
+            }
+            PodcastResumePolicy::DontResume => Ok(("Spoken-word content left paused".to_string(), false)),
+        }
+    }
+
+    fn run_hook(&self, transition: Transition, music_status: &MusicStatus, now_secs: u64) -> Result<String, String> {
+        let hooks = self.app_config.lock_or_recover().hooks.clone();
+        let Some(script) = transition.hook_script(&hooks).filter(|script| !script.trim().is_empty()) else {
+            return Ok("No hook configured".to_string());
+        };
+
+        let meeting_state = self.meeting_state.lock_or_recover().clone();
+        let meeting_secs = meeting_state
+            .meeting_started_at
+            .map(|started_at| now_secs.saturating_sub(started_at))
+            .unwrap_or(0);
+        let track = match (&music_status.title, &music_status.artist) {
+            (Some(title), Some(artist)) => format!("{} — {}", title, artist),
+            (Some(title), None) => title.clone(),
+            _ => String::new(),
+        };
+        let env = vec![
+            ("SOUNDBREAK_EVENT", transition.hook_event().to_string()),
+            ("SOUNDBREAK_MEETING_APP", meeting_state.meeting_app.unwrap_or_default()),
+            ("SOUNDBREAK_MEETING_DURATION_SECS", meeting_secs.to_string()),
+            ("SOUNDBREAK_MUSIC_PLAYING", music_status.is_playing.to_string()),
+            ("SOUNDBREAK_TRACK_TITLE", music_status.title.clone().unwrap_or_default()),
+            ("SOUNDBREAK_TRACK_ARTIST", music_status.artist.clone().unwrap_or_default()),
+            ("SOUNDBREAK_TRACK", track),
+            ("SOUNDBREAK_TIMESTAMP", now_secs.to_string()),
+        ];
+
+        // Hooks may take up to their timeout, so they run on a thread of their own rather
+        // than holding up the check
+        let started = format!("Hook {:?} started", hooks::expand_home(&script));
+        std::thread::spawn(move || match hooks::run_hook(&script, &env, Duration::from_secs(hooks.timeout_secs)) {
+            Ok(message) => println!("SoundBreak: {}", message),
+            Err(e) => eprintln!("SoundBreak: {}", e),
+        });
+        Ok(started)
+    }
+
+    fn notify_peers(&self, event: PeerEvent) {
+        let peer = self.app_config.lock_or_recover().peer.clone();
+        if !peer.token.is_empty() {
+            PeerClient::notify_peers(peer.peers, peer.token, event);
+        }
+    }
+
+    /// Returns the status recorded by the most recent `tick`, without performing a check
+    pub fn get_status(&self) -> MonitoringStatus {
+        self.status.lock_or_recover().clone()
+    }
+
+    /// Runs a one-off meeting detection with the current configuration, without
+    /// affecting monitoring state
+    pub fn detect_meetings(&self) -> MeetingStatus {
+        self.detector.lock_or_recover().detect_meetings()
+    }
+
+    pub fn get_app_config(&self) -> AppConfig {
+        self.app_config.lock_or_recover().clone()
+    }
+
+    // Recomputes the adaptive check interval from the power source and whether any
+    // meeting app is running
+    fn update_effective_interval(&self, meeting_app_running: bool) {
+        let on_battery = self.on_battery();
+        let (base_ms, adaptive_polling) = {
+            let app_config = self.app_config.lock_or_recover();
+            (app_config.check_interval_ms, app_config.adaptive_polling.clone())
+        };
+        let interval_ms = adaptive_polling.effective_interval_ms(base_ms, on_battery, meeting_app_running);
+
+        let mut status_guard = self.status.lock_or_recover();
+        status_guard.on_battery = on_battery;
+        status_guard.effective_check_interval_ms = interval_ms;
+    }
+
+    fn on_battery(&self) -> bool {
+        let mut power_source = self.power_source.lock_or_recover();
+        match *power_source {
+            Some((read_at, on_battery)) if read_at.elapsed() < POWER_SOURCE_CACHE => on_battery,
+            _ => {
+                let on_battery = power::is_on_battery().unwrap_or(false);
+                *power_source = Some((Instant::now(), on_battery));
+                on_battery
+            }
+        }
+    }
+
+    /// Interval the monitoring loop currently waits between checks, shortened so a
+    /// pending pause happens on time
+    pub fn effective_check_interval(&self) -> Duration {
+        let mut interval_ms = self.status.lock_or_recover().effective_check_interval_ms;
+        if let Some(until) = self.meeting_state.lock_or_recover().pause_pending_until {
+            let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            interval_ms = interval_ms.min(until.saturating_sub(now_secs) * 1000);
+        }
+        Duration::from_millis(interval_ms.max(MIN_CHECK_INTERVAL_MS))
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.app_config.lock_or_recover().check_interval_ms)
+    }
+
+    /// Changes how often the monitoring loop runs; a running loop picks it up immediately
+    pub fn set_check_interval(&self, interval_ms: u64) -> Result<(), String> {
+        validate_check_interval(interval_ms)?;
+
+        let previous_status = self.get_status();
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            if app_config.check_interval_ms != interval_ms {
+                app_config.profile = None;
+            }
+            app_config.check_interval_ms = interval_ms;
+            app_config.clone()
+        };
+        self.status.lock_or_recover().profile = app_config.profile;
+        self.interval_changed();
+
+        self.emit_status_change(&previous_status);
+        self.save_app_config(&app_config)
+    }
+
+    // Recomputes the effective interval and restarts the worker's current wait with it
+    fn interval_changed(&self) {
+        let meeting_app_running = self
+            .get_status()
+            .meeting_status
+            .is_some_and(|meeting_status| meeting_status.active_apps.iter().any(|app| app.is_running));
+        self.update_effective_interval(meeting_app_running);
+
+        if let Some(worker) = self.worker.lock_or_recover().as_ref() {
+            let _ = worker.signal_tx.send(WorkerSignal::IntervalChanged);
+        }
+    }
+
+    pub fn apply_profile(&self, profile: Profile) -> Result<(), String> {
+        let previous_status = self.get_status();
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            profile.apply(&mut app_config);
+            app_config.clone()
+        };
+        self.status.lock_or_recover().profile = Some(profile);
+        self.interval_changed();
+
+        self.emit_status_change(&previous_status);
+        self.save_app_config(&app_config)
+    }
+
+    pub fn set_automation_toggles(
+        &self,
+        pause_on_meeting_start: bool,
+        resume_on_meeting_end: bool,
+        ask_before_resume: bool,
+    ) -> Result<(), String> {
+        let previous_status = self.get_status();
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            if app_config.pause_on_meeting_start != pause_on_meeting_start
+                || app_config.resume_on_meeting_end != resume_on_meeting_end
+            {
+                app_config.profile = None;
+            }
+            app_config.pause_on_meeting_start = pause_on_meeting_start;
+            app_config.resume_on_meeting_end = resume_on_meeting_end;
+            app_config.ask_before_resume = ask_before_resume;
+            app_config.clone()
+        };
+        self.status.lock_or_recover().profile = app_config.profile;
+
+        self.emit_status_change(&previous_status);
+        self.save_app_config(&app_config)
+    }
+
+    pub fn update_notification_config(&self, config: NotificationConfig) -> Result<(), String> {
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            app_config.notifications = config;
+            app_config.clone()
+        };
+
+        self.save_app_config(&app_config)
+    }
+
+    /// Uses the refresh token Microsoft replaced the Teams one with. It is already in the
+    /// keychain, so only the settings in use are updated, keeping later saves from
+    /// writing the old token back.
+    pub fn set_teams_refresh_token(&self, refresh_token: String) {
+        self.app_config.lock_or_recover().teams.refresh_token = refresh_token;
+    }
+
+    /// Replaces every setting at once, applying it to the running service and saving it
+    pub fn update_app_config(&self, config: AppConfig) -> Result<(), String> {
+        config.validate()?;
+        self.apply_app_config(config.clone());
+        self.save_app_config(&config)
+    }
+
+    /// Applies a config read back from `config.json`, e.g. after it was edited by hand.
+    /// Returns false if it matches the settings already in use.
+    pub fn reload_app_config(&self, config: AppConfig) -> bool {
+        let current = serde_json::to_value(&*self.app_config.lock_or_recover()).ok();
+        if serde_json::to_value(&config).ok() == current {
+            return false;
+        }
+        self.apply_app_config(config);
+        self.emit_config_change();
+        true
+    }
+
+    // Saves the settings in use, after telling the UI about them
+    fn save_app_config(&self, app_config: &AppConfig) -> Result<(), String> {
+        self.emit_config_change();
+        ConfigManager::save_config(app_config)
+    }
+
+    // Emits `config-changed` with the settings in use, so open windows don't show stale values
+    fn emit_config_change(&self) {
+        let Some(app_handle) = self.app_handle.lock_or_recover().clone() else {
+            return;
+        };
+        if let Err(e) = app_handle.emit("config-changed", self.get_app_config()) {
+            eprintln!("SoundBreak: Failed to emit config change: {}", e);
+        }
+    }
+
+    fn apply_app_config(&self, config: AppConfig) {
+        let previous_status = self.get_status();
+        self.detector.lock_or_recover().update_config(config.meeting_config.clone());
+        i18n::set_language(config.language);
+        i18n::set_plain_labels(config.plain_tray_labels);
+        {
+            let mut status = self.status.lock_or_recover();
+            status.dry_run = config.dry_run;
+            status.profile = config.profile;
+        }
+        *self.app_config.lock_or_recover() = config;
+        self.interval_changed();
+
+        self.emit_status_change(&previous_status);
+    }
+
+    /// Turns detection of one configured meeting app off or back on, keeping it in the list
+    pub fn set_meeting_app_enabled(&self, app: &str, enabled: bool) -> Result<(), String> {
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            let app_override = app_config.app_overrides.entry(app.to_string()).or_default();
+            app_override.enabled = (!enabled).then_some(false);
+            if *app_override == MeetingAppOverride::default() {
+                app_config.app_overrides.remove(app);
+            }
+            app_config.clone()
+        };
+
+        self.save_app_config(&app_config)
+    }
+
+    pub fn get_meeting_config(&self) -> MeetingConfig {
+        let detector = self.detector.lock_or_recover();
+        detector.get_config().clone()
+    }
+
+    /// Adds one process name to the meeting apps, leaving the others as they are
+    pub fn add_meeting_process(&self, process_name: &str) -> Result<String, String> {
+        let process_name = process_name.trim();
+        validate_process_name(process_name).map_err(|e| format!("Invalid process name: {}", e))?;
+
+        let mut config = self.get_meeting_config();
+        if config.process_names.iter().any(|name| name == process_name) {
+            return Err(format!("{} is already a meeting app", process_name));
+        }
+        config.process_names.push(process_name.to_string());
+        self.update_meeting_config(config)?;
+        Ok(format!("Added {} to the meeting apps", process_name))
+    }
+
+    /// Removes one process name from the meeting apps, along with its per-app settings
+    pub fn remove_meeting_process(&self, process_name: &str) -> Result<String, String> {
+        let mut config = self.get_meeting_config();
+        let count = config.process_names.len();
+        config.process_names.retain(|name| name != process_name);
+        if config.process_names.len() == count {
+            return Err(format!("{} is not a meeting app", process_name));
+        }
+        self.app_config.lock_or_recover().app_overrides.remove(process_name);
+        self.update_meeting_config(config)?;
+        Ok(format!("Removed {} from the meeting apps", process_name))
+    }
+
+    /// Applies `config` right away and saves it, so it is also used after a restart
+    pub fn update_meeting_config(&self, config: MeetingConfig) -> Result<(), String> {
+        // Update the detector with the new config
+        {
+            let mut detector = self.detector.lock_or_recover();
+            detector.update_config(config.clone());
+        }
+        
+        // Save the configuration to persistent storage
+        let app_config = {
+            let mut app_config = self.app_config.lock_or_recover();
+            app_config.meeting_config = config;
+            app_config.clone()
+        };
+
+        self.save_app_config(&app_config)
+    }
+}
+
+// Runs `f`, also returning how long it took in milliseconds
+fn timed<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    let started = Instant::now();
+    let result = f();
+    (result, started.elapsed().as_millis() as u64)
+}
+
+/// Top-level fields whose values differ, ignoring timestamps that change on every check
+pub fn status_diff(old: &MonitoringStatus, new: &MonitoringStatus) -> BTreeMap<String, serde_json::Value> {
+    fn normalized(status: &MonitoringStatus) -> serde_json::Map<String, serde_json::Value> {
+        let mut value = serde_json::to_value(status).unwrap_or_default();
+        if let Some(meeting_status) = value.get_mut("meeting_status").and_then(|v| v.as_object_mut()) {
+            meeting_status.remove("timestamp");
+        }
+        let mut fields = value.as_object().cloned().unwrap_or_default();
+        fields.remove("last_check");
+        fields.remove("meeting_duration_secs");
+        fields.remove("timings");
+        fields
+    }
+
+    let old_fields = normalized(old);
+    let new_fields = normalized(new);
+    let new_value = serde_json::to_value(new).unwrap_or_default();
+
+    new_fields
+        .iter()
+        .filter(|(key, value)| old_fields.get(*key) != Some(*value))
+        .map(|(key, _)| (key.clone(), new_value.get(key).cloned().unwrap_or_default()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    MeetingStarted,
+    MeetingEnded,
+    ScreenLocked,
+    ScreenUnlocked,
+    UserAway,
+    UserReturned,
+}
+
+impl Transition {
+    fn context(self) -> &'static str {
+        match self {
+            Transition::MeetingStarted => "Meeting started",
+            Transition::MeetingEnded => "Meeting ended",
+            Transition::ScreenLocked => "Screen locked",
+            Transition::ScreenUnlocked => "Screen unlocked",
+            Transition::UserAway => "User away",
+            Transition::UserReturned => "User returned",
+        }
+    }
+
+    fn is_meeting(self) -> bool {
+        matches!(self, Transition::MeetingStarted | Transition::MeetingEnded)
+    }
+
+    fn hook_event(self) -> &'static str {
+        match self {
+            Transition::MeetingStarted => "meeting_start",
+            Transition::MeetingEnded => "meeting_end",
+            Transition::ScreenLocked => "screen_lock",
+            Transition::ScreenUnlocked => "screen_unlock",
+            Transition::UserAway => "user_away",
+            Transition::UserReturned => "user_return",
+        }
+    }
+
+    fn hook_script(self, hooks: &HookConfig) -> Option<String> {
+        match self {
+            Transition::MeetingStarted => hooks.on_meeting_start.clone(),
+            Transition::MeetingEnded => hooks.on_meeting_end.clone(),
+            Transition::ScreenLocked => hooks.on_screen_lock.clone(),
+            Transition::ScreenUnlocked => hooks.on_screen_unlock.clone(),
+            Transition::UserAway => hooks.on_user_away.clone(),
+            Transition::UserReturned => hooks.on_user_return.clone(),
+        }
+    }
+
+    fn webhook_event(self) -> Option<WebhookEvent> {
+        match self {
+            Transition::MeetingStarted => Some(WebhookEvent::MeetingStart),
+            Transition::MeetingEnded => Some(WebhookEvent::MeetingEnd),
+            _ => None,
+        }
+    }
+
+    fn peer_event(self) -> Option<PeerEvent> {
+        match self {
+            Transition::MeetingStarted => Some(PeerEvent::MeetingStarted),
+            Transition::MeetingEnded => Some(PeerEvent::MeetingEnded),
+            _ => None,
+        }
+    }
+}
+
+impl Default for MonitoringService {
+    fn default() -> Self {
+        Self::new(ConfigManager::load_config())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FlapSuppressionConfig;
+    use crate::monitoring_state::MemoryStateStore;
+
+    const NOW: u64 = 1_700_000_000;
+
+    // A dry-run service, so actions are only simulated, saving to an in-memory store
+    fn service(mut config: AppConfig) -> (MonitoringService, Arc<MemoryStateStore>) {
+        config.dry_run = true;
+        config.on_meeting_end = vec![TransitionAction::ResumeMusic];
+        let store = Arc::new(MemoryStateStore::default());
+        (MonitoringService::in_memory(config, store.clone()), store)
+    }
+
+    fn in_meeting() -> MeetingState {
+        MeetingState {
+            in_meeting: true,
+            meeting_app: Some("Zoom".to_string()),
+            meeting_started_at: Some(NOW - 600),
+            music_was_playing: true,
+            ..MeetingState::default()
+        }
+    }
+
+    #[test]
+    fn waits_for_the_dwell_time_before_ending_a_meeting() {
+        let (service, _) = service(AppConfig {
+            detection_confirmations: 1,
+            flap_suppression: FlapSuppressionConfig {
+                min_secs_before_end: 10,
+                ..FlapSuppressionConfig::default()
+            },
+            ..AppConfig::default()
+        });
+
+        assert!(service.confirm_meeting_state(false, true, None, NOW));
+        assert!(service.confirm_meeting_state(false, true, None, NOW + 5));
+        assert!(!service.confirm_meeting_state(false, true, None, NOW + 10));
+    }
+
+    #[test]
+    fn a_detection_agreeing_again_restarts_the_dwell_time() {
+        let (service, _) = service(AppConfig {
+            detection_confirmations: 1,
+            flap_suppression: FlapSuppressionConfig {
+                min_secs_before_end: 10,
+                ..FlapSuppressionConfig::default()
+            },
+            ..AppConfig::default()
+        });
+
+        assert!(service.confirm_meeting_state(false, true, None, NOW));
+        assert!(service.confirm_meeting_state(true, true, None, NOW + 5));
+        assert!(service.confirm_meeting_state(false, true, None, NOW + 10));
+        assert!(!service.confirm_meeting_state(false, true, None, NOW + 20));
+    }
+
+    #[test]
+    fn needs_the_configured_number_of_confirmations() {
+        let (service, _) = service(AppConfig {
+            detection_confirmations: 3,
+            ..AppConfig::default()
+        });
+
+        assert!(!service.confirm_meeting_state(true, false, None, NOW));
+        assert!(!service.confirm_meeting_state(true, false, None, NOW + 1));
+        assert!(service.confirm_meeting_state(true, false, None, NOW + 2));
+    }
+
+    #[test]
+    fn ignores_new_meetings_during_the_resume_cool_down() {
+        let (service, _) = service(AppConfig {
+            detection_confirmations: 1,
+            flap_suppression: FlapSuppressionConfig {
+                resume_cooldown_secs: 60,
+                ..FlapSuppressionConfig::default()
+            },
+            ..AppConfig::default()
+        });
+        *service.last_resumed_at.lock_or_recover() = Some(NOW);
+
+        assert!(!service.confirm_meeting_state(true, false, None, NOW + 30));
+        assert!(service.confirm_meeting_state(true, false, None, NOW + 60));
+    }
+
+    #[test]
+    fn records_simulated_actions_as_typed_last_actions() {
+        let (service, _) = service(AppConfig::default());
+        *service.meeting_state.lock_or_recover() = in_meeting();
+        let playing = MusicStatus {
+            is_playing: true,
+            ..MusicStatus::default()
+        };
+
+        service.run_transition_actions(
+            &[TransitionAction::PauseMusic],
+            Transition::MeetingStarted,
+            &MusicController::new(),
+            &playing,
+            NOW,
+        );
+
+        let last_action = service.get_status().last_action.unwrap();
+        assert_eq!(last_action.kind, LastActionKind::Pause);
+        assert_eq!(last_action.trigger.as_deref(), Some("Meeting started"));
+        assert_eq!(last_action.app.as_deref(), Some("Zoom"));
+        assert!(last_action.dry_run);
+    }
+
+    #[test]
+    fn ending_a_meeting_runs_the_end_actions_and_forgets_it() {
+        let (service, _) = service(AppConfig::default());
+        *service.meeting_state.lock_or_recover() = in_meeting();
+
+        service.end_meeting(&MusicController::new(), &MusicStatus::default(), NOW);
+
+        let last_action = service.get_status().last_action.unwrap();
+        assert_eq!(last_action.kind, LastActionKind::Resume);
+        assert_eq!(last_action.message, "Would resume music");
+        assert_eq!(*service.meeting_state.lock_or_recover(), MeetingState::default());
+    }
+
+    #[test]
+    fn ending_the_meeting_in_progress_restores_and_forgets_it() {
+        let (service, store) = service(AppConfig::default());
+        *service.meeting_state.lock_or_recover() = in_meeting();
+        service.transition_counter.lock_or_recover().leaving = 1;
+
+        service.end_meeting_in_progress_with(&MusicController::new(), MusicStatus::default, NOW);
+        service.persist_state();
+
+        assert_eq!(service.get_status().last_action.unwrap().kind, LastActionKind::Resume);
+        assert_eq!(store.load().unwrap().meeting, MeetingState::default());
+        assert_eq!(service.transition_counter.lock_or_recover().leaving, 0);
+    }
+
+    #[test]
+    fn ending_without_a_meeting_only_resets_the_counter() {
+        let (service, _) = service(AppConfig::default());
+        service.transition_counter.lock_or_recover().entering = 1;
+
+        service.end_meeting_in_progress_with(&MusicController::new(), || unreachable!("music status read without a meeting"), NOW);
+
+        assert!(service.get_status().last_action.is_none());
+        assert_eq!(service.transition_counter.lock_or_recover().entering, 0);
+    }
+
+    #[test]
+    fn snoozes_until_the_snooze_time_passes() {
+        let (service, _) = service(AppConfig::default());
+        let now = UNIX_EPOCH + Duration::from_secs(NOW);
+
+        *service.snoozed_until.lock_or_recover() = Some(NOW + 60);
+        assert!(service.is_snoozed(now));
+
+        *service.snoozed_until.lock_or_recover() = Some(NOW);
+        assert!(!service.is_snoozed(now));
+        assert_eq!(*service.snoozed_until.lock_or_recover(), None);
+    }
+
+    #[test]
+    fn restores_the_saved_state_on_creation() {
+        let store = Arc::new(MemoryStateStore::default());
+        let mut playback = PlaybackTracker::default();
+        playback.changed_by_soundbreak(false);
+        store
+            .save(&PersistedState {
+                is_active: true,
+                snoozed_until: None,
+                meeting: in_meeting(),
+                playback: playback.clone(),
+            })
+            .unwrap();
+
+        let service = MonitoringService::in_memory(AppConfig::default(), store);
+
+        assert_eq!(*service.meeting_state.lock_or_recover(), in_meeting());
+        assert_eq!(*service.playback_tracker.lock_or_recover(), playback);
+        assert_eq!(service.get_status().last_playback_change_by, Some(PlaybackInitiator::SoundBreak));
+        assert!(service.start_on_launch());
     }
 }
@@ -0,0 +1,167 @@
+use crate::config::ConfigManager;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// What SoundBreak knows about the meeting in progress and what it did to playback
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingState {
+    pub in_meeting: bool,
+    /// Music was playing when the meeting started, so it should come back afterwards
+    pub music_was_playing: bool,
+    /// Player volume before ducking, set while a short meeting only lowered the volume
+    pub ducked_from_volume: Option<u8>,
+    /// Player whose volume was lowered, e.g. `Spotify`; `None` for states saved by
+    /// versions that lowered the system output instead
+    pub ducked_player: Option<String>,
+    pub paused_spoken_word: bool,
+    /// System output was muted by SoundBreak at meeting start
+    pub muted_by_soundbreak: bool,
+    /// A Focus mode was turned on by SoundBreak at meeting start
+    pub focus_enabled_by_soundbreak: bool,
+    /// State of each Hue light turned red at meeting start, by light id
+    pub hue_previous_states: BTreeMap<String, Value>,
+    /// Meeting app that started the current meeting, used to attribute actions
+    pub meeting_app: Option<String>,
+    /// When the current meeting started and when SoundBreak paused music during it (unix secs)
+    pub meeting_started_at: Option<u64>,
+    pub paused_at: Option<u64>,
+    /// Music will be paused at this time (unix secs) unless the user cancels
+    pub pause_pending_until: Option<u64>,
+    /// The user cancelled the pause for this meeting
+    pub pause_cancelled: bool,
+    /// Music has been playing since this time (unix secs) although a meeting is on
+    pub playing_during_meeting_since: Option<u64>,
+    /// The user was asked whether a meeting that kept music paused for hours is still on
+    pub stale_pause_alerted: bool,
+}
+
+impl MeetingState {
+    /// Keeps only what is needed to undo SoundBreak's changes to playback, for handing
+    /// a pause over to whatever comes next
+    pub fn pause_only(&self) -> MeetingState {
+        MeetingState {
+            music_was_playing: self.music_was_playing,
+            ducked_from_volume: self.ducked_from_volume,
+            ducked_player: self.ducked_player.clone(),
+            paused_spoken_word: self.paused_spoken_word,
+            muted_by_soundbreak: self.muted_by_soundbreak,
+            focus_enabled_by_soundbreak: self.focus_enabled_by_soundbreak,
+            hue_previous_states: self.hue_previous_states.clone(),
+            paused_at: self.paused_at,
+            ..MeetingState::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackInitiator {
+    User,
+    SoundBreak,
+}
+
+/// Observed playback state, used to tell the user's own play/pause apart from SoundBreak's
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackTracker {
+    pub last_seen_playing: Option<bool>,
+    pub last_change_by: Option<PlaybackInitiator>,
+}
+
+impl PlaybackTracker {
+    pub fn observe(&mut self, is_playing: bool) {
+        if self.last_seen_playing.is_some_and(|was_playing| was_playing != is_playing) {
+            self.last_change_by = Some(PlaybackInitiator::User);
+        }
+        self.last_seen_playing = Some(is_playing);
+    }
+
+    pub fn changed_by_soundbreak(&mut self, is_playing: bool) {
+        self.last_seen_playing = Some(is_playing);
+        self.last_change_by = Some(PlaybackInitiator::SoundBreak);
+    }
+}
+
+/// Monitoring state saved to the `StateStore` on every change and restored on launch,
+/// so an app update or crash mid-meeting doesn't lose track of paused music.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistedState {
+    pub is_active: bool,
+    pub snoozed_until: Option<u64>,
+    pub meeting: MeetingState,
+    /// Who last paused or played music, so music SoundBreak paused before a restart is
+    /// still resumed after it
+    pub playback: PlaybackTracker,
+}
+
+/// Where `MonitoringService` saves the state it restores on launch
+pub trait StateStore: Send + Sync {
+    /// Returns `None` on first launch or when the saved state is unreadable
+    fn load(&self) -> Option<PersistedState>;
+    fn save(&self, state: &PersistedState) -> Result<(), String>;
+}
+
+/// Returns the file-backed store, or an in-memory one if the app directory is unavailable
+pub fn default_state_store() -> Arc<dyn StateStore> {
+    match ConfigManager::get_app_dir() {
+        Ok(_) => Arc::new(FileStateStore),
+        Err(e) => {
+            eprintln!("SoundBreak: {}; monitoring state won't survive a restart", e);
+            Arc::new(MemoryStateStore::default())
+        }
+    }
+}
+
+/// Keeps the state in `state.json` in the app directory
+pub struct FileStateStore;
+
+impl FileStateStore {
+    fn get_state_path() -> Result<PathBuf, String> {
+        Ok(ConfigManager::get_app_dir()?.join("state.json"))
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn load(&self) -> Option<PersistedState> {
+        let state_path = Self::get_state_path().ok()?;
+        let content = std::fs::read_to_string(state_path).ok()?;
+        match serde_json::from_str::<PersistedState>(&content) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                eprintln!("SoundBreak: Failed to parse saved state: {}", e);
+                None
+            }
+        }
+    }
+
+    fn save(&self, state: &PersistedState) -> Result<(), String> {
+        let state_path = Self::get_state_path()?;
+        let json_content = serde_json::to_string_pretty(state)
+            .map_err(|e| format!("Failed to serialize state: {}", e))?;
+        std::fs::write(&state_path, json_content)
+            .map_err(|e| format!("Failed to write state file: {}", e))
+    }
+}
+
+/// Keeps the state for the lifetime of the process only
+#[derive(Default)]
+pub struct MemoryStateStore {
+    state: Mutex<Option<PersistedState>>,
+}
+
+impl StateStore for MemoryStateStore {
+    fn load(&self) -> Option<PersistedState> {
+        self.state.lock_or_recover().clone()
+    }
+
+    fn save(&self, state: &PersistedState) -> Result<(), String> {
+        *self.state.lock_or_recover() = Some(state.clone());
+        Ok(())
+    }
+}
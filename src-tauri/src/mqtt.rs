@@ -0,0 +1,177 @@
+//! MQTT publisher
+//!
+//! Publishes the meeting, music and monitoring state as retained MQTT topics whenever
+//! they change, so any home automation system can react to them. Only the few MQTT
+//! 3.1.1 packets needed to publish are implemented: each update connects, publishes
+//! the topics that changed and disconnects, on a background thread. TLS isn't supported.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const MQTT_TIMEOUT: Duration = Duration::from_secs(5);
+const KEEP_ALIVE_SECS: u16 = 60;
+
+// Payload last published to each topic, so unchanged topics aren't published again.
+// Also serializes publishing between threads.
+static PUBLISHED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    /// Host name or IP address of the broker
+    pub host: String,
+    pub port: u16,
+    /// Leave empty for brokers that allow anonymous clients
+    pub username: String,
+    /// Kept in the keychain; only read from `config.json` when set there by hand
+    pub password: String,
+    pub client_id: String,
+    /// Start of every topic, e.g. "soundbreak" for `soundbreak/meeting`
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 1883,
+            username: String::new(),
+            password: String::new(),
+            client_id: "soundbreak".to_string(),
+            topic_prefix: "soundbreak".to_string(),
+        }
+    }
+}
+
+/// Publishes the topics whose payload changed since they were last published, on a
+/// background thread
+pub fn publish(config: &MqttConfig, status: &MonitoringStatus) {
+    if !config.enabled || config.host.trim().is_empty() {
+        return;
+    }
+
+    let config = config.clone();
+    let topics = topics(&config.topic_prefix, status);
+    std::thread::spawn(move || {
+        let mut published = PUBLISHED.lock_or_recover();
+        let changed: Vec<(String, String)> = topics
+            .into_iter()
+            .filter(|(topic, payload)| published.get(topic) != Some(payload))
+            .collect();
+        if changed.is_empty() {
+            return;
+        }
+
+        match publish_retained(&config, &changed) {
+            Ok(()) => published.extend(changed),
+            Err(e) => eprintln!("SoundBreak: Failed to publish to MQTT broker {}: {}", config.host, e),
+        }
+    });
+}
+
+fn topics(prefix: &str, status: &MonitoringStatus) -> Vec<(String, String)> {
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let music = status.music_status.clone().unwrap_or_default();
+    let track = match (music.title, music.artist) {
+        (Some(title), Some(artist)) => format!("{} — {}", title, artist),
+        (Some(title), None) => title,
+        _ => String::new(),
+    };
+    vec![
+        (format!("{}/meeting", prefix), if in_meeting { "on" } else { "off" }.to_string()),
+        (format!("{}/meeting/app", prefix), status.meeting_app().unwrap_or_default()),
+        (format!("{}/music", prefix), if music.is_playing { "playing" } else { "paused" }.to_string()),
+        (format!("{}/music/track", prefix), track),
+        (format!("{}/monitoring", prefix), status.monitoring_state().to_string()),
+    ]
+}
+
+fn publish_retained(config: &MqttConfig, messages: &[(String, String)]) -> Result<(), String> {
+    let mut stream = TcpStream::connect((config.host.trim(), config.port)).map_err(|e| format!("Failed to connect: {}", e))?;
+    stream.set_read_timeout(Some(MQTT_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(MQTT_TIMEOUT)).map_err(|e| e.to_string())?;
+
+    stream.write_all(&connect_packet(config)).map_err(|e| format!("Failed to send CONNECT: {}", e))?;
+    let mut connack = [0u8; 4];
+    stream.read_exact(&mut connack).map_err(|e| format!("Failed to read CONNACK: {}", e))?;
+    if connack[0] != 0x20 {
+        return Err(format!("Unexpected answer to CONNECT: {:#04x}", connack[0]));
+    }
+    match connack[3] {
+        0 => {}
+        4 | 5 => return Err("The broker rejected the username or password".to_string()),
+        code => return Err(format!("The broker refused the connection with code {}", code)),
+    }
+
+    for (topic, payload) in messages {
+        stream
+            .write_all(&publish_packet(topic, payload))
+            .map_err(|e| format!("Failed to publish {}: {}", topic, e))?;
+    }
+    // DISCONNECT
+    stream.write_all(&[0xE0, 0x00]).map_err(|e| format!("Failed to disconnect: {}", e))
+}
+
+fn connect_packet(config: &MqttConfig) -> Vec<u8> {
+    // Clean session, plus the username and password flags when they are set
+    let mut flags = 0x02;
+    let mut body = Vec::new();
+    push_string(&mut body, "MQTT");
+    body.push(4); // Protocol level 3.1.1
+    let flags_index = body.len();
+    body.push(0);
+    body.extend_from_slice(&KEEP_ALIVE_SECS.to_be_bytes());
+
+    push_string(&mut body, &config.client_id);
+    if !config.username.is_empty() {
+        flags |= 0x80;
+        push_string(&mut body, &config.username);
+        if !config.password.is_empty() {
+            flags |= 0x40;
+            push_string(&mut body, &config.password);
+        }
+    }
+    body[flags_index] = flags;
+
+    packet(0x10, body)
+}
+
+fn publish_packet(topic: &str, payload: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_string(&mut body, topic);
+    body.extend_from_slice(payload.as_bytes());
+    // QoS 0, retained
+    packet(0x31, body)
+}
+
+// Fixed header: packet type and flags, then the remaining length as a varint
+fn packet(header: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut length = body.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend(body);
+    packet
+}
+
+fn push_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::process::Command;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MusicStatus {
     pub is_playing: bool,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub media_type: Option<String>,
+    // Bundle identifier of the app that owns the now-playing session
+    pub app_bundle_id: Option<String>,
 }
 
+// Players with a `sound volume` AppleScript property, by bundle identifier
+const SCRIPTABLE_PLAYERS: [(&str, &str); 2] = [("com.spotify.client", "Spotify"), ("com.apple.Music", "Music")];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MusicAction {
     Play,
@@ -21,18 +31,26 @@ impl MusicController {
 
     pub fn get_music_status(&self) -> MusicStatus {
         // Use MediaRemote framework for universal music detection
-        let is_playing = self.check_music_via_mediaremote().unwrap_or(false);
+        self.check_music_via_mediaremote().unwrap_or_default()
+    }
 
-        MusicStatus {
-            is_playing,
-        }
+    /// Like `get_music_status`, but reports MediaRemote failures instead of "not playing"
+    pub fn try_get_music_status(&self) -> Result<MusicStatus, String> {
+        self.check_music_via_mediaremote()
     }
 
 
-    fn check_music_via_mediaremote(&self) -> Result<bool, String> {
+    fn check_music_via_mediaremote(&self) -> Result<MusicStatus, String> {
         // Use AppleScript with MediaRemote framework for macOS 15.4+ compatibility
+        // Output is one field per line: playing flag, title, artist, album, media type, bundle id
         let script = r#"
             use framework "AppKit"
+
+            on textOf(theValue)
+                if theValue is missing value then return ""
+                return theValue as text
+            end textOf
+
             try
                 set MediaRemote to current application's NSBundle's bundleWithPath:"/System/Library/PrivateFrameworks/MediaRemote.framework/"
                 MediaRemote's load()
@@ -44,32 +62,128 @@ impl MusicController {
                     return "false"
                 end if
 
+                set isPlaying to "false"
                 set playbackRate to infoDict's valueForKey:"kMRMediaRemoteNowPlayingInfoPlaybackRate"
-                if playbackRate is missing value then
-                    return "false"
+                if playbackRate is not missing value then
+                    if (playbackRate as real) > 0 then set isPlaying to "true"
                 end if
 
-                set rateValue to playbackRate as real
-                if rateValue > 0 then
-                    return "true"
-                else
-                    return "false"
-                end if
-            on error
-                return "false"
+                set bundleId to ""
+                try
+                    set bundleId to my textOf(MRNowPlayingRequest's localNowPlayingPlayerPath()'s client()'s bundleIdentifier())
+                end try
+
+                set titleText to my textOf(infoDict's valueForKey:"kMRMediaRemoteNowPlayingInfoTitle")
+                set artistText to my textOf(infoDict's valueForKey:"kMRMediaRemoteNowPlayingInfoArtist")
+                set albumText to my textOf(infoDict's valueForKey:"kMRMediaRemoteNowPlayingInfoAlbum")
+                set mediaTypeText to my textOf(infoDict's valueForKey:"kMRMediaRemoteNowPlayingInfoMediaType")
+
+                return isPlaying & linefeed & titleText & linefeed & artistText & linefeed & albumText & linefeed & mediaTypeText & linefeed & bundleId
+            on error errorMessage
+                return "error" & linefeed & errorMessage
             end try
         "#;
 
         match Command::new("osascript").arg("-e").arg(script).output() {
+            Ok(output) if !output.status.success() => Err(format!(
+                "MediaRemote check failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
             Ok(output) => {
                 let result_str = String::from_utf8_lossy(&output.stdout);
-                let result = result_str.trim();
-                Ok(result == "true")
+                let mut fields = result_str.trim_end_matches('\n').split('\n');
+                let first_field = fields.next().map(str::trim);
+                if first_field == Some("error") {
+                    let error = fields.collect::<Vec<_>>().join(" ");
+                    return Err(format!("MediaRemote check failed: {}", error.trim()));
+                }
+                let is_playing = first_field == Some("true");
+                let mut next_field = || {
+                    fields
+                        .next()
+                        .map(str::trim)
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                };
+
+                Ok(MusicStatus {
+                    is_playing,
+                    title: next_field(),
+                    artist: next_field(),
+                    album: next_field(),
+                    media_type: next_field(),
+                    app_bundle_id: next_field(),
+                })
             }
             Err(e) => Err(format!("MediaRemote check failed: {}", e)),
         }
     }
 
+    /// Returns the raw MediaRemote now-playing dictionary for troubleshooting
+    ///
+    /// Values are rendered with their Objective-C description. The now-playing app's
+    /// bundle identifier is added under the `bundleIdentifier` key when available.
+    pub fn get_now_playing_info(&self) -> Result<BTreeMap<String, String>, String> {
+        let script = r#"
+            use framework "AppKit"
+            try
+                set MediaRemote to current application's NSBundle's bundleWithPath:"/System/Library/PrivateFrameworks/MediaRemote.framework/"
+                MediaRemote's load()
+
+                set MRNowPlayingRequest to current application's NSClassFromString("MRNowPlayingRequest")
+                set output to ""
+                try
+                    set bundleId to MRNowPlayingRequest's localNowPlayingPlayerPath()'s client()'s bundleIdentifier()
+                    set output to "bundleIdentifier" & tab & (bundleId as text) & linefeed
+                end try
+
+                set infoDict to MRNowPlayingRequest's localNowPlayingItem()'s nowPlayingInfo()
+                if infoDict is missing value then
+                    return output
+                end if
+
+                repeat with infoKey in (infoDict's allKeys() as list)
+                    set infoValue to (infoDict's objectForKey:infoKey)
+                    set valueText to (infoValue's |description|()) as text
+                    set output to output & (infoKey as text) & tab & valueText & linefeed
+                end repeat
+                return output
+            on error errMsg
+                return "error" & tab & errMsg
+            end try
+        "#;
+
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output()
+            .map_err(|e| format!("MediaRemote query failed: {}", e))?;
+
+        let result_str = String::from_utf8_lossy(&output.stdout);
+        let mut info = BTreeMap::new();
+        let mut last_key: Option<String> = None;
+        for line in result_str.lines() {
+            match line.split_once('\t') {
+                Some((key, value)) => {
+                    info.insert(key.to_string(), value.to_string());
+                    last_key = Some(key.to_string());
+                }
+                // Multi-line descriptions (e.g. nested dictionaries) continue the previous value
+                None => {
+                    if let Some(value) = last_key.as_ref().and_then(|key| info.get_mut(key)) {
+                        value.push(' ');
+                        value.push_str(line.trim());
+                    }
+                }
+            }
+        }
+
+        match info.get("error") {
+            Some(error) if info.len() == 1 => Err(format!("MediaRemote query failed: {}", error)),
+            _ => Ok(info),
+        }
+    }
+
     pub fn play_music(&self) -> Result<String, String> {
         self.send_mediaremote_command("play")
     }
@@ -78,11 +192,25 @@ impl MusicController {
         self.send_mediaremote_command("pause")
     }
 
+    /// Skips playback back by the given number of seconds (e.g. to recap a podcast)
+    pub fn rewind(&self, seconds: u32) -> Result<String, String> {
+        let options = format!(
+            r#"current application's NSDictionary's dictionaryWithObject:{} forKey:"kMRMediaRemoteOptionSkipInterval""#,
+            seconds
+        );
+        self.send_mediaremote_command_with_options("rewind", &options)
+    }
+
     fn send_mediaremote_command(&self, action: &str) -> Result<String, String> {
+        self.send_mediaremote_command_with_options(action, "current application's NSDictionary's alloc()'s init()")
+    }
+
+    fn send_mediaremote_command_with_options(&self, action: &str, options: &str) -> Result<String, String> {
         // Map action to MediaRemote command numbers
         let command_num = match action {
             "play" => "0",      // play command
             "pause" => "1",     // pause command
+            "rewind" => "18",   // skip backward command, honors kMRMediaRemoteOptionSkipInterval
             _ => return Err(format!("Unknown action: {}", action)),
         };
 
@@ -93,7 +221,7 @@ impl MusicController {
                 MediaRemote's load()
 
                 set MRNowPlayingController to current application's NSClassFromString("MRNowPlayingController")
-                set commandOptions to current application's NSDictionary's alloc()'s init()
+                set commandOptions to {}
                 set controller to MRNowPlayingController's localRouteController()
 
                 controller's sendCommand:{} options:commandOptions completion:(missing value)
@@ -101,7 +229,7 @@ impl MusicController {
             on error errMsg
                 return "error"
             end try
-        "#, command_num);
+        "#, options, command_num);
 
         match Command::new("osascript").arg("-e").arg(&script).output() {
             Ok(output) => {
@@ -122,6 +250,104 @@ impl MusicController {
         }
     }
 
+    /// Name of the app playing `music_status` if it has a volume of its own that can be
+    /// scripted, so it can be lowered without lowering everything else
+    pub fn scriptable_player(music_status: &MusicStatus) -> Option<&'static str> {
+        let bundle_id = music_status.app_bundle_id.as_deref()?;
+        SCRIPTABLE_PLAYERS
+            .iter()
+            .find(|(id, _)| *id == bundle_id)
+            .map(|(_, name)| *name)
+    }
+
+    /// Volume of `player`, one of the apps `scriptable_player` returns
+    pub fn get_player_volume(&self, player: &str) -> Result<u8, String> {
+        let player = Self::known_player(player)?;
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!("tell application \"{}\" to get sound volume", player))
+            .output()
+            .map_err(|e| format!("Failed to read the {} volume: {}", player, e))?;
+
+        let result_str = String::from_utf8_lossy(&output.stdout);
+        result_str
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| format!("Unexpected {} volume: {}", player, result_str.trim()))
+    }
+
+    pub fn set_player_volume(&self, player: &str, volume: u8) -> Result<String, String> {
+        let player = Self::known_player(player)?;
+        let volume = volume.min(100);
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!("tell application \"{}\" to set sound volume to {}", player, volume))
+            .output()
+            .map_err(|e| format!("Failed to set the {} volume: {}", player, e))?;
+
+        if output.status.success() {
+            Ok(format!("{} volume set to {}%", player, volume))
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            Err(format!("Failed to set the {} volume: {}", player, error))
+        }
+    }
+
+    // Only names from the list make it into a script, whatever a saved state says
+    fn known_player(player: &str) -> Result<&'static str, String> {
+        SCRIPTABLE_PLAYERS
+            .iter()
+            .map(|(_, name)| *name)
+            .find(|name| *name == player)
+            .ok_or_else(|| format!("Can't change the volume of {}", player))
+    }
+
+    pub fn set_output_volume(&self, volume: u8) -> Result<String, String> {
+        let volume = volume.min(100);
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!("set volume output volume {}", volume))
+            .output()
+            .map_err(|e| format!("Failed to set output volume: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Output volume set to {}%", volume))
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            Err(format!("Failed to set output volume: {}", error))
+        }
+    }
+
+    pub fn is_output_muted(&self) -> Result<bool, String> {
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg("output muted of (get volume settings)")
+            .output()
+            .map_err(|e| format!("Failed to read mute state: {}", e))?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim() == "true")
+    }
+
+    pub fn set_output_muted(&self, muted: bool) -> Result<String, String> {
+        let script = if muted {
+            "set volume with output muted"
+        } else {
+            "set volume without output muted"
+        };
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output()
+            .map_err(|e| format!("Failed to change mute state: {}", e))?;
+
+        if output.status.success() {
+            Ok(if muted { "System output muted" } else { "System output unmuted" }.to_string())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            Err(format!("Failed to change mute state: {}", error))
+        }
+    }
+
     pub fn execute_action(&self, action: MusicAction) -> Result<String, String> {
         match action {
             MusicAction::Play => self.play_music(),
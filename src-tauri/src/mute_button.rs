@@ -0,0 +1,64 @@
+//! Hardware mute button
+//!
+//! Tapping a MuteMe button toggles the manual meeting override, so music pauses or
+//! resumes without reaching for the menu bar. The button's light is driven like any
+//! other busylight, so it mirrors the meeting state through the `busylight` settings.
+//! Touches arrive as HID input reports, watched through IOKit on a thread of their own.
+
+#[cfg(target_os = "macos")]
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+#[cfg(target_os = "macos")]
+use tauri::Manager;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MuteButtonConfig {
+    pub enabled: bool,
+}
+
+/// Starts watching for touches on a background thread
+#[cfg(target_os = "macos")]
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    // The fourth byte of a report is the touch state: 0x01 when a touch starts, 0x02
+    // while the button is held and 0x04 when it is let go
+    const TOUCH_START: u8 = 0x01;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = crate::hid::watch_input_reports(&crate::busylight::muteme_ids(), move |report| {
+            if report.get(3) == Some(&TOUCH_START) {
+                toggle_manual_meeting(&app);
+            }
+        });
+        if let Err(e) = result {
+            eprintln!("SoundBreak: Failed to watch for mute button presses: {}", e);
+        }
+    });
+
+    println!("SoundBreak: Watching for mute button presses");
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start(_app: &AppHandle) -> Result<(), String> {
+    Err("Mute buttons are only supported on macOS".to_string())
+}
+
+// Pausing or resuming shells out to osascript, so run it off the run loop's thread
+#[cfg(target_os = "macos")]
+fn toggle_manual_meeting(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let service = app.state::<AppState>().monitoring_service.clone();
+        if !service.get_app_config().mute_button.enabled {
+            return;
+        }
+        match service.set_manual_meeting(!service.is_manual_meeting()) {
+            Ok(msg) => println!("SoundBreak: {}", msg),
+            Err(e) => eprintln!("SoundBreak: {}", e),
+        }
+        let _ = crate::update_tray_menu_text(&app, &service.get_status());
+    });
+}
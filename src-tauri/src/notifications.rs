@@ -0,0 +1,143 @@
+use crate::action_history::ActionKind;
+use crate::i18n::{tr, tr_args};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Which automatic actions show a desktop notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    pub on_pause: bool,
+    pub on_resume: bool,
+    /// Ducking and restoring the player volume
+    pub on_volume_change: bool,
+    pub on_mute: bool,
+    /// Actions that failed, regardless of the toggles above
+    pub on_failure: bool,
+    /// When a meeting pauses music, offer "Keep Playing" and "Snooze 30m" buttons, and
+    /// "Resume Now" when music stays paused after it
+    pub actions: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_pause: true,
+            on_resume: true,
+            on_volume_change: true,
+            on_mute: false,
+            on_failure: true,
+            actions: false,
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn wants_actions(&self) -> bool {
+        self.enabled && self.actions
+    }
+
+    fn wants(&self, kind: ActionKind, success: bool) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if !success {
+            return self.on_failure;
+        }
+        match kind {
+            ActionKind::Pause => self.on_pause,
+            ActionKind::Resume => self.on_resume,
+            ActionKind::Duck | ActionKind::RestoreVolume => self.on_volume_change,
+            ActionKind::Mute | ActionKind::Unmute => self.on_mute,
+            ActionKind::Webhook => false,
+        }
+    }
+}
+
+/// Shows a notification for an automatic action, if enabled for that kind of action
+pub fn notify_action(
+    app_handle: &AppHandle,
+    config: &NotificationConfig,
+    kind: ActionKind,
+    reason: &str,
+    result: &Result<String, String>,
+) {
+    if !config.wants(kind, result.is_ok()) {
+        return;
+    }
+
+    let body = match result {
+        Ok(_) => action_text(kind, reason),
+        Err(error) => tr_args("Couldn't {action}: {error}", &[("action", &tr(failure_verb(kind))), ("error", &error)]),
+    };
+
+    if let Err(e) = app_handle.notification().builder().title("SoundBreak").body(body).show() {
+        eprintln!("SoundBreak: Failed to show notification: {}", e);
+    }
+}
+
+/// Announces that music is about to be paused, if pause notifications are enabled
+pub fn notify_pending_pause(app_handle: &AppHandle, config: &NotificationConfig, countdown_secs: u64) {
+    if !config.enabled || !config.on_pause {
+        return;
+    }
+
+    let body = tr_args("Pausing music in {secs}s — cancel from the menu bar", &[("secs", &countdown_secs)]);
+    if let Err(e) = app_handle.notification().builder().title("SoundBreak").body(body).show() {
+        eprintln!("SoundBreak: Failed to show notification: {}", e);
+    }
+}
+
+/// Warns that music has been paused for a suspiciously long meeting
+pub fn notify_stale_pause(app_handle: &AppHandle, config: &NotificationConfig, hours: u64) {
+    if !config.enabled {
+        return;
+    }
+
+    let body = tr_args(
+        "Music has been paused for over {hours} hours — is your meeting still going on?",
+        &[("hours", &hours)],
+    );
+    if let Err(e) = app_handle.notification().builder().title("SoundBreak").body(body).show() {
+        eprintln!("SoundBreak: Failed to show notification: {}", e);
+    }
+}
+
+/// Confirms that monitoring was started or stopped from the keyboard shortcut
+pub fn notify_monitoring_toggled(app_handle: &AppHandle, config: &NotificationConfig, is_active: bool) {
+    if !config.enabled {
+        return;
+    }
+
+    let body = tr(if is_active { "Monitoring started" } else { "Monitoring stopped" });
+    if let Err(e) = app_handle.notification().builder().title("SoundBreak").body(body).show() {
+        eprintln!("SoundBreak: Failed to show notification: {}", e);
+    }
+}
+
+fn action_text(kind: ActionKind, reason: &str) -> String {
+    match kind {
+        ActionKind::Pause => tr_args("Music paused — {reason}", &[("reason", &reason)]),
+        ActionKind::Duck => tr_args("Music volume lowered — {reason}", &[("reason", &reason)]),
+        ActionKind::Mute => tr_args("Sound muted — {reason}", &[("reason", &reason)]),
+        ActionKind::Resume => tr("Music resumed"),
+        ActionKind::RestoreVolume => tr("Music volume restored"),
+        ActionKind::Unmute => tr("Sound unmuted"),
+        ActionKind::Webhook => tr("Webhook delivered"),
+    }
+}
+
+fn failure_verb(kind: ActionKind) -> &'static str {
+    match kind {
+        ActionKind::Pause => "pause music",
+        ActionKind::Resume => "resume music",
+        ActionKind::Duck => "lower the volume",
+        ActionKind::RestoreVolume => "restore the volume",
+        ActionKind::Mute => "mute sound",
+        ActionKind::Unmute => "unmute sound",
+        ActionKind::Webhook => "call the webhook",
+    }
+}
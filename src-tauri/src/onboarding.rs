@@ -0,0 +1,68 @@
+//! First-run setup
+//!
+//! On first launch (no config file yet) the main window is shown and the page walks new
+//! users through picking their meeting apps from the ones installed, and checks that
+//! the music status can be read before they rely on it.
+
+use crate::meeting_detector::MeetingConfig;
+use crate::music_controller::MusicController;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Meeting apps recognized by their bundle in an Applications folder, with the process
+// that runs during their meetings
+const KNOWN_MEETING_APPS: &[(&str, &str)] = &[
+    ("Lark", "Lark Helper (Iron)"),
+    ("Feishu", "Lark Helper (Iron)"),
+    ("TencentMeeting", "TencentMeeting"),
+    ("zoom.us", "zoom.us"),
+    ("Microsoft Teams", "Microsoft Teams"),
+    ("Cisco Webex Meetings", "Cisco Webex Meetings"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledMeetingApp {
+    pub name: String,
+    pub process_name: String,
+    /// Already in the configured meeting apps
+    pub configured: bool,
+}
+
+/// What the onboarding page needs to suggest a working setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Onboarding {
+    pub installed_apps: Vec<InstalledMeetingApp>,
+    /// Why the music status couldn't be read, if it couldn't
+    pub music_status_error: Option<String>,
+}
+
+impl Onboarding {
+    /// Scans for installed meeting apps and tries reading the music status
+    pub fn check(meeting_config: &MeetingConfig) -> Self {
+        Self {
+            installed_apps: installed_meeting_apps(meeting_config),
+            music_status_error: MusicController::new().try_get_music_status().err(),
+        }
+    }
+}
+
+/// Known meeting apps found in `/Applications` or `~/Applications`
+pub fn installed_meeting_apps(meeting_config: &MeetingConfig) -> Vec<InstalledMeetingApp> {
+    let app_dirs: Vec<PathBuf> = [Some(PathBuf::from("/Applications")), dirs::home_dir().map(|home| home.join("Applications"))]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut apps: Vec<InstalledMeetingApp> = KNOWN_MEETING_APPS
+        .iter()
+        .filter(|(name, _)| app_dirs.iter().any(|dir| dir.join(format!("{}.app", name)).exists()))
+        .map(|(name, process_name)| InstalledMeetingApp {
+            name: name.to_string(),
+            process_name: process_name.to_string(),
+            configured: meeting_config.process_names.iter().any(|configured| configured == process_name),
+        })
+        .collect();
+    // Lark and Feishu share a process
+    apps.dedup_by(|app, previous| app.process_name == previous.process_name);
+    apps
+}
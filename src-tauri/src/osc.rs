@@ -0,0 +1,119 @@
+//! OSC output
+//!
+//! Sends Open Sound Control messages over UDP when the meeting, music or monitoring
+//! state changes, so audio consoles, OBS plugins and lighting rigs can react to calls.
+//! Each value is sent once when it changes; there is no connection to keep up.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::UdpSocket;
+use std::sync::Mutex;
+
+// Argument last sent to each address, so unchanged values aren't sent again.
+// Also serializes sending between threads.
+static SENT: Mutex<BTreeMap<String, OscArg>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscConfig {
+    pub enabled: bool,
+    /// Host name or IP address the messages are sent to
+    pub host: String,
+    pub port: u16,
+    /// Start of every address, e.g. "/soundbreak" for `/soundbreak/meeting`
+    pub address_prefix: String,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 9000,
+            address_prefix: "/soundbreak".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum OscArg {
+    Int(i32),
+    Str(String),
+}
+
+/// Sends the messages whose value changed since they were last sent, on a background thread
+pub fn publish(config: &OscConfig, status: &MonitoringStatus) {
+    if !config.enabled || config.host.trim().is_empty() {
+        return;
+    }
+
+    let config = config.clone();
+    let messages = messages(config.address_prefix.trim_end_matches('/'), status);
+    std::thread::spawn(move || {
+        let mut sent = SENT.lock_or_recover();
+        let changed: Vec<(String, OscArg)> = messages
+            .into_iter()
+            .filter(|(address, arg)| sent.get(address) != Some(arg))
+            .collect();
+        if changed.is_empty() {
+            return;
+        }
+
+        match send(&config, &changed) {
+            Ok(()) => sent.extend(changed),
+            Err(e) => eprintln!("SoundBreak: Failed to send OSC to {}:{}: {}", config.host, config.port, e),
+        }
+    });
+}
+
+fn messages(prefix: &str, status: &MonitoringStatus) -> Vec<(String, OscArg)> {
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let music_playing = status.music_status.as_ref().is_some_and(|music| music.is_playing);
+    vec![
+        (format!("{}/meeting", prefix), OscArg::Int(in_meeting as i32)),
+        (format!("{}/meeting/app", prefix), OscArg::Str(status.meeting_app().unwrap_or_default())),
+        (format!("{}/music", prefix), OscArg::Int(music_playing as i32)),
+        (format!("{}/monitoring", prefix), OscArg::Str(status.monitoring_state().to_string())),
+    ]
+}
+
+fn send(config: &OscConfig, messages: &[(String, OscArg)]) -> Result<(), String> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).map_err(|e| format!("Failed to open a UDP socket: {}", e))?;
+    socket
+        .connect((config.host.trim(), config.port))
+        .map_err(|e| format!("Failed to resolve host: {}", e))?;
+    for (address, arg) in messages {
+        socket
+            .send(&message_packet(address, arg))
+            .map_err(|e| format!("Failed to send {}: {}", address, e))?;
+    }
+    Ok(())
+}
+
+// Address pattern, type tags, then the argument; strings are null-terminated and
+// everything is padded to a multiple of 4 bytes
+fn message_packet(address: &str, arg: &OscArg) -> Vec<u8> {
+    let mut packet = Vec::new();
+    push_string(&mut packet, address);
+    match arg {
+        OscArg::Int(value) => {
+            push_string(&mut packet, ",i");
+            packet.extend_from_slice(&value.to_be_bytes());
+        }
+        OscArg::Str(value) => {
+            push_string(&mut packet, ",s");
+            push_string(&mut packet, value);
+        }
+    }
+    packet
+}
+
+fn push_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(0);
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(0);
+    }
+}
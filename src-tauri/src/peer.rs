@@ -0,0 +1,248 @@
+//! LAN peer mode
+//!
+//! Lets one SoundBreak instance pause and resume playback on another instance,
+//! for setups where music plays from a second Mac connected to the speakers.
+//!
+//! The protocol is a few JSON lines per TCP connection. The receiving side sends a
+//! random challenge, and the request answers it with an HMAC-SHA256 of the challenge
+//! and the event, keyed with the shared token configured on both machines. The token
+//! itself never goes over the network, and a recorded request can't be replayed.
+//! The receiving side only resumes playback that it paused itself.
+
+use crate::local_api::{read_line_limited, ConnectionSlot};
+use crate::music_controller::{MusicAction, MusicController};
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const PEER_TIMEOUT: Duration = Duration::from_secs(3);
+// Longest line either side reads, and most peer connections served at once
+const MAX_LINE_BYTES: usize = 1024;
+const MAX_CONNECTIONS: usize = 8;
+const CHALLENGE_BYTES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerEvent {
+    MeetingStarted,
+    MeetingEnded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PeerChallenge {
+    challenge: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PeerRequest {
+    event: PeerEvent,
+    /// `sign(token, challenge, event)`
+    signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PeerResponse {
+    ok: bool,
+    message: String,
+}
+
+pub struct PeerServer;
+
+impl PeerServer {
+    /// Starts accepting peer requests on `address:port` on a background thread
+    pub fn start(address: &str, port: u16, token: String) -> Result<(), String> {
+        if token.is_empty() {
+            return Err("Peer token must not be empty".to_string());
+        }
+
+        let listener = TcpListener::bind((address, port))
+            .map_err(|e| format!("Failed to listen on {}:{}: {}", address, port, e))?;
+        let paused_by_peer = Arc::new(Mutex::new(false));
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let Some(slot) = ConnectionSlot::take(&connections, MAX_CONNECTIONS) else {
+                    continue;
+                };
+                let token = token.clone();
+                let paused_by_peer = paused_by_peer.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = Self::handle_connection(stream, &token, &paused_by_peer) {
+                        eprintln!("SoundBreak: Peer request failed: {}", e);
+                    }
+                    drop(slot);
+                });
+            }
+        });
+
+        println!("SoundBreak: Listening for peer requests on {}:{}", address, port);
+        Ok(())
+    }
+
+    fn handle_connection(stream: TcpStream, token: &str, paused_by_peer: &Mutex<bool>) -> Result<(), String> {
+        stream.set_read_timeout(Some(PEER_TIMEOUT)).map_err(|e| e.to_string())?;
+        stream.set_write_timeout(Some(PEER_TIMEOUT)).map_err(|e| e.to_string())?;
+        let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+
+        let challenge = random_hex(CHALLENGE_BYTES)?;
+        let challenge_json = serde_json::to_string(&PeerChallenge { challenge: challenge.clone() }).map_err(|e| e.to_string())?;
+        writeln!(writer, "{}", challenge_json).map_err(|e| format!("Failed to send challenge: {}", e))?;
+
+        let line = read_line_limited(&mut BufReader::new(stream), MAX_LINE_BYTES)?;
+        let response = match serde_json::from_str::<PeerRequest>(&line) {
+            Ok(request) if tokens_match(&request.signature, &sign(token, &challenge, request.event)) => {
+                Self::apply_event(request.event, paused_by_peer)
+            }
+            Ok(_) => PeerResponse {
+                ok: false,
+                message: "Invalid peer token".to_string(),
+            },
+            Err(e) => PeerResponse {
+                ok: false,
+                message: format!("Invalid request: {}", e),
+            },
+        };
+
+        let response_json = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+        writeln!(writer, "{}", response_json).map_err(|e| format!("Failed to write response: {}", e))
+    }
+
+    fn apply_event(event: PeerEvent, paused_by_peer: &Mutex<bool>) -> PeerResponse {
+        let controller = MusicController::new();
+        let mut paused_by_peer = paused_by_peer.lock_or_recover();
+
+        let result = match event {
+            PeerEvent::MeetingStarted => {
+                if controller.get_music_status().is_playing {
+                    let result = controller.execute_action(MusicAction::Pause);
+                    *paused_by_peer = result.is_ok();
+                    result
+                } else {
+                    Ok("Music is not playing".to_string())
+                }
+            }
+            PeerEvent::MeetingEnded => {
+                if std::mem::take(&mut *paused_by_peer) {
+                    controller.execute_action(MusicAction::Play)
+                } else {
+                    Ok("Music was not paused by peer".to_string())
+                }
+            }
+        };
+
+        match result {
+            Ok(message) => PeerResponse { ok: true, message },
+            Err(message) => PeerResponse { ok: false, message },
+        }
+    }
+}
+
+pub struct PeerClient;
+
+impl PeerClient {
+    /// Sends the event to every configured peer on a background thread
+    pub fn notify_peers(peers: Vec<String>, token: String, event: PeerEvent) {
+        if peers.is_empty() {
+            return;
+        }
+
+        std::thread::spawn(move || {
+            for peer in &peers {
+                match Self::send(peer, &token, event) {
+                    Ok(message) => println!("SoundBreak: Peer {} - {}", peer, message),
+                    Err(e) => eprintln!("SoundBreak: Failed to notify peer {}: {}", peer, e),
+                }
+            }
+        });
+    }
+
+    fn send(peer: &str, token: &str, event: PeerEvent) -> Result<String, String> {
+        let address = peer
+            .to_socket_addrs()
+            .map_err(|e| format!("Invalid peer address: {}", e))?
+            .next()
+            .ok_or("Peer address did not resolve")?;
+
+        let mut stream = TcpStream::connect_timeout(&address, PEER_TIMEOUT)
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+        stream.set_read_timeout(Some(PEER_TIMEOUT)).map_err(|e| e.to_string())?;
+        stream.set_write_timeout(Some(PEER_TIMEOUT)).map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+
+        let line = read_line_limited(&mut reader, MAX_LINE_BYTES)?;
+        let challenge: PeerChallenge = serde_json::from_str(&line).map_err(|e| format!("Invalid challenge: {}", e))?;
+
+        let request = PeerRequest {
+            event,
+            signature: sign(token, &challenge.challenge, event),
+        };
+        let request_json = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+        writeln!(stream, "{}", request_json).map_err(|e| format!("Failed to send request: {}", e))?;
+
+        let line = read_line_limited(&mut reader, MAX_LINE_BYTES)?;
+        let response: PeerResponse = serde_json::from_str(&line).map_err(|e| format!("Invalid response: {}", e))?;
+        if response.ok {
+            Ok(response.message)
+        } else {
+            Err(response.message)
+        }
+    }
+}
+
+/// Compares tokens without short-circuiting on the first mismatching byte
+pub fn tokens_match(provided: &str, expected: &str) -> bool {
+    if provided.len() != expected.len() || expected.is_empty() {
+        return false;
+    }
+
+    provided
+        .bytes()
+        .zip(expected.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Hex of `len` random bytes from the kernel
+pub fn random_hex(len: usize) -> Result<String, String> {
+    let mut bytes = vec![0u8; len];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read random bytes: {}", e))?;
+    Ok(hex(&bytes))
+}
+
+// HMAC-SHA256 of the challenge and the event, keyed with the token, as hex
+fn sign(token: &str, challenge: &str, event: PeerEvent) -> String {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut key = [0u8; BLOCK_SIZE];
+    if token.len() > BLOCK_SIZE {
+        key[..32].copy_from_slice(&Sha256::digest(token.as_bytes()));
+    } else {
+        key[..token.len()].copy_from_slice(token.as_bytes());
+    }
+    let event = match event {
+        PeerEvent::MeetingStarted => "meeting_started",
+        PeerEvent::MeetingEnded => "meeting_ended",
+    };
+
+    let inner = Sha256::new()
+        .chain_update(key.map(|byte| byte ^ 0x36))
+        .chain_update(format!("{}:{}", challenge, event))
+        .finalize();
+    let outer = Sha256::new()
+        .chain_update(key.map(|byte| byte ^ 0x5c))
+        .chain_update(inner)
+        .finalize();
+    hex(&outer)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,121 @@
+//! macOS privacy permissions
+//!
+//! When a permission is missing, pausing or resuming fails silently or "does nothing",
+//! so Settings shows what has been granted and links to the matching System Settings pane.
+
+use crate::music_controller::MusicController;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    /// Running AppleScript to read and control playback
+    Automation,
+    Accessibility,
+    /// Reading calendar events to estimate meeting length
+    Calendar,
+    Microphone,
+}
+
+impl PermissionKind {
+    pub const ALL: [PermissionKind; 4] = [
+        PermissionKind::Automation,
+        PermissionKind::Accessibility,
+        PermissionKind::Calendar,
+        PermissionKind::Microphone,
+    ];
+
+    /// System Settings pane where the permission is granted
+    pub fn settings_url(self) -> &'static str {
+        match self {
+            PermissionKind::Automation => "x-apple.systempreferences:com.apple.preference.security?Privacy_Automation",
+            PermissionKind::Accessibility => "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility",
+            PermissionKind::Calendar => "x-apple.systempreferences:com.apple.preference.security?Privacy_Calendars",
+            PermissionKind::Microphone => "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// macOS hasn't asked the user yet
+    NotDetermined,
+    /// The state couldn't be read
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionStatus {
+    pub kind: PermissionKind,
+    pub state: PermissionState,
+    /// Error behind a denied or unknown state, if any
+    pub detail: Option<String>,
+}
+
+pub fn check_all() -> Vec<PermissionStatus> {
+    PermissionKind::ALL.into_iter().map(check).collect()
+}
+
+pub fn check(kind: PermissionKind) -> PermissionStatus {
+    let (state, detail) = match kind {
+        // Reading the music status runs AppleScript, which macOS asks about the first time
+        PermissionKind::Automation => match MusicController::new().try_get_music_status() {
+            Ok(_) => (PermissionState::Granted, None),
+            Err(e) => (PermissionState::Denied, Some(e)),
+        },
+        PermissionKind::Accessibility => (accessibility_state(), None),
+        // EKAuthorizationStatus and AVAuthorizationStatus number their states alike;
+        // 4 is EventKit's write-only access, which can't read events either
+        PermissionKind::Calendar => authorization_state(
+            r#"use framework "EventKit"
+            return (current application's EKEventStore's authorizationStatusForEntityType:0) as integer"#,
+        ),
+        PermissionKind::Microphone => authorization_state(
+            r#"use framework "AVFoundation"
+            return (current application's AVCaptureDevice's authorizationStatusForMediaType:(current application's AVMediaTypeAudio)) as integer"#,
+        ),
+    };
+    PermissionStatus { kind, state, detail }
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility_state() -> PermissionState {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    if unsafe { AXIsProcessTrusted() } {
+        PermissionState::Granted
+    } else {
+        PermissionState::Denied
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility_state() -> PermissionState {
+    PermissionState::Unknown
+}
+
+// Runs an AppleScriptObjC snippet returning an authorization status number
+fn authorization_state(script: &str) -> (PermissionState, Option<String>) {
+    let output = match Command::new("osascript").arg("-e").arg(script).output() {
+        Ok(output) => output,
+        Err(e) => return (PermissionState::Unknown, Some(format!("Failed to run osascript: {}", e))),
+    };
+    if !output.status.success() {
+        return (PermissionState::Unknown, Some(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    let state = match String::from_utf8_lossy(&output.stdout).trim() {
+        "0" => PermissionState::NotDetermined,
+        "1" | "2" | "4" => PermissionState::Denied,
+        "3" => PermissionState::Granted,
+        _ => PermissionState::Unknown,
+    };
+    (state, None)
+}
@@ -0,0 +1,17 @@
+use std::process::Command;
+
+/// Returns whether the Mac is running on battery, or `None` if it can't be determined
+pub fn is_on_battery() -> Option<bool> {
+    // First line reads e.g. "Now drawing from 'Battery Power'"
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first_line = stdout.lines().next()?;
+
+    if first_line.contains("Battery Power") {
+        Some(true)
+    } else if first_line.contains("AC Power") {
+        Some(false)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,39 @@
+//! Signals that the user has stepped away from the Mac
+
+use std::process::Command;
+
+/// Returns whether the screen of the current login session is locked
+pub fn is_screen_locked() -> Result<bool, String> {
+    // The console session dictionary carries "CGSSessionScreenIsLocked"=Yes only while locked
+    let output = Command::new("ioreg")
+        .args(["-n", "Root", "-d1"])
+        .output()
+        .map_err(|e| format!("Failed to read screen lock state: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read screen lock state: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+/// Returns how long it has been since the last keyboard, mouse or trackpad input
+pub fn idle_secs() -> Result<u64, String> {
+    // IOHIDSystem reports e.g. `"HIDIdleTime" = 1234567890` in nanoseconds
+    let output = Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .map_err(|e| format!("Failed to read idle time: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find_map(|line| {
+            let (_, value) = line.split_once("\"HIDIdleTime\" = ")?;
+            value.trim().parse::<u64>().ok()
+        })
+        .map(|nanos| nanos / 1_000_000_000)
+        .ok_or_else(|| "Failed to read idle time: HIDIdleTime not found".to_string())
+}
@@ -0,0 +1,83 @@
+//! Behavior profiles
+//!
+//! A profile is a named bundle of settings (check interval, transition actions and
+//! grace periods) that can be switched at once from the tray instead of editing
+//! `config.json`. Applying a profile overwrites just those settings; everything
+//! else in the config is left alone.
+
+use crate::actions::{default_meeting_end_actions, default_meeting_start_actions};
+use crate::config::{AppConfig, FlapSuppressionConfig, DEFAULT_CHECK_INTERVAL_MS};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    /// The default settings
+    Balanced,
+    /// Reacts as fast as possible, at the cost of more checks
+    Aggressive,
+    /// Lowers the volume during meetings instead of pausing
+    DuckOnly,
+    /// Pauses automatically but leaves resuming to the user
+    ManualResume,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 4] = [
+        Profile::Balanced,
+        Profile::Aggressive,
+        Profile::DuckOnly,
+        Profile::ManualResume,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Profile::Balanced => "balanced",
+            Profile::Aggressive => "aggressive",
+            Profile::DuckOnly => "duck_only",
+            Profile::ManualResume => "manual_resume",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Profile::Balanced => "Balanced",
+            Profile::Aggressive => "Aggressive",
+            Profile::DuckOnly => "Duck Only",
+            Profile::ManualResume => "Manual Resume",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|profile| profile.id() == id)
+    }
+
+    pub fn apply(self, config: &mut AppConfig) {
+        config.check_interval_ms = DEFAULT_CHECK_INTERVAL_MS;
+        config.detection_confirmations = 1;
+        config.flap_suppression = FlapSuppressionConfig::default();
+        config.on_meeting_start = default_meeting_start_actions();
+        config.on_meeting_end = default_meeting_end_actions();
+        config.pause_on_meeting_start = true;
+        config.resume_on_meeting_end = true;
+        config.soft_pause.enabled = false;
+        config.soft_pause.always = false;
+
+        match self {
+            Profile::Balanced => {}
+            Profile::Aggressive => {
+                config.check_interval_ms = 1000;
+                config.flap_suppression.min_secs_before_end = 3;
+            }
+            Profile::DuckOnly => {
+                config.soft_pause.enabled = true;
+                config.soft_pause.always = true;
+            }
+            Profile::ManualResume => {
+                config.resume_on_meeting_end = false;
+            }
+        }
+
+        config.profile = Some(self);
+    }
+}
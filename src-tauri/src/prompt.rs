@@ -0,0 +1,45 @@
+use std::process::Command;
+use std::time::Duration;
+
+/// Asks the user a question in a dialog with the given buttons.
+///
+/// Notifications can't carry action buttons for a tray app, so questions are shown
+/// as a standard AppleScript dialog instead. The last button is the default.
+/// Blocks until the user answers; returns `None` when the dialog times out or
+/// can't be shown.
+pub fn ask(message: &str, buttons: &[&str], timeout: Duration) -> Option<String> {
+    // Arguments are passed through argv so the message never needs escaping
+    let script = r#"
+        on run argv
+            set theMessage to item 1 of argv
+            set timeoutSecs to (item 2 of argv) as integer
+            set theButtons to items 3 thru -1 of argv
+            set theResult to display dialog theMessage with title "SoundBreak" buttons theButtons default button (count of theButtons) giving up after timeoutSecs
+            if gave up of theResult then return ""
+            return button returned of theResult
+        end run
+    "#;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .arg(message)
+        .arg(timeout.as_secs().to_string())
+        .args(buttons)
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let answer = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Some(answer).filter(|answer| !answer.is_empty())
+        }
+        Ok(output) => {
+            eprintln!("SoundBreak: Failed to show dialog: {}", String::from_utf8_lossy(&output.stderr).trim());
+            None
+        }
+        Err(e) => {
+            eprintln!("SoundBreak: Failed to show dialog: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Work hours during which monitoring is allowed to run, e.g. Mon–Fri 09:00–18:00.
+///
+/// Times are local wall-clock times in `HH:MM` format. An end time earlier than the
+/// start time spans midnight and belongs to the day the window started on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    pub enabled: bool,
+    pub days: Vec<Weekday>,
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    /// Whether monitoring should be active at the given time; always true when disabled
+    pub fn is_within(&self, now: DateTime<Local>) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return true;
+        };
+
+        let time = now.time();
+        let today = now.weekday();
+        if start <= end {
+            self.days.contains(&today) && time >= start && time < end
+        } else {
+            // Overnight window: the evening part belongs to today, the morning part to yesterday
+            (self.days.contains(&today) && time >= start) || (self.days.contains(&today.pred()) && time < end)
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("Invalid schedule time '{}', expected HH:MM", value))
+}
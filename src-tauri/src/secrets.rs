@@ -0,0 +1,131 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Keychain service name the secrets are filed under
+const KEYCHAIN_SERVICE: &str = "com.kfstorm.sound-break";
+
+// Exit status of `security` when there is no such item (errSecItemNotFound)
+const ITEM_NOT_FOUND: i32 = 44;
+
+/// Keychain account holding `peer.token`
+pub const PEER_TOKEN: &str = "peer.token";
+
+/// Keychain account holding `home_assistant.token`
+pub const HOME_ASSISTANT_TOKEN: &str = "home_assistant.token";
+
+/// Keychain account holding `mqtt.password`
+pub const MQTT_PASSWORD: &str = "mqtt.password";
+
+/// Keychain account holding `ifttt.key`
+pub const IFTTT_KEY: &str = "ifttt.key";
+
+/// Keychain account holding `teams.refresh_token`
+pub const TEAMS_REFRESH_TOKEN: &str = "teams.refresh_token";
+
+/// Keychain account holding `google_chat.refresh_token`
+pub const GOOGLE_CHAT_REFRESH_TOKEN: &str = "google_chat.refresh_token";
+
+/// Keychain account holding `companion.token`
+pub const COMPANION_TOKEN: &str = "companion.token";
+
+/// Where tokens and API keys are kept instead of `config.json`
+pub trait SecretsStore: Send + Sync {
+    /// Returns `None` when the secret isn't stored, and an error when it can't be read
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Returns the store the app keeps its secrets in
+pub fn default_secrets_store() -> KeychainSecretsStore {
+    KeychainSecretsStore
+}
+
+/// Keeps secrets as generic passwords in the login keychain, using the `security` tool
+pub struct KeychainSecretsStore;
+
+impl SecretsStore for KeychainSecretsStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        let output = Command::new("security")
+            .args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", key, "-w"])
+            .output()
+            .map_err(|e| format!("Failed to run security: {}", e))?;
+        if output.status.code() == Some(ITEM_NOT_FOUND) {
+            return Ok(None);
+        }
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to read {} from the keychain: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let value = String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string();
+        Ok(Some(value).filter(|value| !value.is_empty()))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        if self.get(key)?.as_deref() == Some(value) {
+            return Ok(());
+        }
+        if value.chars().any(char::is_control) {
+            return Err(format!("{} can't contain control characters", key));
+        }
+        // The command is read from stdin in interactive mode, so the secret never shows up
+        // in the process list. -U updates the item if it already exists.
+        let command = format!(
+            "add-generic-password -U -s {} -a {} -w {}\n",
+            quote(KEYCHAIN_SERVICE),
+            quote(key),
+            quote(value)
+        );
+        let mut child = Command::new("security")
+            .arg("-i")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run security: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(command.as_bytes())
+                .map_err(|e| format!("Failed to pass {} to security: {}", key, e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run security: {}", e))?;
+        // Interactive mode exits successfully even when a command fails, so errors only
+        // show up on stderr
+        if !output.status.success() || !output.stderr.is_empty() {
+            return Err(format!(
+                "Failed to store {} in the keychain: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        if self.get(key)?.is_none() {
+            return Ok(());
+        }
+        let output = Command::new("security")
+            .args(["delete-generic-password", "-s", KEYCHAIN_SERVICE, "-a", key])
+            .output()
+            .map_err(|e| format!("Failed to run security: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to remove {} from the keychain: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Quotes an argument for a command line read by `security -i`
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,95 @@
+use crate::notifications;
+use crate::AppState;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Sets up the global shortcut plugin with the configured shortcuts
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let plugin = tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let (toggle_monitoring_shortcut, manual_meeting_shortcut) = configured_shortcuts(app);
+            if toggle_monitoring_shortcut.is_some_and(|(_, configured)| configured == *shortcut) {
+                toggle_monitoring(app);
+            } else if manual_meeting_shortcut.is_some_and(|(_, configured)| configured == *shortcut) {
+                toggle_manual_meeting(app);
+            }
+        })
+        .build();
+    app.plugin(plugin).map_err(|e| format!("Failed to set up global shortcut: {}", e))?;
+    register(app)
+}
+
+/// Registers the configured shortcuts in place of the previous ones. Called again
+/// whenever settings change.
+pub fn register(app: &AppHandle) -> Result<(), String> {
+    let (toggle_monitoring_shortcut, manual_meeting_shortcut) = configured_shortcuts(app);
+    let shortcuts: Vec<(String, Shortcut)> = toggle_monitoring_shortcut.into_iter().chain(manual_meeting_shortcut).collect();
+
+    let global_shortcut = app.global_shortcut();
+    if !shortcuts.is_empty() && shortcuts.iter().all(|(_, shortcut)| global_shortcut.is_registered(*shortcut)) {
+        return Ok(());
+    }
+    global_shortcut
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    for (text, shortcut) in shortcuts {
+        global_shortcut
+            .register(shortcut)
+            .map_err(|e| format!("Failed to register shortcut {:?}: {}", text, e))?;
+        println!("SoundBreak: Registered shortcut {}", text);
+    }
+    Ok(())
+}
+
+// A shortcut from the config, as written there and parsed
+type ConfiguredShortcut = Option<(String, Shortcut)>;
+
+// The toggle monitoring and manual meeting shortcuts, where set and valid
+fn configured_shortcuts(app: &AppHandle) -> (ConfiguredShortcut, ConfiguredShortcut) {
+    let app_config = app.state::<AppState>().monitoring_service.get_app_config();
+    (
+        parse_shortcut(&app_config.toggle_monitoring_shortcut),
+        parse_shortcut(&app_config.manual_meeting_shortcut),
+    )
+}
+
+fn parse_shortcut(text: &str) -> ConfiguredShortcut {
+    if text.trim().is_empty() {
+        return None;
+    }
+    match text.parse::<Shortcut>() {
+        Ok(shortcut) => Some((text.to_string(), shortcut)),
+        Err(e) => {
+            eprintln!("SoundBreak: Invalid shortcut {:?}: {}", text, e);
+            None
+        }
+    }
+}
+
+fn toggle_monitoring(app: &AppHandle) {
+    let service = app.state::<AppState>().monitoring_service.clone();
+    match service.toggle_monitoring() {
+        Ok(msg) => println!("SoundBreak: {}", msg),
+        Err(e) => eprintln!("SoundBreak: {}", e),
+    }
+
+    let status = service.get_status();
+    let _ = crate::update_tray_menu_text(app, &status);
+    notifications::notify_monitoring_toggled(app, &service.get_app_config().notifications, status.is_active);
+}
+
+// Pausing or resuming shells out to osascript, so run it off the shortcut handler's thread
+fn toggle_manual_meeting(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let service = app.state::<AppState>().monitoring_service.clone();
+        match service.set_manual_meeting(!service.is_manual_meeting()) {
+            Ok(msg) => println!("SoundBreak: {}", msg),
+            Err(e) => eprintln!("SoundBreak: {}", e),
+        }
+        let _ = crate::update_tray_menu_text(&app, &service.get_status());
+    });
+}
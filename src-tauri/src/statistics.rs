@@ -0,0 +1,143 @@
+use crate::config::ConfigManager;
+use crate::sync::MutexExt;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// Days of per-day detail returned alongside the aggregates
+const DAILY_DETAIL_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeriodStats {
+    pub meetings: u32,
+    pub meeting_secs: u64,
+    /// Time music spent paused or ducked by SoundBreak
+    pub paused_secs: u64,
+}
+
+impl PeriodStats {
+    fn add(&mut self, other: &PeriodStats) {
+        self.meetings += other.meetings;
+        self.meeting_secs += other.meeting_secs;
+        self.paused_secs += other.paused_secs;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub stats: PeriodStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStatistics {
+    pub today: PeriodStats,
+    pub this_week: PeriodStats,
+    pub all_time: PeriodStats,
+    /// Most recent days first
+    pub daily: Vec<DailyStats>,
+}
+
+/// Aggregate usage statistics, persisted per local day as `statistics.json`.
+///
+/// Meetings are attributed to the day they started on.
+#[derive(Clone)]
+pub struct StatisticsStore {
+    days: Arc<Mutex<BTreeMap<NaiveDate, PeriodStats>>>,
+    // Never written to disk
+    in_memory: bool,
+}
+
+impl StatisticsStore {
+    pub fn load() -> Self {
+        let days = Self::get_statistics_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<BTreeMap<NaiveDate, PeriodStats>>(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            days: Arc::new(Mutex::new(days)),
+            in_memory: false,
+        }
+    }
+
+    /// Empty statistics kept for the lifetime of the process only
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            days: Arc::default(),
+            in_memory: true,
+        }
+    }
+
+    pub fn record_meeting(&self, started_at: u64, ended_at: u64, paused_secs: u64) {
+        let date = local_date(started_at);
+        let snapshot = {
+            let mut days = self.days.lock_or_recover();
+            let day = days.entry(date).or_default();
+            day.meetings += 1;
+            day.meeting_secs += ended_at.saturating_sub(started_at);
+            day.paused_secs += paused_secs;
+            days.clone()
+        };
+
+        if self.in_memory {
+            return;
+        }
+        if let Err(e) = Self::save(&snapshot) {
+            eprintln!("SoundBreak: Failed to save statistics: {}", e);
+        }
+    }
+
+    pub fn get_statistics(&self) -> UsageStatistics {
+        let days = self.days.lock_or_recover();
+        let today = Local::now().date_naive();
+        let week_start = today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+        let detail_start = today - ChronoDuration::days(DAILY_DETAIL_DAYS - 1);
+
+        let mut statistics = UsageStatistics {
+            today: days.get(&today).cloned().unwrap_or_default(),
+            this_week: PeriodStats::default(),
+            all_time: PeriodStats::default(),
+            daily: Vec::new(),
+        };
+
+        for (date, stats) in days.iter() {
+            statistics.all_time.add(stats);
+            if *date >= week_start {
+                statistics.this_week.add(stats);
+            }
+            if *date >= detail_start {
+                statistics.daily.push(DailyStats {
+                    date: *date,
+                    stats: stats.clone(),
+                });
+            }
+        }
+        statistics.daily.reverse();
+
+        statistics
+    }
+
+    fn get_statistics_path() -> Result<PathBuf, String> {
+        Ok(ConfigManager::get_app_dir()?.join("statistics.json"))
+    }
+
+    fn save(days: &BTreeMap<NaiveDate, PeriodStats>) -> Result<(), String> {
+        let statistics_path = Self::get_statistics_path()?;
+        let json_content = serde_json::to_string_pretty(days)
+            .map_err(|e| format!("Failed to serialize statistics: {}", e))?;
+        std::fs::write(&statistics_path, json_content)
+            .map_err(|e| format!("Failed to write statistics: {}", e))
+    }
+}
+
+fn local_date(timestamp: u64) -> NaiveDate {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.with_timezone(&Local).date_naive())
+        .unwrap_or_else(|| Local::now().date_naive())
+}
@@ -0,0 +1,24 @@
+//! `status.json` for scripts
+//!
+//! AppleScript and Shortcuts can't call into the app, so they run actions through
+//! `soundbreak://` URLs and read the current status from this file, which is rewritten
+//! whenever something shown in the tray changes.
+
+use crate::config::ConfigManager;
+use crate::monitoring_service::MonitoringStatus;
+use std::path::PathBuf;
+
+const STATUS_FILE_NAME: &str = "status.json";
+
+pub fn path() -> Result<PathBuf, String> {
+    Ok(ConfigManager::get_app_dir()?.join(STATUS_FILE_NAME))
+}
+
+/// Replaces the status file in one step, so readers never see half of it
+pub fn write(status: &MonitoringStatus) -> Result<(), String> {
+    let path = path()?;
+    let content = serde_json::to_string_pretty(status).map_err(|e| format!("Failed to serialize status: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content).map_err(|e| format!("Failed to write status file: {}", e))?;
+    std::fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write status file: {}", e))
+}
@@ -0,0 +1,16 @@
+use std::sync::{Mutex, MutexGuard};
+
+/// Locking that survives a panic in another thread.
+///
+/// Every lock in the app guards plain data that stays consistent between
+/// statements, so after a panic the data is still usable. Recovering the guard
+/// keeps one failed check from taking down the tray and every command with it.
+pub trait MutexExt<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,195 @@
+//! Microsoft Teams presence
+//!
+//! Sets the user's Teams presence to Busy or Do Not Disturb through Microsoft Graph
+//! while a meeting in another app (Zoom, Webex, ...) is active, and clears it afterwards,
+//! so colleagues see accurate availability. Teams meetings are left alone since Teams
+//! sets the presence itself. Graph is reached with `curl` on a background thread.
+
+use crate::monitoring_service::MonitoringStatus;
+use crate::secrets::{default_secrets_store, SecretsStore, TEAMS_REFRESH_TOKEN};
+use crate::sync::MutexExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const GRAPH_PRESENCE_URL: &str = "https://graph.microsoft.com/v1.0/me/presence";
+const SCOPE: &str = "Presence.ReadWrite offline_access";
+// Teams drops the presence by itself after this long, in case it's never cleared
+const PRESENCE_EXPIRATION: &str = "PT8H";
+// Access tokens are renewed this long before they expire
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+static STATE: Mutex<TeamsState> = Mutex::new(TeamsState {
+    access_token: None,
+    presence_set: false,
+});
+
+// Also serializes updates between threads
+struct TeamsState {
+    access_token: Option<(String, Instant)>,
+    presence_set: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TeamsAvailability {
+    Busy,
+    DoNotDisturb,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamsConfig {
+    pub enabled: bool,
+    /// Application (client) id of the app registration used to sign in, see the README
+    pub client_id: String,
+    /// Directory (tenant) id, or "common"
+    pub tenant: String,
+    /// Refresh token from signing in. Kept in the keychain; only read from `config.json`
+    /// when set there by hand.
+    pub refresh_token: String,
+    pub availability: TeamsAvailability,
+}
+
+impl Default for TeamsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_id: String::new(),
+            tenant: "common".to_string(),
+            refresh_token: String::new(),
+            availability: TeamsAvailability::DoNotDisturb,
+        }
+    }
+}
+
+/// Sets or clears the presence when the meeting state changed, on a background thread.
+/// `on_rotated` gets the new refresh token whenever Microsoft replaces it.
+pub fn publish(config: &TeamsConfig, status: &MonitoringStatus, on_rotated: impl FnOnce(String) + Send + 'static) {
+    if !config.enabled || config.client_id.trim().is_empty() || config.refresh_token.is_empty() {
+        return;
+    }
+
+    let in_meeting = status.meeting_status.as_ref().is_some_and(|meeting| meeting.in_meeting);
+    let in_teams = status.meeting_app().is_some_and(|app| app.contains("Teams"));
+    let wanted = in_meeting && !in_teams;
+    let config = config.clone();
+    std::thread::spawn(move || {
+        let mut state = STATE.lock_or_recover();
+        if state.presence_set == wanted {
+            return;
+        }
+
+        let result = access_token(&config, &mut state, on_rotated).and_then(|token| {
+            if wanted {
+                let availability = match config.availability {
+                    TeamsAvailability::Busy => "Busy",
+                    TeamsAvailability::DoNotDisturb => "DoNotDisturb",
+                };
+                let body = json!({
+                    "availability": availability,
+                    "activity": availability,
+                    "expirationDuration": PRESENCE_EXPIRATION,
+                });
+                graph_post(&token, "setUserPreferredPresence", &body)
+            } else {
+                graph_post(&token, "clearUserPreferredPresence", &json!({}))
+            }
+        });
+        match result {
+            Ok(()) => state.presence_set = wanted,
+            Err(e) => eprintln!("SoundBreak: Failed to update Teams presence: {}", e),
+        }
+    });
+}
+
+/// Clears the presence if SoundBreak set it, e.g. before quitting
+pub fn clear(config: &TeamsConfig, on_rotated: impl FnOnce(String)) {
+    let mut state = STATE.lock_or_recover();
+    if !state.presence_set {
+        return;
+    }
+    let result = access_token(config, &mut state, on_rotated)
+        .and_then(|token| graph_post(&token, "clearUserPreferredPresence", &json!({})));
+    match result {
+        Ok(()) => state.presence_set = false,
+        Err(e) => eprintln!("SoundBreak: Failed to clear Teams presence: {}", e),
+    }
+}
+
+// A valid access token, redeeming the refresh token for a new one when needed. Microsoft
+// may answer with a new refresh token too, replacing the old one; it is saved to the
+// keychain and handed to `on_rotated` right away, so the next refresh and the next launch
+// don't use a token that no longer works.
+fn access_token(config: &TeamsConfig, state: &mut TeamsState, on_rotated: impl FnOnce(String)) -> Result<String, String> {
+    if let Some((token, expires_at)) = &state.access_token {
+        if Instant::now() + TOKEN_EXPIRY_MARGIN < *expires_at {
+            return Ok(token.clone());
+        }
+    }
+
+    let url = format!("https://login.microsoftonline.com/{}/oauth2/v2.0/token", config.tenant.trim());
+    // The refresh token is passed on stdin so it doesn't show up in the process list
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--data-urlencode", "grant_type=refresh_token"])
+        .args(["--data-urlencode", &format!("client_id={}", config.client_id.trim())])
+        .args(["--data-urlencode", &format!("scope={}", SCOPE)])
+        .args(["--data-urlencode", "refresh_token@-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.refresh_token.trim().as_bytes())
+            .map_err(|e| format!("Failed to pass the refresh token to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let response: Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected answer from Microsoft: {}", e))?;
+    let Some(token) = response["access_token"].as_str() else {
+        let error = response["error_description"].as_str().unwrap_or("no access token in the answer");
+        return Err(format!("Failed to sign in to Microsoft: {}", error));
+    };
+    if let Some(refresh_token) = response["refresh_token"].as_str().filter(|token| *token != config.refresh_token.trim()) {
+        if let Err(e) = default_secrets_store().set(TEAMS_REFRESH_TOKEN, refresh_token) {
+            eprintln!("SoundBreak: Failed to save the new Teams refresh token: {}", e);
+        }
+        on_rotated(refresh_token.to_string());
+    }
+    let expires_in = Duration::from_secs(response["expires_in"].as_u64().unwrap_or(0));
+    state.access_token = Some((token.to_string(), Instant::now() + expires_in));
+    Ok(token.to_string())
+}
+
+// The access token is passed on stdin so it doesn't show up in the process list
+fn graph_post(token: &str, action: &str, body: &Value) -> Result<(), String> {
+    let url = format!("{}/{}", GRAPH_PRESENCE_URL, action);
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--header", "@-", "--header", "Content-Type: application/json"])
+        .args(["--data", &body.to_string(), "--output", "/dev/null", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Authorization: Bearer {}", token).map_err(|e| format!("Failed to pass the token to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,395 @@
+//! Outgoing webhooks
+//!
+//! POSTs a JSON payload to configured URLs when a meeting starts or ends and when
+//! SoundBreak pauses or resumes music. Payloads, URLs and headers can be templated with
+//! `{{name}}` placeholders, and failed deliveries are retried a few times before giving up.
+//! IFTTT and Zapier are set up with just a key or a hook URL and become webhooks too.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const IFTTT_TRIGGER_URL: &str = "https://maker.ifttt.com/trigger";
+// IFTTT applets can use these three values as ingredients
+const IFTTT_PAYLOAD: &str = r#"{"value1": {{app}}, "value2": {{title}}, "value3": {{artist}}}"#;
+// Wait before the first retry, doubled for each one after it
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    MeetingStart,
+    MeetingEnd,
+    MusicPaused,
+    MusicResumed,
+}
+
+impl WebhookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::MeetingStart => "meeting_start",
+            WebhookEvent::MeetingEnd => "meeting_end",
+            WebhookEvent::MusicPaused => "music_paused",
+            WebhookEvent::MusicResumed => "music_resumed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Webhook {
+    /// May hold placeholders like the payload, filled in URL-encoded
+    pub url: String,
+    /// Shown in logs and the action history instead of the URL, which may hold a secret
+    pub name: String,
+    /// Events that fire this webhook; all of them when empty
+    pub events: Vec<WebhookEvent>,
+    /// Extra request headers, e.g. `Authorization`. Values may hold placeholders like
+    /// the payload, filled in as plain text.
+    pub headers: BTreeMap<String, String>,
+    /// JSON body with `{{event}}`, `{{app}}`, `{{timestamp}}`, `{{duration}}`, `{{title}}`,
+    /// `{{artist}}` and `{{track}}` placeholders. Empty sends an object with all of them.
+    pub payload: String,
+    /// Further attempts after a failed delivery
+    pub retries: u32,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            name: String::new(),
+            events: Vec::new(),
+            headers: BTreeMap::new(),
+            payload: String::new(),
+            retries: 2,
+        }
+    }
+}
+
+impl Webhook {
+    pub fn fires_on(&self, event: WebhookEvent) -> bool {
+        !self.url.trim().is_empty() && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    pub fn display_name(&self) -> &str {
+        if self.name.trim().is_empty() {
+            self.url.trim()
+        } else {
+            self.name.trim()
+        }
+    }
+}
+
+/// IFTTT's Webhooks service. Each transition triggers the event named
+/// `{event_prefix}{event}`, e.g. `soundbreak_meeting_start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IftttConfig {
+    /// Key from the Webhooks service's settings page. Kept in the keychain; only read
+    /// from `config.json` when set there by hand.
+    pub key: String,
+    pub event_prefix: String,
+    /// Events that trigger IFTTT; all of them when empty
+    pub events: Vec<WebhookEvent>,
+}
+
+impl Default for IftttConfig {
+    fn default() -> Self {
+        Self {
+            key: String::new(),
+            event_prefix: "soundbreak_".to_string(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl IftttConfig {
+    /// The webhook that triggers `event` on IFTTT, if it should
+    pub fn webhook(&self, event: WebhookEvent) -> Option<Webhook> {
+        let key = self.key.trim();
+        if key.is_empty() || !(self.events.is_empty() || self.events.contains(&event)) {
+            return None;
+        }
+        let event_name = format!("{}{}", self.event_prefix, event.name());
+        Some(Webhook {
+            url: format!("{}/{}/with/key/{}", IFTTT_TRIGGER_URL, event_name, key),
+            name: format!("IFTTT {}", event_name),
+            payload: IFTTT_PAYLOAD.to_string(),
+            ..Webhook::default()
+        })
+    }
+}
+
+/// A Zapier "Catch Hook" trigger. The default payload carries the event name, so one
+/// Zap can branch on it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZapierConfig {
+    pub hook_url: String,
+    /// Events sent to Zapier; all of them when empty
+    pub events: Vec<WebhookEvent>,
+}
+
+impl ZapierConfig {
+    /// The webhook that sends `event` to Zapier, if it should
+    pub fn webhook(&self, event: WebhookEvent) -> Option<Webhook> {
+        let webhook = Webhook {
+            url: self.hook_url.clone(),
+            name: "Zapier".to_string(),
+            events: self.events.clone(),
+            ..Webhook::default()
+        };
+        Some(webhook).filter(|webhook| webhook.fires_on(event))
+    }
+}
+
+/// What a payload can refer to
+#[derive(Debug, Clone)]
+pub struct WebhookContext {
+    pub event: WebhookEvent,
+    pub app: Option<String>,
+    pub timestamp: u64,
+    /// Seconds since the current meeting started, if in one
+    pub duration_secs: Option<u64>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+}
+
+impl WebhookContext {
+    fn values(&self) -> Value {
+        json!({
+            "event": self.event.name(),
+            "app": self.app,
+            "timestamp": self.timestamp,
+            "duration": self.duration_secs,
+            "title": self.title,
+            "artist": self.artist,
+            "track": self.track(),
+        })
+    }
+
+    // "Title — Artist", or just the title
+    fn track(&self) -> Option<String> {
+        match (&self.title, &self.artist) {
+            (Some(title), Some(artist)) => Some(format!("{} — {}", title, artist)),
+            (Some(title), None) => Some(title.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The request body for `context`: the template with each placeholder replaced by its
+/// value as JSON, so `"app": {{app}}` becomes `"app": "zoom.us"` or `"app": null`
+pub fn render_payload(template: &str, context: &WebhookContext) -> String {
+    if template.trim().is_empty() {
+        return context.values().to_string();
+    }
+    render(template, context, |value| value.to_string())
+}
+
+/// `template` with each placeholder replaced by its value as plain text, e.g.
+/// `Meeting in {{app}}` becomes `Meeting in zoom.us`; missing values are left empty
+pub fn render_text(template: &str, context: &WebhookContext) -> String {
+    render(template, context, plain_text)
+}
+
+// Like `render_text`, with values percent-encoded to fit in a query string or path
+fn render_url(template: &str, context: &WebhookContext) -> String {
+    render(template, context, |value| {
+        plain_text(value)
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    })
+}
+
+// Replaces placeholders in one pass over `template`, so values that look like
+// placeholders themselves are left as they are. Unknown placeholders are kept.
+fn render(template: &str, context: &WebhookContext, format: impl Fn(&Value) -> String) -> String {
+    let values = context.values();
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let placeholder = after
+            .find("}}")
+            .and_then(|end| values.get(&after[..end]).map(|value| (value, end)));
+        match placeholder {
+            Some((value, end)) => {
+                rendered.push_str(&format(value));
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+// Header lines and URLs can't hold line breaks or other control characters; a value
+// carrying them could otherwise add headers of its own
+fn without_control_chars(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Delivers the webhook, retrying failed attempts. Blocks until it succeeds or the
+/// last retry fails.
+pub fn deliver(webhook: &Webhook, context: &WebhookContext) -> Result<String, String> {
+    let payload = render_payload(&webhook.payload, context);
+    let url = without_control_chars(&render_url(webhook.url.trim(), context));
+    let headers: Vec<(String, String)> = webhook
+        .headers
+        .iter()
+        .map(|(name, value)| (without_control_chars(name), without_control_chars(&render_text(value, context))))
+        .collect();
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match post(&url, &headers, &payload) {
+            Ok(()) => return Ok(format!("Webhook {} delivered ({})", webhook.display_name(), context.event.name())),
+            Err(e) if attempt > webhook.retries => {
+                return Err(format!(
+                    "Webhook {} failed after {} attempt(s): {}",
+                    webhook.display_name(),
+                    attempt,
+                    e
+                ));
+            }
+            Err(e) => {
+                eprintln!("SoundBreak: Webhook {} failed, retrying: {}", webhook.display_name(), e);
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+    }
+}
+
+// POSTs with `curl`. The URL, headers and body go through stdin as a curl config, since
+// the URL (IFTTT's key) and headers often carry secrets that shouldn't be on a command line.
+fn post(url: &str, headers: &[(String, String)], payload: &str) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["--output", "/dev/null", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut config = format!("url = {}\nheader = \"Content-Type: application/json\"\n", curl_quote(url));
+        for (name, value) in headers {
+            config.push_str(&format!("header = {}\n", curl_quote(&format!("{}: {}", name, value))));
+        }
+        config.push_str(&format!("data = {}\n", curl_quote(payload)));
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| format!("Failed to pass the request to curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+// A double-quoted curl config value
+fn curl_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> WebhookContext {
+        WebhookContext {
+            event: WebhookEvent::MeetingStart,
+            app: Some("zoom.us".to_string()),
+            timestamp: 1_700_000_000,
+            duration_secs: None,
+            title: Some("Song {{app}}".to_string()),
+            artist: Some("Band\r\nX-Injected: 1".to_string()),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders_as_json_in_payloads() {
+        let payload = render_payload(r#"{"event": {{event}}, "app": {{app}}, "duration": {{duration}}}"#, &context());
+        assert_eq!(payload, r#"{"event": "meeting_start", "app": "zoom.us", "duration": null}"#);
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_inside_values() {
+        assert_eq!(render_text("{{title}} in {{app}}", &context()), "Song {{app}} in zoom.us");
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_placeholders() {
+        assert_eq!(render_text("{{unknown}} {{app}} {{app", &context()), "{{unknown}} zoom.us {{app");
+    }
+
+    #[test]
+    fn percent_encodes_url_values() {
+        let url = render_url("https://example.com/hook?track={{track}}", &context());
+        assert!(url.starts_with("https://example.com/hook?track=Song%20%7B%7Bapp%7D%7D%20%E2%80%94%20Band%0D%0A"));
+    }
+
+    #[test]
+    fn strips_control_characters_from_header_values() {
+        let header = without_control_chars(&render_text("Bearer {{artist}}", &context()));
+        assert_eq!(header, "Bearer BandX-Injected: 1");
+    }
+
+    #[test]
+    fn quotes_curl_config_values() {
+        assert_eq!(curl_quote("a \"b\" \\c\nd"), r#""a \"b\" \\c\nd""#);
+    }
+
+    #[test]
+    fn ifttt_webhook_only_fires_with_a_key_and_for_chosen_events() {
+        let mut ifttt = IftttConfig::default();
+        assert!(ifttt.webhook(WebhookEvent::MeetingStart).is_none());
+
+        ifttt.key = "secret".to_string();
+        ifttt.events = vec![WebhookEvent::MeetingEnd];
+        assert!(ifttt.webhook(WebhookEvent::MeetingStart).is_none());
+        let webhook = ifttt.webhook(WebhookEvent::MeetingEnd).unwrap();
+        assert_eq!(webhook.url, "https://maker.ifttt.com/trigger/soundbreak_meeting_end/with/key/secret");
+        assert_eq!(webhook.display_name(), "IFTTT soundbreak_meeting_end");
+    }
+}